@@ -0,0 +1,150 @@
+// HNSW vs brute force: recall@k and query latency over mock embeddings
+//
+// Points are jittered copies of a few hundred topic vectors, so the data has
+// the clumpy structure of real phrase embeddings (uniform random 768-d
+// vectors are all roughly equidistant and make any ANN index look bad).
+//
+//   cargo bench --bench hnsw
+//   SEFI_BENCH_N=20000 cargo bench --bench hnsw
+
+use sefi::embed::{normalize_vector, EmbedService};
+use sefi::ledger::hnsw::{cosine_distance, Hnsw, HnswConfig};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+const DIM: usize = 768;
+const K: usize = 10;
+const QUERIES: usize = 200;
+const TOPICS: usize = 200;
+const JITTER: f32 = 0.35;
+const RADIUS: f32 = 0.25; // ClusterConfig::default().epsilon
+
+fn main() {
+    let n: usize = std::env::var("SEFI_BENCH_N")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5_000);
+
+    let embed = EmbedService::with_dim(DIM);
+    let point = |topic: usize, tag: &str| {
+        let base = embed.embed(&format!("topic {}", topic));
+        let noise = embed.embed(tag);
+        let mut v: Vec<f32> = base
+            .iter()
+            .zip(&noise)
+            .map(|(b, n)| b + JITTER * n)
+            .collect();
+        normalize_vector(&mut v);
+        v
+    };
+    let data: Vec<Vec<f32>> = (0..n)
+        .map(|i| point(i % TOPICS, &format!("phrase {}", i)))
+        .collect();
+    let queries: Vec<Vec<f32>> = (0..QUERIES)
+        .map(|i| point(i * 7 % TOPICS, &format!("query {}", i)))
+        .collect();
+
+    println!("n={} dim={} queries={} k={}", n, DIM, QUERIES, K);
+
+    let start = Instant::now();
+    let mut index = Hnsw::new(HnswConfig::default());
+    for (i, v) in data.iter().enumerate() {
+        index.insert(i, v.clone());
+    }
+    let build = start.elapsed();
+    println!(
+        "build: {:?} ({:.1} µs/insert)",
+        build,
+        micros(build) / n as f64
+    );
+
+    // k-NN
+    let (exact, brute_time) = timed(|| {
+        queries
+            .iter()
+            .map(|q| brute_knn(&data, q, K))
+            .collect::<Vec<_>>()
+    });
+    let (approx, ann_time) = timed(|| {
+        queries
+            .iter()
+            .map(|q| index.knn(q, K).into_iter().map(|(id, _)| id).collect())
+            .collect::<Vec<Vec<usize>>>()
+    });
+    let hits: usize = exact
+        .iter()
+        .zip(&approx)
+        .map(|(e, a)| {
+            let e: HashSet<&usize> = e.iter().collect();
+            a.iter().filter(|id| e.contains(id)).count()
+        })
+        .sum();
+    println!(
+        "knn:    recall@{} {:.3}  brute {:.1} µs/q  hnsw {:.1} µs/q  ({:.1}x)",
+        K,
+        hits as f64 / (QUERIES * K) as f64,
+        micros(brute_time) / QUERIES as f64,
+        micros(ann_time) / QUERIES as f64,
+        micros(brute_time) / micros(ann_time)
+    );
+
+    // Radius (what DBSCAN neighbourhoods use)
+    let (exact, brute_time) = timed(|| {
+        queries
+            .iter()
+            .map(|q| brute_within(&data, q, RADIUS))
+            .collect::<Vec<_>>()
+    });
+    let (approx, ann_time) = timed(|| {
+        queries
+            .iter()
+            .map(|q| {
+                index
+                    .within(q, RADIUS)
+                    .into_iter()
+                    .map(|(id, _)| id)
+                    .collect()
+            })
+            .collect::<Vec<HashSet<usize>>>()
+    });
+    let total: usize = exact.iter().map(|e| e.len()).sum();
+    let found: usize = exact
+        .iter()
+        .zip(&approx)
+        .map(|(e, a)| e.intersection(a).count())
+        .sum();
+    println!(
+        "radius: recall {:.3} ({} true hits)  brute {:.1} µs/q  hnsw {:.1} µs/q  ({:.1}x)",
+        found as f64 / total.max(1) as f64,
+        total,
+        micros(brute_time) / QUERIES as f64,
+        micros(ann_time) / QUERIES as f64,
+        micros(brute_time) / micros(ann_time)
+    );
+}
+
+fn brute_knn(data: &[Vec<f32>], query: &[f32], k: usize) -> Vec<usize> {
+    let mut scored: Vec<(f32, usize)> = data
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (cosine_distance(query, v), i))
+        .collect();
+    scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+    scored.into_iter().take(k).map(|(_, i)| i).collect()
+}
+
+fn brute_within(data: &[Vec<f32>], query: &[f32], radius: f32) -> HashSet<usize> {
+    (0..data.len())
+        .filter(|&i| cosine_distance(query, &data[i]) <= radius)
+        .collect()
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let out = f();
+    (out, start.elapsed())
+}
+
+fn micros(d: Duration) -> f64 {
+    d.as_secs_f64() * 1e6
+}
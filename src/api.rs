@@ -0,0 +1,686 @@
+// HTTP front door for agents that cannot link the crate
+//
+// Served by the daemon next to its Unix socket, over the same shared
+// Engine. Plain JSON request/response, one request per connection:
+//
+//   POST /packets             one ConceptPacket or an array of them; with
+//                             Content-Type application/msgpack, wire frames
+//   GET  /basins              live clusters, heaviest first
+//   GET  /basins/{id}         cluster, ridge or urgent alert, contributors resolved
+//   GET  /ledger/{hash}       one ledger entry, vector and 2-D coordinates included
+//   GET  /events              recent cluster lifecycle events, oldest first
+//   GET  /feedback/stream     Server-Sent Events, one BasinFeedback per event
+//
+// The daemon publishes every basin to the stream just before queueing it
+// for the sinks, so stream clients see exactly what the sinks see. Each
+// client gets a bounded queue; one that falls that far behind is dropped
+// rather than slowing the daemon down.
+
+use crate::clustering::TimedEvent;
+use crate::daemon::{BasinSummary, Outlet, StatusReport};
+use crate::embed::Embedder;
+use crate::engine::{self, Engine};
+use crate::feedback::sinks::NETWORK_TIMEOUT;
+use crate::http;
+use crate::types::{BasinFeedback, BasinType, ConceptPacket, LedgerEntry, Polarity, Tempo};
+use crate::wire;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Comment line sent to idle stream clients (also detects hang-ups)
+const KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// Events a stream client may fall behind by before it is dropped
+pub const STREAM_QUEUE: usize = 64;
+
+/// Reply to `POST /packets`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestReply {
+    pub accepted: usize,
+    pub rejected: usize,
+    pub ledger_len: usize,
+    pub results: Vec<IngestResult>, // one per packet, in request order
+}
+
+/// Outcome for one posted packet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestResult {
+    pub rationale_hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert: Option<String>, // urgent alert raised by this packet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>, // why it was rejected
+}
+
+/// Reply to `GET /basins/{id}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasinDetail {
+    pub basin_id: String,
+    pub type_: BasinType,
+    pub rep_id: String, // medoid rationale_hash
+    pub rep_phrase: String,
+    pub persistence: u32,
+    pub tempo: Tempo,
+    pub contributors: Vec<Contributor>,
+    pub dissent: Vec<Contributor>, // live Repel entries within reach (clusters only)
+}
+
+/// A ledger entry without its vector
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contributor {
+    pub rationale_hash: String,
+    pub agent_id: String,
+    pub phrase: String,
+    pub provenance: String,
+    pub amp: f32,
+    pub polarity: Polarity,
+    pub tempo: Tempo,
+    pub timestamp: u64,
+}
+
+impl From<&LedgerEntry> for Contributor {
+    fn from(entry: &LedgerEntry) -> Self {
+        Self {
+            rationale_hash: entry.rationale_hash.clone(),
+            agent_id: entry.agent_id.clone(),
+            phrase: entry.phrase.clone(),
+            provenance: entry.provenance.clone(),
+            amp: entry.amp,
+            polarity: entry.polarity,
+            tempo: entry.tempo,
+            timestamp: entry.timestamp,
+        }
+    }
+}
+
+/// Subscribers of `GET /feedback/stream`
+/// Clones share one subscriber list
+#[derive(Clone, Default)]
+pub struct Streams {
+    clients: Arc<Mutex<Vec<SyncSender<BasinFeedback>>>>,
+}
+
+impl Streams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connected stream clients
+    pub fn len(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Hand `basin` to every client; gone or lagging clients are dropped
+    pub fn publish(&self, basin: &BasinFeedback) -> usize {
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|client| client.try_send(basin.clone()).is_ok());
+        clients.len()
+    }
+
+    /// Disconnect every client (at shutdown)
+    pub fn close(&self) {
+        self.clients.lock().unwrap().clear();
+    }
+
+    fn subscribe(&self) -> Receiver<BasinFeedback> {
+        let (tx, rx) = mpsc::sync_channel(STREAM_QUEUE);
+        self.clients.lock().unwrap().push(tx);
+        rx
+    }
+}
+
+/// Accept loop for `listener`; stops at the first connection after `shutdown`
+pub(crate) fn spawn<E: Embedder + Send + Sync + 'static>(
+    listener: TcpListener,
+    engine: Arc<Mutex<Engine<E>>>,
+    embedder: Arc<E>,
+    outlet: Outlet,
+    shutdown: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            if shutdown.load(Ordering::SeqCst) {
+                break;
+            }
+            let Ok(stream) = stream else { continue };
+            // A stalled peer must not hold a handler thread forever
+            let timeouts = stream
+                .set_read_timeout(Some(NETWORK_TIMEOUT))
+                .and_then(|()| stream.set_write_timeout(Some(NETWORK_TIMEOUT)));
+            if timeouts.is_err() {
+                continue;
+            }
+            let engine = Arc::clone(&engine);
+            let embedder = Arc::clone(&embedder);
+            let outlet = outlet.clone();
+            thread::spawn(move || {
+                // A dropped client is not the daemon's problem
+                let _ = handle_client(stream, &engine, &*embedder, &outlet);
+            });
+        }
+    })
+}
+
+fn handle_client<E: Embedder>(
+    stream: TcpStream,
+    engine: &Mutex<Engine<E>>,
+    embedder: &E,
+    outlet: &Outlet,
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let request = match http::read_request(&mut BufReader::new(stream)) {
+        Ok(Some(request)) => request,
+        Ok(None) => return Ok(()),
+        Err(e) => return reply(&mut writer, 400, &error(e.to_string())),
+    };
+    let path = request.path.split('?').next().unwrap_or("");
+
+    if path == "/feedback/stream" {
+        if request.method != "GET" {
+            return reply(&mut writer, 405, &error("use GET".to_string()));
+        }
+        return stream_feedback(&mut writer, outlet.streams().subscribe());
+    }
+    let (status, body) = route(&request, path, engine, embedder, outlet);
+    reply(&mut writer, status, &body)
+}
+
+/// Status and JSON body for every endpoint except the stream
+fn route<E: Embedder>(
+    request: &http::Request,
+    path: &str,
+    engine: &Mutex<Engine<E>>,
+    embedder: &E,
+    outlet: &Outlet,
+) -> (u16, serde_json::Value) {
+    let method = request.method.as_str();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        ("POST", ["packets"]) => ingest(request, engine, embedder, outlet),
+        ("GET", ["basins"]) => {
+            let basins = StatusReport::from_engine(&engine.lock().unwrap()).basins;
+            json::<Vec<BasinSummary>>(200, &basins)
+        }
+        ("GET", ["basins", id]) => match basin_detail(&engine.lock().unwrap(), id) {
+            Some(detail) => json(200, &detail),
+            None => (404, error(format!("no live basin {}", id))),
+        },
+        ("GET", ["ledger", hash]) => {
+            let engine = engine.lock().unwrap();
+            match engine.ledger().get(hash) {
+                Some(entry) => {
+                    let mut entry = entry.clone();
+                    entry.coords_2d = engine.projector().project(&entry.vector);
+                    json(200, &entry)
+                }
+                None => (404, error(format!("no ledger entry {}", hash))),
+            }
+        }
+        ("GET", ["events"]) => {
+            let events: Vec<TimedEvent> = engine.lock().unwrap().events().cloned().collect();
+            json(200, &events)
+        }
+        (_, ["packets"] | ["basins"] | ["basins", _] | ["ledger", _] | ["events"]) => {
+            (405, error(format!("{} not allowed on {}", method, path)))
+        }
+        _ => (404, error(format!("no route for {}", path))),
+    }
+}
+
+/// Ingest one packet or a batch
+/// All phrases go out in one embedding call with the engine unlocked, then
+/// every packet is appended under a single lock
+fn ingest<E: Embedder>(
+    request: &http::Request,
+    engine: &Mutex<Engine<E>>,
+    embedder: &E,
+    outlet: &Outlet,
+) -> (u16, serde_json::Value) {
+    let msgpack = request
+        .content_type
+        .as_deref()
+        .is_some_and(|t| t.starts_with(wire::MEDIA_TYPE));
+    let packets = if msgpack {
+        read_frames(&request.body)
+    } else {
+        read_json(&request.body)
+    };
+    let packets = match packets {
+        Ok(packets) if !packets.is_empty() => packets,
+        Ok(_) => return (400, error("no packets".to_string())),
+        Err(e) => return (400, error(format!("bad packet: {}", e))),
+    };
+
+    let mut results: Vec<IngestResult> = packets
+        .iter()
+        .map(|packet| IngestResult {
+            rationale_hash: packet.rationale_hash.clone(),
+            alert: None,
+            error: None,
+        })
+        .collect();
+
+    // Rejects are found before any embedding is spent on them
+    let mut slots = Vec::with_capacity(packets.len());
+    let mut batch = Vec::with_capacity(packets.len());
+    {
+        let engine = engine.lock().unwrap();
+        for (slot, packet) in packets.into_iter().enumerate() {
+            match engine.check(&packet) {
+                Ok(()) => {
+                    slots.push(slot);
+                    batch.push(packet);
+                }
+                Err(e) => results[slot].error = Some(e.to_string()),
+            }
+        }
+    }
+    let vectors: Vec<Result<Vec<f32>, String>> = match engine::vectorize_batch(embedder, &mut batch)
+    {
+        Ok(vectors) => vectors
+            .into_iter()
+            .map(|vector| vector.map_err(|e| e.to_string()))
+            .collect(),
+        Err(e) => vec![Err(e.to_string()); batch.len()],
+    };
+
+    let mut alerts = Vec::new();
+    let ledger_len = {
+        let mut engine = engine.lock().unwrap();
+        for ((slot, packet), vector) in slots.into_iter().zip(batch).zip(vectors) {
+            let ingested = vector.and_then(|vector| {
+                engine
+                    .ingest_vector(packet, vector)
+                    .map_err(|e| e.to_string())
+            });
+            match ingested {
+                Ok(alert) => {
+                    results[slot].alert = alert.as_ref().map(|a| a.basin_id.clone());
+                    alerts.extend(alert);
+                }
+                Err(e) => results[slot].error = Some(e),
+            }
+        }
+        engine.ledger().len()
+    };
+    // A full queue blocks this client, never the engine
+    for alert in alerts {
+        outlet.send(alert); // receiver gone only at shutdown
+    }
+
+    let rejected = results.iter().filter(|r| r.error.is_some()).count();
+    let reply = IngestReply {
+        accepted: results.len() - rejected,
+        rejected,
+        ledger_len,
+        results,
+    };
+    let status = if reply.accepted == 0 { 422 } else { 200 };
+    json(status, &reply)
+}
+
+/// One ConceptPacket object or an array of them
+fn read_json(body: &[u8]) -> Result<Vec<ConceptPacket>, String> {
+    let packets = match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(serde_json::Value::Array(items)) => items
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<_>, _>>(),
+        Ok(item) => serde_json::from_value(item).map(|packet| vec![packet]),
+        Err(e) => Err(e),
+    };
+    packets.map_err(|e| e.to_string())
+}
+
+/// Back-to-back ConceptPacket frames
+fn read_frames(mut body: &[u8]) -> Result<Vec<ConceptPacket>, String> {
+    let mut packets = Vec::new();
+    while let Some(packet) = wire::read_frame(&mut body).map_err(|e| e.to_string())? {
+        packets.push(packet);
+    }
+    Ok(packets)
+}
+
+/// Cluster, mature ridge or urgent alert with its members resolved
+fn basin_detail<E: Embedder>(engine: &Engine<E>, id: &str) -> Option<BasinDetail> {
+    let ledger = engine.ledger();
+    let resolve = |hashes: &[String]| -> Vec<Contributor> {
+        ledger
+            .get_batch(hashes)
+            .into_iter()
+            .map(Contributor::from)
+            .collect()
+    };
+
+    if let Some(cluster) = engine.clusters().get_cluster(id) {
+        return Some(BasinDetail {
+            basin_id: cluster.id.clone(),
+            type_: cluster.basin_type(),
+            rep_id: cluster.medoid_hash.clone(),
+            rep_phrase: cluster.medoid_phrase.clone(),
+            persistence: cluster.persistence,
+            tempo: cluster.tempo,
+            contributors: resolve(&cluster.members),
+            dissent: resolve(&cluster.dissent),
+        });
+    }
+    if let Some(ridge) = engine.clusters().ridges().get(id) {
+        return Some(BasinDetail {
+            basin_id: ridge.id.clone(),
+            type_: BasinType::Ridge,
+            rep_id: ridge.rep_hash.clone(),
+            rep_phrase: ridge.rep_phrase.clone(),
+            persistence: ridge.persistence,
+            tempo: ridge.tempo,
+            contributors: resolve(&ridge.boundary),
+            dissent: Vec::new(),
+        });
+    }
+    let alert = engine.urgent().get(id)?;
+    Some(BasinDetail {
+        basin_id: alert.id.clone(),
+        type_: BasinType::Valley,
+        rep_id: alert.rep_hash.clone(),
+        rep_phrase: alert.rep_phrase.clone(),
+        persistence: 0, // bypassed
+        tempo: Tempo::Urgent,
+        contributors: resolve(&alert.contributors),
+        dissent: Vec::new(),
+    })
+}
+
+/// Write events until the client hangs up or the daemon closes the streams
+fn stream_feedback<W: Write>(writer: &mut W, feed: Receiver<BasinFeedback>) -> io::Result<()> {
+    http::write_event_stream_head(writer)?;
+    loop {
+        match feed.recv_timeout(KEEPALIVE) {
+            Ok(basin) => {
+                let data = serde_json::to_string(&basin)?;
+                write!(writer, "id: {}\ndata: {}\n\n", basin.basin_id, data)?;
+            }
+            Err(RecvTimeoutError::Timeout) => writer.write_all(b": keepalive\n\n")?,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+        writer.flush()?;
+    }
+}
+
+fn reply<W: Write>(writer: &mut W, status: u16, body: &serde_json::Value) -> io::Result<()> {
+    let body = serde_json::to_vec(body)?;
+    http::write_response(writer, status, "application/json", &body)
+}
+
+fn json<T: Serialize + ?Sized>(status: u16, value: &T) -> (u16, serde_json::Value) {
+    match serde_json::to_value(value) {
+        Ok(value) => (status, value),
+        Err(e) => (500, error(e.to_string())),
+    }
+}
+
+fn error(message: String) -> serde_json::Value {
+    serde_json::json!({ "error": message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clustering::ClusterEvent;
+    use crate::daemon::Daemon;
+    use crate::now_ms;
+    use std::io::{BufRead, Read};
+    use std::net::SocketAddr;
+    use std::path::PathBuf;
+
+    fn packet(phrase: &str, hash: &str, agent: &str) -> ConceptPacket {
+        ConceptPacket {
+            phrase: phrase.to_string(),
+            amp: 0.8,
+            sigma: 1.0,
+            polarity: Polarity::Attract,
+            tempo: Tempo::Slow,
+            provenance: "test".to_string(),
+            agent_id: agent.to_string(),
+            rationale_hash: hash.to_string(),
+            timestamp: now_ms(),
+            center: None,
+            model: None,
+        }
+    }
+
+    fn socket_path() -> PathBuf {
+        std::env::temp_dir().join(format!("sefi_{}.sock", uuid::Uuid::new_v4()))
+    }
+
+    /// One request over a fresh connection: (status, body)
+    fn call(addr: &str, method: &str, path: &str, body: &str) -> (u16, String) {
+        let url = http::Url::parse(&format!("http://{}{}", addr, path)).unwrap();
+        let body = (!body.is_empty()).then_some(body.as_bytes());
+        let response = http::send(method, &url, body, Duration::from_secs(5)).unwrap();
+        (response.status, String::from_utf8(response.body).unwrap())
+    }
+
+    #[test]
+    fn test_ingest_and_queries() {
+        let socket = socket_path();
+        let mut daemon = Daemon::spawn(Engine::new(), &socket, Duration::from_millis(10)).unwrap();
+        let addr = daemon.serve_http("127.0.0.1:0").unwrap().to_string();
+
+        // A single packet, then a batch with one duplicate
+        let one = serde_json::to_string(&packet("memory safety", "h0", "a")).unwrap();
+        let (status, body) = call(&addr, "POST", "/packets", &one);
+        assert_eq!(status, 200);
+        let reply: IngestReply = serde_json::from_str(&body).unwrap();
+        assert_eq!((reply.accepted, reply.ledger_len), (1, 1));
+
+        let batch = vec![
+            packet("memory safety", "h1", "b"),
+            packet("memory safety", "h2", "c"),
+            packet("memory safety", "h0", "a"),
+        ];
+        let (status, body) = call(
+            &addr,
+            "POST",
+            "/packets",
+            &serde_json::to_string(&batch).unwrap(),
+        );
+        assert_eq!(status, 200);
+        let reply: IngestReply = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            (reply.accepted, reply.rejected, reply.ledger_len),
+            (2, 1, 3)
+        );
+        assert!(reply.results[2].error.as_ref().unwrap().contains("h0"));
+
+        assert_eq!(call(&addr, "POST", "/packets", "{\"phrase\": 1}").0, 400);
+        assert_eq!(call(&addr, "POST", "/packets", "[]").0, 400);
+        let dup = serde_json::to_string(&[packet("memory safety", "h0", "a")]).unwrap();
+        assert_eq!(call(&addr, "POST", "/packets", &dup).0, 422);
+
+        // The basin forms; its contributors come back resolved
+        let deadline = std::time::Instant::now() + Duration::from_secs(2);
+        let basins = loop {
+            let (status, body) = call(&addr, "GET", "/basins", "");
+            assert_eq!(status, 200);
+            let basins: Vec<BasinSummary> = serde_json::from_str(&body).unwrap();
+            if basins.first().is_some_and(|b| b.members == 3) {
+                break basins;
+            }
+            assert!(std::time::Instant::now() < deadline, "basin never formed");
+            thread::sleep(Duration::from_millis(10));
+        };
+        let (status, body) = call(&addr, "GET", &format!("/basins/{}", basins[0].basin_id), "");
+        assert_eq!(status, 200);
+        let detail: BasinDetail = serde_json::from_str(&body).unwrap();
+        let mut agents: Vec<&str> = detail
+            .contributors
+            .iter()
+            .map(|c| c.agent_id.as_str())
+            .collect();
+        agents.sort_unstable();
+        assert_eq!(agents, vec!["a", "b", "c"]);
+        assert_eq!(detail.rep_phrase, "memory safety");
+
+        let (status, body) = call(&addr, "GET", "/ledger/h1", "");
+        assert_eq!(status, 200);
+        let entry: LedgerEntry = serde_json::from_str(&body).unwrap();
+        assert_eq!((entry.agent_id.as_str(), entry.vector.len()), ("b", 768));
+        assert!(entry.coords_2d.is_some(), "placed once the basis is fitted");
+
+        // Its birth is in the lifecycle history
+        let (status, body) = call(&addr, "GET", "/events", "");
+        assert_eq!(status, 200);
+        let events: Vec<TimedEvent> = serde_json::from_str(&body).unwrap();
+        let born = ClusterEvent::Born {
+            id: basins[0].basin_id.clone(),
+        };
+        assert!(events.iter().any(|e| e.event == born));
+
+        assert_eq!(call(&addr, "GET", "/basins/nope", "").0, 404);
+        assert_eq!(call(&addr, "GET", "/ledger/nope", "").0, 404);
+        assert_eq!(call(&addr, "DELETE", "/basins", "").0, 405);
+        assert_eq!(call(&addr, "GET", "/elsewhere", "").0, 404);
+
+        daemon.shutdown().unwrap();
+    }
+
+    /// POST wire frames over a fresh connection: (status, body)
+    fn post_frames(addr: SocketAddr, frames: &[u8]) -> (u16, String) {
+        let mut client = TcpStream::connect(addr).unwrap();
+        write!(
+            client,
+            "POST /packets HTTP/1.1\r\nHost: x\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+            wire::MEDIA_TYPE,
+            frames.len()
+        )
+        .unwrap();
+        client.write_all(frames).unwrap();
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, body.to_string())
+    }
+
+    #[test]
+    fn test_ingest_msgpack_frames() {
+        let socket = socket_path();
+        let mut daemon = Daemon::spawn(Engine::new(), &socket, Duration::from_millis(10)).unwrap();
+        let addr = daemon.serve_http("127.0.0.1:0").unwrap();
+
+        let mut frames = Vec::new();
+        for hash in ["h0", "h1"] {
+            wire::write_frame(&mut frames, &packet("memory safety", hash, "a")).unwrap();
+        }
+        let (status, body) = post_frames(addr, &frames);
+        assert_eq!(status, 200);
+        let reply: IngestReply = serde_json::from_str(&body).unwrap();
+        assert_eq!((reply.accepted, reply.ledger_len), (2, 2));
+
+        // A truncated frame is a bad request, not a partial ingest
+        assert_eq!(post_frames(addr, &frames[..3]).0, 400);
+
+        daemon.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_feedback_stream() {
+        let socket = socket_path();
+        let mut daemon = Daemon::spawn(Engine::new(), &socket, Duration::from_millis(10)).unwrap();
+        let addr = daemon.serve_http("127.0.0.1:0").unwrap();
+        let streams = daemon.streams();
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        client
+            .write_all(b"GET /feedback/stream HTTP/1.1\r\nHost: x\r\n\r\n")
+            .unwrap();
+        let mut reader = BufReader::new(client);
+        let mut head = String::new();
+        while !head.ends_with("\r\n\r\n") {
+            reader.read_line(&mut head).unwrap();
+        }
+        assert!(head.starts_with("HTTP/1.1 200"));
+        assert!(head.contains("text/event-stream"));
+
+        // Subscribed once the head is out; ingest until the daemon reports a basin
+        assert_eq!(streams.len(), 1);
+        for i in 0..3 {
+            let packet = packet("memory safety", &format!("h{}", i), "a");
+            let body = serde_json::to_string(&packet).unwrap();
+            assert_eq!(call(&addr.to_string(), "POST", "/packets", &body).0, 200);
+        }
+
+        let mut event = String::new();
+        while !event.ends_with("\n\n") {
+            reader.read_line(&mut event).unwrap();
+        }
+        let mut lines = event.lines();
+        let id = lines.next().unwrap().strip_prefix("id: ").unwrap();
+        let data = lines.next().unwrap().strip_prefix("data: ").unwrap();
+        let got: BasinFeedback = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            (got.basin_id.as_str(), got.rep_phrase.as_str()),
+            (id, "memory safety")
+        );
+
+        // The same basin reached the daemon's own feedback queue
+        let queued = daemon
+            .feedback()
+            .recv_timeout(Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(queued.basin_id, got.basin_id);
+
+        // Shutdown ends the stream instead of leaving the client hanging
+        daemon.shutdown().unwrap();
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).unwrap();
+        assert!(streams.is_empty());
+    }
+
+    #[test]
+    fn test_lagging_subscriber_is_dropped() {
+        let streams = Streams::new();
+        let feed = streams.subscribe();
+        let basin = BasinFeedback {
+            basin_id: "c7".to_string(),
+            type_: BasinType::Valley,
+            coords_2d: [0.0, 0.0],
+            rep_id: "h0".to_string(),
+            rep_phrase: "memory safety".to_string(),
+            contributors: vec!["h0".to_string(), "h1".to_string()],
+            nd_cohesion: 0.8,
+            nd_radius: 0.1,
+            persistence: 2,
+            tempo: Tempo::Slow,
+            dissent: 0,
+            centroid: None,
+            endpoints: None,
+            axis: None,
+            decompose_into: None,
+            recommended_action: crate::types::Action::PlanSpike,
+            precard: None,
+            thresholds: None,
+            timestamp: 1000,
+        };
+        for _ in 0..STREAM_QUEUE {
+            assert_eq!(streams.publish(&basin), 1);
+        }
+        assert_eq!(streams.publish(&basin), 0);
+
+        // What was queued is still delivered, then the stream ends
+        assert_eq!(feed.iter().count(), STREAM_QUEUE);
+    }
+}
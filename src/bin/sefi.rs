@@ -1,7 +1,51 @@
 // Sefi CLI for Phase 1
 
-use sefi::{ConceptPacket, Polarity, Tempo};
-use std::time::{SystemTime, UNIX_EPOCH};
+use sefi::viz::frames;
+use std::path::PathBuf;
+
+// Everything but `animate` talks to the daemon over a Unix socket
+#[cfg(unix)]
+use {
+    sefi::daemon::{self, Daemon, Request, Response},
+    sefi::embed::cache::{CacheConfig, CachedEmbedder},
+    sefi::embed::client::{HttpEmbedder, HttpEmbedderConfig},
+    sefi::embed::{EmbedService, Embedder},
+    sefi::feedback::emitter::{self, Emitter, SinkPolicy},
+    sefi::governor::Governor,
+    sefi::ledger::segment::ModelInfo,
+    sefi::ledger::store::Ledger,
+    sefi::viz::dashboard,
+    sefi::viz::frames::FrameWriter,
+    sefi::viz::heatmap::{self, HeatmapConfig, Scene},
+    sefi::{now_ms, BasinFeedback, ConceptPacket, Engine, Polarity, Tempo},
+    std::fs::File,
+    std::io::{Read, Write},
+    std::process::{Command, Stdio},
+    std::sync::mpsc::{self, RecvTimeoutError},
+    std::time::{Duration, Instant},
+};
+
+/// How often `serve` writes the embedding cache to disk
+#[cfg(unix)]
+const CACHE_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often `serve` checks for governor adjustments to log
+#[cfg(unix)]
+const LOG_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Ledger directory (override with SEFI_DATA)
+#[cfg(unix)]
+fn data_dir() -> String {
+    std::env::var("SEFI_DATA").unwrap_or_else(|_| "sefi-data".to_string())
+}
+
+/// Daemon socket (override with SEFI_SOCKET)
+#[cfg(unix)]
+fn socket_path() -> PathBuf {
+    std::env::var("SEFI_SOCKET")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(data_dir()).join("sefi.sock"))
+}
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -12,8 +56,22 @@ fn main() {
     }
 
     match args[1].as_str() {
+        #[cfg(unix)]
+        "serve" => serve_command(&args[2..]),
+        #[cfg(unix)]
         "emit" => emit_command(&args[2..]),
+        #[cfg(unix)]
         "status" => status_command(),
+        #[cfg(unix)]
+        "top" => top_command(&args[2..]),
+        #[cfg(not(unix))]
+        "serve" | "emit" | "status" | "top" => {
+            println!(
+                "{} is unsupported on this platform: the daemon needs Unix sockets",
+                args[1]
+            );
+        }
+        "animate" => animate_command(&args[2..]),
         _ => {
             println!("Unknown command: {}", args[1]);
             print_usage();
@@ -25,15 +83,36 @@ fn print_usage() {
     println!("Sefi v0.3 - Semantic Field Blackboard (N-D Primary)");
     println!();
     println!("Usage:");
-    println!("  sefi emit <phrase> [--amp <0.0-1.0>] [--tempo fast|slow|urgent]");
+    println!(
+        "  sefi serve [--tick-ms <ms>] [--fixed-thresholds] [--frames <dir> [--frame-ms <ms>]]"
+    );
+    println!("             [--http <host:port>]");
+    println!(
+        "  sefi emit <phrase> [--amp <0.0-1.0>] [--tempo fast|slow|urgent] [--agent <id>] [--repel]"
+    );
     println!("  sefi status");
+    println!("  sefi top [--interval-ms <ms>] [--once]");
+    println!("  sefi animate <frames-dir> [--delay-ms <ms>]");
+    println!();
+    println!("Environment:");
+    println!("  SEFI_DATA         ledger directory (default: sefi-data)");
+    println!("  SEFI_SOCKET       daemon socket (default: $SEFI_DATA/sefi.sock)");
+    println!("  SEFI_EMBED_URL    embedding service, e.g. http://embedding-host:8000/embed");
+    println!("  SEFI_EMBED_DIM    expected vector dimension (default: 768)");
+    println!("  SEFI_EMBED_MODEL  model id reported for remote vectors");
+    println!("  SEFI_SINKS        feedback sinks, comma-separated (default: stdout):");
+    println!("                    stdout, jsonl:<path>, tcp:<host:port>, tcp+msgpack:<host:port>,");
+    println!("                    udp:<group:port>, webhook:<http url>");
     println!();
     println!("Examples:");
+    println!("  sefi serve &");
     println!("  sefi emit \"memory safety\" --amp 0.9 --tempo fast");
     println!("  sefi emit \"consensus pattern\" --tempo slow");
+    println!("  sefi emit \"memory leak\" --repel");
     println!("  sefi status");
 }
 
+#[cfg(unix)]
 fn emit_command(args: &[String]) {
     if args.is_empty() {
         println!("Error: phrase required");
@@ -43,6 +122,8 @@ fn emit_command(args: &[String]) {
     let phrase = &args[0];
     let mut amp = 0.5;
     let mut tempo = Tempo::Slow;
+    let mut agent_id = "human".to_string();
+    let mut polarity = Polarity::Attract;
 
     // Parse optional args
     let mut i = 1;
@@ -69,40 +150,508 @@ fn emit_command(args: &[String]) {
                     i += 1;
                 }
             }
+            "--agent" => {
+                if i + 1 < args.len() {
+                    agent_id = args[i + 1].clone();
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            "--repel" => {
+                polarity = Polarity::Repel;
+                i += 1;
+            }
             _ => i += 1,
         }
     }
 
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_millis() as u64;
+    let now = now_ms();
 
     let packet = ConceptPacket {
         phrase: phrase.clone(),
         amp,
         sigma: 1.0,
-        polarity: Polarity::Attract,
+        polarity,
         tempo,
         provenance: "cli".to_string(),
-        agent_id: "human".to_string(),
-        rationale_hash: format!("cli_{}", now),
+        agent_id,
+        rationale_hash: format!("cli_{}_{}", now, std::process::id()),
         timestamp: now,
+        center: None,
+        model: None,
     };
 
     println!("Emitted packet:");
     println!("  phrase: {}", packet.phrase);
     println!("  amp: {}", packet.amp);
+    println!("  polarity: {:?}", packet.polarity);
     println!("  tempo: {:?} (τ={}s)", packet.tempo, packet.tempo.tau());
     println!("  timestamp: {}", packet.timestamp);
     println!();
-    println!("TODO: Store in ledger and trigger clustering");
+
+    match daemon::request(&socket_path(), &Request::Emit { packet }) {
+        Ok(Response::Accepted { ledger_len, alert }) => {
+            println!("Stored in ledger ({} entries)", ledger_len);
+            if let Some(id) = alert {
+                println!("Urgent alert raised ({})", id);
+            }
+        }
+        Ok(Response::Error { message }) => println!("Error: {}", message),
+        Ok(other) => println!("Error: unexpected reply {:?}", other),
+        Err(e) => print_unreachable(&e),
+    }
 }
 
+#[cfg(unix)]
 fn status_command() {
     println!("Sefi Status:");
     println!("  Version: 0.3 (N-D Primary)");
     println!("  Mode: Phase 1 - Minimal Loop");
     println!();
-    println!("TODO: Show ledger size, active basins, etc.");
+
+    let report = match daemon::request(&socket_path(), &Request::Status) {
+        Ok(Response::Status(report)) => report,
+        Ok(Response::Error { message }) => {
+            println!("Error: {}", message);
+            return;
+        }
+        Ok(other) => {
+            println!("Error: unexpected reply {:?}", other);
+            return;
+        }
+        Err(e) => {
+            print_unreachable(&e);
+            return;
+        }
+    };
+
+    let storage = if report.durable { "durable" } else { "memory" };
+    println!("  Ledger: {} entries ({})", report.ledger_len, storage);
+    println!("  Active basins: {}", report.basins.len());
+    for basin in &report.basins {
+        println!(
+            "    {} \"{}\" members={} dissent={} mass={:.2} persistence={} tempo={:?}",
+            basin.basin_id,
+            basin.rep_phrase,
+            basin.members,
+            basin.dissent,
+            basin.mass,
+            basin.persistence,
+            basin.tempo
+        );
+    }
+}
+
+#[cfg(unix)]
+fn top_command(args: &[String]) {
+    let mut interval_ms = 1000;
+    let mut once = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--interval-ms" => {
+                if i + 1 < args.len() {
+                    interval_ms = args[i + 1].parse().unwrap_or(1000);
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            "--once" => {
+                once = true;
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    // One plain frame for scripts, CI logs and non-terminals
+    if once {
+        match top_frame() {
+            Ok(lines) => {
+                for line in lines {
+                    println!("{}", line.trim_end());
+                }
+            }
+            Err(message) => println!("Error: {}", message),
+        }
+        return;
+    }
+
+    let Some(_terminal) = RawTerminal::enter() else {
+        println!("Error: sefi top needs a terminal (use --once otherwise)");
+        return;
+    };
+
+    // Raw mode: keys arrive one byte at a time, Ctrl-C included
+    let (keys, pressed) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin();
+        let mut byte = [0u8; 1];
+        while stdin.read(&mut byte).is_ok_and(|n| n == 1) {
+            if keys.send(byte[0]).is_err() {
+                break;
+            }
+        }
+    });
+
+    let interval = Duration::from_millis(interval_ms.max(50));
+    let mut stdout = std::io::stdout();
+    loop {
+        let lines = top_frame().unwrap_or_else(|message| vec![format!(" {}", message)]);
+        let rows: Vec<String> = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| match i {
+                0 => format!("\x1b[7m{}\x1b[0m\x1b[K", line), // header in reverse video
+                _ => format!("{}\x1b[K", line),
+            })
+            .collect();
+        let screen = format!("\x1b[H{}\x1b[J", rows.join("\r\n"));
+        let _ = stdout.write_all(screen.as_bytes());
+        let _ = stdout.flush();
+
+        match pressed.recv_timeout(interval) {
+            Ok(b'q') | Ok(b'Q') | Ok(3) => break, // 3 = Ctrl-C in raw mode
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// Fetch a TopReport and lay it out for the current terminal size
+#[cfg(unix)]
+fn top_frame() -> Result<Vec<String>, String> {
+    let (width, height) = terminal_size();
+    match daemon::request(&socket_path(), &Request::Top) {
+        Ok(Response::Top(report)) => Ok(dashboard::render(&report, width, height)),
+        Ok(Response::Error { message }) => Err(message),
+        Ok(other) => Err(format!("unexpected reply {:?}", other)),
+        Err(e) => Err(format!(
+            "cannot reach daemon at {} ({})",
+            socket_path().display(),
+            e
+        )),
+    }
+}
+
+/// (columns, rows) from `stty size`, else COLUMNS/LINES, else 80×24
+#[cfg(unix)]
+fn terminal_size() -> (usize, usize) {
+    let from_stty = stty(&["size"]).and_then(|size| {
+        let mut fields = size.split_whitespace().map(|f| f.parse::<usize>().ok());
+        let (rows, cols) = (fields.next()??, fields.next()??);
+        (rows > 0 && cols > 0).then_some((cols, rows))
+    });
+    from_stty.unwrap_or_else(|| {
+        let env = |name: &str, default| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        (env("COLUMNS", 80), env("LINES", 24))
+    })
+}
+
+/// Raw mode on the alternate screen; restored on drop
+#[cfg(unix)]
+struct RawTerminal {
+    saved: String, // `stty -g` settings to restore
+}
+
+#[cfg(unix)]
+impl RawTerminal {
+    fn enter() -> Option<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        let _ = std::io::stdout().flush();
+        Some(Self {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = std::io::stdout().flush();
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+/// Run stty against the controlling terminal; None without one
+#[cfg(unix)]
+fn stty(args: &[&str]) -> Option<String> {
+    let tty = File::open("/dev/tty").ok()?;
+    let out = Command::new("stty")
+        .args(args)
+        .stdin(tty)
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+#[cfg(unix)]
+fn serve_command(args: &[String]) {
+    let mut tick_ms = 100; // 10 Hz
+    let mut governed = true;
+    let mut frames_dir: Option<PathBuf> = None;
+    let mut http_addr: Option<String> = None;
+    let mut frame_ms = 1000;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--tick-ms" => {
+                if i + 1 < args.len() {
+                    tick_ms = args[i + 1].parse().unwrap_or(100);
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            "--fixed-thresholds" => {
+                governed = false;
+                i += 1;
+            }
+            "--frames" => {
+                if i + 1 < args.len() {
+                    frames_dir = Some(PathBuf::from(&args[i + 1]));
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            "--frame-ms" => {
+                if i + 1 < args.len() {
+                    frame_ms = args[i + 1].parse().unwrap_or(1000);
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            "--http" => {
+                if i + 1 < args.len() {
+                    http_addr = Some(args[i + 1].clone());
+                    i += 2;
+                } else {
+                    i += 1;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    let embedder = match make_embedder() {
+        Ok(embedder) => embedder,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+    let model = embedder.model_id().to_string();
+    let ledger = match Ledger::open(data_dir(), ModelInfo::new(&model, embedder.dim())) {
+        Ok(ledger) => ledger,
+        Err(e) => {
+            println!("Error: cannot open ledger at {}: {}", data_dir(), e);
+            return;
+        }
+    };
+    let cache_path = PathBuf::from(data_dir()).join("embed-cache.json");
+    let embedder = match CachedEmbedder::persistent(embedder, CacheConfig::default(), &cache_path) {
+        Ok(embedder) => embedder,
+        Err(e) => {
+            println!("Error: cannot load {}: {}", cache_path.display(), e);
+            return;
+        }
+    };
+    let cached = embedder.stats().entries;
+    let sinks = std::env::var("SEFI_SINKS").unwrap_or_else(|_| "stdout".to_string());
+    let mut emitter = match emitter::parse_sinks(&sinks, &SinkPolicy::default()) {
+        Ok(emitter) => emitter,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+    let mut engine = Engine::with_embedder(ledger, embedder);
+    if governed {
+        engine = engine.with_governor(Governor::new());
+    }
+    let recovered = engine.ledger().len();
+    let embedder = engine.shared_embedder();
+    let truncated = engine.ledger().truncated();
+
+    let mut frame_writer = match &frames_dir {
+        Some(dir) => match FrameWriter::create(dir) {
+            Ok(writer) => Some(writer),
+            Err(e) => {
+                println!(
+                    "Error: cannot create frame directory {}: {}",
+                    dir.display(),
+                    e
+                );
+                return;
+            }
+        },
+        None => None,
+    };
+    let frame_interval = Duration::from_millis(frame_ms.max(1));
+
+    let socket = socket_path();
+    let mut daemon = match Daemon::spawn(engine, &socket, Duration::from_millis(tick_ms)) {
+        Ok(daemon) => daemon,
+        Err(e) => {
+            println!("Error: cannot listen on {}: {}", socket.display(), e);
+            return;
+        }
+    };
+    let http = match &http_addr {
+        Some(addr) => match daemon.serve_http(addr) {
+            Ok(local) => Some(local),
+            Err(e) => {
+                println!("Error: cannot listen on {}: {}", addr, e);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    println!("Sefi daemon listening on {}", socket.display());
+    println!("  Ledger: {} ({} entries recovered)", data_dir(), recovered);
+    if truncated > 0 {
+        println!("  Warning: dropped {} bytes of torn tail record", truncated);
+    }
+    println!("  Embedder: {} ({} cached phrases)", model, cached);
+    if let Some(addr) = http {
+        println!("  HTTP: http://{}", addr);
+    }
+    println!("  Tick: {} ms", tick_ms);
+    println!("  Sinks: {}", sink_names(&emitter));
+    let governor = if governed { "adaptive" } else { "fixed" };
+    println!("  Thresholds: {}", governor);
+    if let Some(dir) = &frames_dir {
+        println!("  Frames: {} (every {} ms)", dir.display(), frame_ms);
+    }
+    println!();
+
+    let mut last_save = Instant::now();
+    let mut last_log = Instant::now();
+    let mut last_frame = Instant::now();
+    let poll = match frame_writer {
+        Some(_) => LOG_INTERVAL.min(frame_interval),
+        None => LOG_INTERVAL,
+    };
+    loop {
+        match daemon.feedback().recv_timeout(poll) {
+            Ok(basin) => emit_basin(&mut emitter, &basin),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if last_log.elapsed() >= LOG_INTERVAL {
+            let adjustments = daemon.engine().lock().unwrap().take_adjustments();
+            for adjustment in adjustments {
+                println!("Governor: {}", adjustment);
+            }
+            last_log = Instant::now();
+        }
+        if let Some(writer) = frame_writer.as_mut() {
+            if last_frame.elapsed() >= frame_interval {
+                let scene = Scene::capture(&daemon.engine().lock().unwrap(), now_ms());
+                let image = heatmap::render(&scene, &HeatmapConfig::default());
+                if let Err(e) = writer.write(&image) {
+                    println!("Warning: cannot write frame: {}", e);
+                }
+                last_frame = Instant::now();
+            }
+        }
+        if last_save.elapsed() >= CACHE_SAVE_INTERVAL {
+            // The cache has its own lock; the engine stays free while we write
+            if let Err(e) = embedder.save() {
+                println!("Warning: cannot save embedding cache: {}", e);
+            }
+            last_save = Instant::now();
+        }
+    }
+}
+
+fn animate_command(args: &[String]) {
+    let Some(dir) = args.first() else {
+        println!("Usage: sefi animate <frames-dir> [--delay-ms <ms>]");
+        return;
+    };
+    let mut delay_ms = 200;
+    if args.len() >= 3 && args[1] == "--delay-ms" {
+        delay_ms = args[2].parse().unwrap_or(200);
+    }
+
+    match frames::animate(&PathBuf::from(dir), delay_ms) {
+        Ok(path) => println!("Wrote {}", path.display()),
+        Err(e) => println!("Error: cannot animate {}: {}", dir, e),
+    }
+}
+
+/// LAN embedding service when SEFI_EMBED_URL is set, SHA-256 mock otherwise
+#[cfg(unix)]
+fn make_embedder() -> Result<Box<dyn Embedder + Send + Sync>, String> {
+    let Ok(url) = std::env::var("SEFI_EMBED_URL") else {
+        return Ok(Box::new(EmbedService::new()));
+    };
+
+    let mut config = HttpEmbedderConfig {
+        url,
+        ..Default::default()
+    };
+    if let Ok(dim) = std::env::var("SEFI_EMBED_DIM") {
+        config.dim = dim
+            .parse()
+            .map_err(|_| format!("bad SEFI_EMBED_DIM: {}", dim))?;
+    }
+    if let Ok(model) = std::env::var("SEFI_EMBED_MODEL") {
+        config.model_id = model;
+    }
+
+    HttpEmbedder::new(config)
+        .map(|e| Box::new(e) as Box<dyn Embedder + Send + Sync>)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(unix)]
+fn sink_names(emitter: &Emitter) -> String {
+    let names: Vec<&str> = emitter.stats().iter().map(|(name, _)| *name).collect();
+    names.join(", ")
+}
+
+/// Fan out one packet, reporting sinks that gave up on it
+#[cfg(unix)]
+fn emit_basin(emitter: &mut Emitter, basin: &BasinFeedback) {
+    let dropped: Vec<u64> = emitter.stats().iter().map(|(_, s)| s.dropped).collect();
+    emitter.emit(basin);
+    for ((name, stats), before) in emitter.stats().into_iter().zip(dropped) {
+        if stats.dropped > before {
+            let error = stats.last_error.as_deref().unwrap_or("unknown error");
+            println!("Warning: {} dropped {}: {}", name, basin.basin_id, error);
+            if stats.disabled {
+                println!("Warning: {} disabled", name);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn print_unreachable(e: &std::io::Error) {
+    println!(
+        "Error: cannot reach daemon at {} ({})",
+        socket_path().display(),
+        e
+    );
+    println!("Start it with: sefi serve");
 }
@@ -1,8 +1,35 @@
 // N-D streaming density clustering (primary basin detection)
-
+//
+// DBSCAN-lite over the live window (entries whose tempo decay is still
+// above DECAY_FLOOR). Cluster ids carry over between ticks by member
+// overlap, so a basin keeps its id as it grows. Repel entries never join
+// clusters; they subtract mass from any cluster they land near. Urgent
+// entries the fast path takes (`urgent`) skip clustering; quieter or Repel
+// urgent entries are clustered like any other. Mature
+// clusters with a dense shared boundary are reported as ridges (`ridge`);
+// large, incoherent ones are flagged as peaks to decompose (`peak`).
+// Each tick also reports lifecycle events (birth, growth, merge, split,
+// decay) so a basin's history can be reconstructed.
+
+pub mod peak;
+pub mod ridge;
+pub mod urgent;
+
+use self::peak::{Peak, PeakConfig};
+use self::ridge::{RidgeConfig, RidgeTracker};
+use self::urgent::UrgentConfig;
+
+use crate::embed::normalize_vector;
+use crate::ledger::hnsw::Hnsw;
 use crate::ledger::store::Ledger;
-use crate::types::{LedgerEntry, Tempo};
-use std::collections::HashMap;
+use crate::types::{BasinType, LedgerEntry, Polarity, Tempo, ThresholdSnapshot};
+use crate::validator::medoid;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Decay weight below which an entry stops taking part in clustering
+const DECAY_FLOOR: f32 = 0.1;
 
 /// Cluster/Basin in N-D space
 #[derive(Debug, Clone)]
@@ -11,35 +38,189 @@ pub struct Cluster {
     pub members: Vec<String>, // rationale_hashes
     pub medoid_hash: String,
     pub medoid_phrase: String,
-    pub centroid: Vec<f32>, // diagnostic only
-    pub persistence: u32,    // ticks survived
-    pub tempo: Tempo,        // dominant tempo
-    pub last_update: u64,    // timestamp
+    pub centroid: Vec<f32>,   // diagnostic only
+    pub persistence: u32,     // ticks survived
+    pub mass: f32,            // decayed, amp-weighted, net of dissent (≥ 0)
+    pub dissent: Vec<String>, // live Repel hashes within epsilon of a member
+    pub dissenters: usize,    // distinct agent_ids behind `dissent`
+    pub tempo: Tempo,         // dominant tempo
+    pub peak: Option<Peak>,   // set when large and incoherent
+    pub lineage: Vec<String>, // ancestor ids from merges and splits
+    pub last_update: u64,     // timestamp
+}
+
+impl Cluster {
+    /// Peak when flagged as overloaded, Valley otherwise
+    pub fn basin_type(&self) -> BasinType {
+        if self.peak.is_some() {
+            BasinType::Peak
+        } else {
+            BasinType::Valley
+        }
+    }
+}
+
+/// DBSCAN label of a live entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointType {
+    Core,   // ≥ min_points neighbours within epsilon (itself included)
+    Border, // not core, but within epsilon of a core point
+    Noise,  // neither; belongs to no cluster
+}
+
+/// What happened to a cluster on a tick
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ClusterEvent {
+    Born { id: String },                         // no previous cluster overlaps
+    Grew { id: String, from: usize, to: usize }, // member count went up
+    Merged { from: Vec<String>, into: String },  // `from` ids absorbed into `into`
+    Split { from: String, into: Vec<String> },   // parts that left `from`
+    Decayed { id: String },                      // no member left in any cluster
+}
+
+/// A lifecycle event stamped with the tick that reported it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedEvent {
+    pub at: u64, // tick time (ms epoch)
+    #[serde(flatten)]
+    pub event: ClusterEvent,
+}
+
+/// Density and maturity parameters
+#[derive(Debug, Clone)]
+pub struct ClusterConfig {
+    pub epsilon: f32,         // max cosine distance (1 - similarity) between neighbours
+    pub min_points: usize,    // neighbourhood size (incl. the point) to be core
+    pub min_persistence: u32, // min ticks before emitting basin (Slow tempo)
+    pub min_persistence_fast: u32, // same for Fast-tempo clusters
+    pub min_mass: f32,        // min decayed mass for valid cluster
+    pub window_ms: u64,       // ledger window considered each tick
+    pub ann_threshold: usize, // live entries beyond which neighbours come from HNSW
+    pub ridge: RidgeConfig,   // boundary detection between mature clusters
+    pub peak: PeakConfig,     // overload detection and damping
+    pub urgent: UrgentConfig, // fast-path thresholds; entries it takes skip clustering
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            epsilon: 0.25,
+            min_points: 2,
+            min_persistence: 2, // at least 2 ticks
+            min_persistence_fast: 2,
+            min_mass: 0.8,      // ~two recent default-amp (0.5) signals
+            window_ms: 60_000,  // 60s window
+            ann_threshold: 512, // brute force is exact and cheap below this
+            ridge: RidgeConfig::default(),
+            peak: PeakConfig::default(),
+            urgent: UrgentConfig::default(),
+        }
+    }
+}
+
+impl ClusterConfig {
+    /// Ticks a cluster of `tempo` must survive before it is mature
+    pub fn persistence_min(&self, tempo: Tempo) -> u32 {
+        match tempo {
+            Tempo::Fast => self.min_persistence_fast,
+            Tempo::Slow | Tempo::Urgent => self.min_persistence,
+        }
+    }
+
+    /// Thresholds as reported on BasinFeedback for a basin of `tempo`
+    pub fn snapshot(&self, tempo: Tempo) -> ThresholdSnapshot {
+        ThresholdSnapshot {
+            persistence_min: self.persistence_min(tempo),
+            density_threshold: self.min_mass,
+            nd_min_members: self.min_points,
+            nd_radius: self.epsilon,
+            window_w: (self.window_ms / 1000) as u32, // seconds
+        }
+    }
 }
 
 /// Streaming clustering engine with two-tempo decay
+/// Incremental: each tick absorbs only entries appended since the last one
+/// (tracked by a ledger watermark) and drops entries that decayed away;
+/// neighbourhoods are kept between ticks so old pairs are never re-scored
 pub struct ClusterEngine {
     clusters: HashMap<String, Cluster>,
+    labels: HashMap<String, PointType>, // rationale_hash → label at last tick
+    live: BTreeMap<usize, BTreeSet<usize>>, // ledger position → neighbours (incl. itself)
+    repels: BTreeSet<usize>,            // live Repel entries (ledger positions)
+    index: Option<Hnsw>,                // live vectors by position (≥ ann_threshold only)
+    ridges: RidgeTracker,               // boundaries between mature clusters
+    events: Vec<ClusterEvent>,          // lifecycle events of the last tick
+    watermark: usize,                   // ledger entries already absorbed
+    rescore: Vec<usize>,                // live points to relink under a new epsilon
+    stale: bool,                        // thresholds changed since the last regroup
     cluster_counter: u32,
-
-    // Parameters
-    cosine_threshold: f32, // min similarity to join cluster
-    min_persistence: u32,  // min ticks before emitting basin
-    min_members: usize,    // min members for valid cluster
+    config: ClusterConfig,
 }
 
 impl ClusterEngine {
     pub fn new() -> Self {
+        Self::with_config(ClusterConfig::default())
+    }
+
+    pub fn with_config(config: ClusterConfig) -> Self {
         Self {
             clusters: HashMap::new(),
+            labels: HashMap::new(),
+            live: BTreeMap::new(),
+            repels: BTreeSet::new(),
+            index: None,
+            ridges: RidgeTracker::with_config(config.ridge.clone()),
+            events: Vec::new(),
+            watermark: 0,
+            rescore: Vec::new(),
+            stale: false,
             cluster_counter: 0,
-            cosine_threshold: 0.75, // reasonable default for semantic clustering
-            min_persistence: 2,     // at least 2 ticks
-            min_members: 2,         // at least 2 members
+            config,
         }
     }
 
-    /// Process new ledger entries and update clusters
+    pub fn config(&self) -> &ClusterConfig {
+        &self.config
+    }
+
+    /// Replace the thresholds (e.g. from the governor); applied next tick
+    /// A new epsilon invalidates the cached neighbourhoods, so the live
+    /// points (and only those) are re-scored on the next tick
+    pub fn set_config(&mut self, config: ClusterConfig) {
+        if config.epsilon != self.config.epsilon {
+            self.rescore.extend(self.live.keys());
+            self.live.clear();
+            self.index = None;
+        }
+        self.ridges.set_config(config.ridge.clone());
+        self.config = config;
+        self.stale = true;
+    }
+
+    /// Attracting entries currently in the live window
+    pub fn live_points(&self) -> usize {
+        self.live.len()
+    }
+
+    /// Ledger positions of the attracting entries in the live window
+    pub fn live(&self) -> impl Iterator<Item = usize> + '_ {
+        self.live.keys().copied()
+    }
+
+    /// Ledger position up to which entries have been absorbed
+    pub fn watermark(&self) -> usize {
+        self.watermark
+    }
+
+    /// Live points in the HNSW index (0 while the window is scanned exactly)
+    pub fn indexed(&self) -> usize {
+        self.index.as_ref().map_or(0, |index| index.len())
+    }
+
+    /// Absorb new ledger entries, expire decayed ones and update clusters
+    /// Always pass the same ledger: points are tracked by ledger position
     /// Returns list of mature cluster IDs ready for basin feedback
     pub fn tick(
         &mut self,
@@ -47,103 +228,381 @@ impl ClusterEngine {
         current_time: u64,
         phrases: &HashMap<String, String>, // rationale_hash -> phrase
     ) -> Vec<String> {
-        // Apply decay to existing clusters
-        self.apply_decay(current_time);
+        let entries = ledger.entries();
+        self.events.clear();
+
+        // Two-tempo decay: Fast entries drop out long before Slow ones
+        let expired: Vec<usize> = self
+            .live
+            .keys()
+            .filter(|&&i| !self.is_live(&entries[i], current_time))
+            .copied()
+            .collect();
+        for i in &expired {
+            if let Some(index) = &mut self.index {
+                index.remove(*i);
+            }
+            for j in self.live.remove(i).unwrap_or_default() {
+                if let Some(neighbours) = self.live.get_mut(&j) {
+                    neighbours.remove(i);
+                }
+            }
+        }
+        // Small windows are cheaper to scan than to index
+        if self.live.len() < self.config.ann_threshold / 2 {
+            self.index = None;
+        }
+        let faded: Vec<usize> = self
+            .repels
+            .iter()
+            .filter(|&&i| !self.is_live(&entries[i], current_time))
+            .copied()
+            .collect();
+        for i in &faded {
+            self.repels.remove(i);
+        }
 
-        // Get recent entries (within reasonable window)
-        let window_ms = 60_000; // 60s window
-        let recent = ledger.recent_window(window_ms, current_time);
+        // Live points from before an epsilon change are linked afresh
+        let mut absorbed = 0;
+        for i in std::mem::take(&mut self.rescore) {
+            if self.is_live(&entries[i], current_time) {
+                self.absorb(i, entries);
+                absorbed += 1;
+            }
+        }
 
-        // For each entry, assign to nearest cluster or create new
-        for entry in recent.iter() {
-            self.assign_or_create(entry, phrases);
+        // Only entries appended since the last tick are scored
+        let fresh = ledger.since(self.watermark);
+        let start = self.watermark;
+        self.watermark = ledger.watermark();
+        for (offset, entry) in fresh.iter().enumerate() {
+            // The urgent fast path already handled these
+            if self.config.urgent.takes(entry) || !self.is_live(entry, current_time) {
+                continue;
+            }
+            match entry.polarity {
+                Polarity::Attract => {
+                    self.absorb(start + offset, entries);
+                    absorbed += 1;
+                }
+                Polarity::Repel => {
+                    self.repels.insert(start + offset); // affects mass, not shape
+                }
+            }
         }
 
+        if expired.is_empty() && absorbed == 0 && !self.stale {
+            // Nothing moved: every cluster simply survives this tick
+            for cluster in self.clusters.values_mut() {
+                cluster.persistence += 1;
+            }
+        } else {
+            let groups = self.regroup(entries);
+            self.track(&groups, entries, phrases, current_time);
+            self.stale = false;
+        }
+        self.update_mass(ledger, current_time);
+
         // Find mature clusters ready for emission
-        self.find_mature_clusters()
+        let mature = self.find_mature_clusters();
+
+        // Ridges only form between clusters that are themselves mature
+        let endpoints: Vec<&Cluster> = mature.iter().map(|id| &self.clusters[id]).collect();
+        let points: Vec<&LedgerEntry> = self.live.keys().map(|&i| &entries[i]).collect();
+        self.ridges
+            .update(&endpoints, &points, ledger, self.config.epsilon);
+
+        mature
     }
 
-    /// Apply two-tempo decay to cluster persistence
-    fn apply_decay(&mut self, current_time: u64) {
-        let mut to_remove = Vec::new();
+    /// Inside the window and not yet decayed below the floor
+    fn is_live(&self, entry: &LedgerEntry, now: u64) -> bool {
+        now.saturating_sub(entry.timestamp) <= self.config.window_ms
+            && decay_weight(entry, now) >= DECAY_FLOOR
+    }
+
+    /// Add one entry to the live set, linking it with its epsilon-neighbours
+    /// Small windows are scanned exactly; from `ann_threshold` live points on,
+    /// an HNSW index is built and kept until the window shrinks to half that
+    fn absorb(&mut self, i: usize, entries: &[LedgerEntry]) {
+        let vector = &entries[i].vector;
+        if self.index.is_none() && self.live.len() >= self.config.ann_threshold {
+            let mut index = Hnsw::default();
+            for &j in self.live.keys() {
+                index.insert(j, entries[j].vector.clone());
+            }
+            self.index = Some(index);
+        }
+        let found: Vec<usize> = match &self.index {
+            Some(index) => index
+                .within(vector, self.config.epsilon)
+                .into_iter()
+                .map(|(j, _)| j)
+                .collect(),
+            None => self
+                .live
+                .keys()
+                .copied()
+                .filter(|&j| {
+                    1.0 - cosine_similarity(vector, &entries[j].vector) <= self.config.epsilon
+                })
+                .collect(),
+        };
 
-        for (id, cluster) in self.clusters.iter_mut() {
-            let dt = (current_time - cluster.last_update) as f32 / 1000.0; // seconds
+        let mut neighbours = BTreeSet::from([i]);
+        for j in found {
+            if let Some(others) = self.live.get_mut(&j) {
+                others.insert(i);
+                neighbours.insert(j);
+            }
+        }
+        self.live.insert(i, neighbours);
+        if let Some(index) = &mut self.index {
+            index.insert(i, vector.clone());
+        }
+    }
 
-            // Decay weight based on tempo
-            let tau = cluster.tempo.tau();
-            if tau > 0.0 {
-                let decay = (-dt / tau).exp();
+    /// Group live points into density-connected clusters (ledger positions,
+    /// ascending) and relabel every live point as core, border or noise
+    fn regroup(&mut self, entries: &[LedgerEntry]) -> Vec<Vec<usize>> {
+        let min_points = self.config.min_points;
+        let core = |neighbours: &BTreeSet<usize>| neighbours.len() >= min_points;
+
+        let mut group_of: HashMap<usize, usize> = HashMap::new();
+        let mut groups = Vec::new();
+        for (&seed, neighbours) in &self.live {
+            if !core(neighbours) || group_of.contains_key(&seed) {
+                continue;
+            }
 
-                // If decayed below threshold, mark for removal
-                if decay < 0.1 {
-                    to_remove.push(id.clone());
+            // Expand from an unvisited core point
+            let g = groups.len();
+            let mut members = Vec::new();
+            let mut queue = vec![seed];
+            group_of.insert(seed, g);
+            while let Some(i) = queue.pop() {
+                members.push(i);
+                if !core(&self.live[&i]) {
+                    continue; // border points join but don't expand
+                }
+                for &j in &self.live[&i] {
+                    if let Entry::Vacant(slot) = group_of.entry(j) {
+                        slot.insert(g);
+                        queue.push(j);
+                    }
                 }
             }
-            // Urgent never decays (but we process them immediately anyway)
+            members.sort_unstable();
+            groups.push(members);
         }
 
-        // Remove decayed clusters
-        for id in to_remove {
-            self.clusters.remove(&id);
-        }
+        self.labels = self
+            .live
+            .iter()
+            .map(|(i, neighbours)| {
+                let label = match (core(neighbours), group_of.contains_key(i)) {
+                    (true, _) => PointType::Core,
+                    (false, true) => PointType::Border,
+                    (false, false) => PointType::Noise,
+                };
+                (entries[*i].rationale_hash.clone(), label)
+            })
+            .collect();
+
+        groups
     }
 
-    /// Assign entry to nearest cluster or create new cluster
-    fn assign_or_create(
+    /// Replace clusters with this tick's groups, keeping ids by member overlap
+    /// Largest overlaps claim ids first; on a split the bigger part keeps
+    /// the id, on a merge the side contributing most members does
+    /// Records the lifecycle events implied by the overlaps
+    fn track(
         &mut self,
-        entry: &LedgerEntry,
+        groups: &[Vec<usize>],
+        entries: &[LedgerEntry],
         phrases: &HashMap<String, String>,
+        now: u64,
     ) {
-        // Find nearest cluster
-        let mut best_sim = self.cosine_threshold;
-        let mut best_cluster_id: Option<String> = None;
+        let previous = std::mem::take(&mut self.clusters);
+
+        let mut pairs: Vec<(usize, usize, &str)> = Vec::new(); // (overlap, group, old id)
+        for (id, cluster) in previous.iter() {
+            let old: HashSet<&str> = cluster.members.iter().map(|h| h.as_str()).collect();
+            for (g, members) in groups.iter().enumerate() {
+                let overlap = members
+                    .iter()
+                    .filter(|&&i| old.contains(entries[i].rationale_hash.as_str()))
+                    .count();
+                if overlap > 0 {
+                    pairs.push((overlap, g, id.as_str()));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(b.2)));
 
-        for (id, cluster) in self.clusters.iter() {
-            // Compute similarity to cluster centroid
-            let sim = cosine_similarity(&entry.vector, &cluster.centroid);
+        let mut sources: Vec<BTreeSet<&str>> = vec![BTreeSet::new(); groups.len()];
+        let mut targets: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for &(_, g, id) in &pairs {
+            sources[g].insert(id);
+            targets.entry(id).or_default().push(g);
+        }
 
-            if sim > best_sim {
-                best_sim = sim;
-                best_cluster_id = Some(id.clone());
+        let mut inherited: Vec<Option<&Cluster>> = vec![None; groups.len()];
+        let mut claimed = HashSet::new();
+        for &(_, g, id) in &pairs {
+            if inherited[g].is_none() && claimed.insert(id) {
+                inherited[g] = previous.get(id);
             }
         }
 
-        // Assign to cluster or create new
-        if let Some(cluster_id) = best_cluster_id {
-            // Add to existing cluster
-            if let Some(cluster) = self.clusters.get_mut(&cluster_id) {
-                if !cluster.members.contains(&entry.rationale_hash) {
-                    cluster.members.push(entry.rationale_hash.clone());
-                    cluster.persistence += 1;
-                    cluster.last_update = entry.timestamp;
+        let mut ids = Vec::with_capacity(groups.len());
+        for (g, (members, prev)) in groups.iter().zip(&inherited).enumerate() {
+            let members: Vec<&LedgerEntry> = members.iter().map(|&i| &entries[i]).collect();
+            let mut cluster = self.build_cluster(&members, *prev, phrases, now);
+
+            // Ancestors: whatever this id descended from, plus every other
+            // cluster that contributed members (merged in or split from)
+            let mut lineage = prev.map(|p| p.lineage.clone()).unwrap_or_default();
+            for &source in &sources[g] {
+                if source != cluster.id {
+                    lineage.extend(previous[source].lineage.iter().cloned());
+                    lineage.push(source.to_string());
+                }
+            }
+            let mut seen = HashSet::new();
+            lineage.retain(|id| seen.insert(id.clone()));
+            cluster.lineage = lineage;
+
+            ids.push(cluster.id.clone());
+            self.clusters.insert(cluster.id.clone(), cluster);
+        }
 
-                    // Recompute centroid (simple average)
-                    // In production, this would be more sophisticated
+        let mut old_ids: Vec<&str> = previous.keys().map(|id| id.as_str()).collect();
+        old_ids.sort_unstable();
+        for id in old_ids {
+            match targets.get(id) {
+                None => self
+                    .events
+                    .push(ClusterEvent::Decayed { id: id.to_string() }),
+                Some(parts) if parts.len() > 1 => self.events.push(ClusterEvent::Split {
+                    from: id.to_string(),
+                    into: parts
+                        .iter()
+                        .map(|&g| ids[g].clone())
+                        .filter(|part| part != id)
+                        .collect(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (g, id) in ids.iter().enumerate() {
+            if sources[g].is_empty() {
+                self.events.push(ClusterEvent::Born { id: id.clone() });
+                continue;
+            }
+            if sources[g].len() > 1 {
+                self.events.push(ClusterEvent::Merged {
+                    from: sources[g]
+                        .iter()
+                        .filter(|&&source| source != id)
+                        .map(|source| source.to_string())
+                        .collect(),
+                    into: id.clone(),
+                });
+            }
+            if let Some(prev) = inherited[g] {
+                if groups[g].len() > prev.members.len() {
+                    self.events.push(ClusterEvent::Grew {
+                        id: id.clone(),
+                        from: prev.members.len(),
+                        to: groups[g].len(),
+                    });
                 }
             }
-        } else {
-            // Create new cluster
-            let cluster_id = format!("cluster_{}", self.cluster_counter);
-            self.cluster_counter += 1;
-
-            let phrase = phrases
-                .get(&entry.rationale_hash)
-                .cloned()
-                .unwrap_or_else(|| "unknown".to_string());
-
-            let cluster = Cluster {
-                id: cluster_id.clone(),
-                members: vec![entry.rationale_hash.clone()],
-                medoid_hash: entry.rationale_hash.clone(),
-                medoid_phrase: phrase,
-                centroid: entry.vector.clone(),
-                persistence: 1,
-                tempo: entry.tempo,
-                last_update: entry.timestamp,
-            };
+        }
+    }
+
+    /// Cluster record for one group, continuing `previous` if it matched
+    fn build_cluster(
+        &mut self,
+        entries: &[&LedgerEntry],
+        previous: Option<&Cluster>,
+        phrases: &HashMap<String, String>,
+        now: u64,
+    ) -> Cluster {
+        let members: Vec<String> = entries.iter().map(|e| e.rationale_hash.clone()).collect();
+
+        // Persistence counts ticks survived, however many members joined
+        let (id, persistence) = match previous {
+            Some(prev) => (prev.id.clone(), prev.persistence + 1),
+            None => {
+                let id = format!("cluster_{}", self.cluster_counter);
+                self.cluster_counter += 1;
+                (id, 1)
+            }
+        };
 
-            self.clusters.insert(cluster_id, cluster);
+        let peak_since = previous
+            .and_then(|p| p.peak.as_ref())
+            .map_or(now, |p| p.since);
+        let m = medoid_index(entries);
+        let medoid = entries[m];
+        let medoid_phrase = phrases
+            .get(&medoid.rationale_hash)
+            .cloned()
+            .unwrap_or_else(|| "unknown".to_string());
+
+        Cluster {
+            id,
+            medoid_hash: medoid.rationale_hash.clone(),
+            medoid_phrase,
+            centroid: centroid(entries),
+            persistence,
+            mass: 0.0, // set by update_mass
+            dissent: Vec::new(),
+            dissenters: 0,
+            tempo: dominant_tempo(entries),
+            peak: self.config.peak.classify(entries, m, peak_since),
+            lineage: Vec::new(), // set by track
+            last_update: entries.iter().map(|e| e.timestamp).max().unwrap_or(0),
+            members,
+        }
+    }
+
+    /// Recompute every cluster's mass at `now` (decay moves it every tick)
+    /// Repel entries near a cluster count as dissent and subtract their mass
+    fn update_mass(&mut self, ledger: &Ledger, now: u64) {
+        let epsilon = self.config.epsilon;
+        let repels: Vec<&LedgerEntry> = self.repels.iter().map(|&i| &ledger.entries()[i]).collect();
+
+        for cluster in self.clusters.values_mut() {
+            let Some(medoid) = ledger.get(&cluster.medoid_hash) else {
+                continue;
+            };
+            let members = ledger.get_batch(&cluster.members);
+            let dissent: Vec<&LedgerEntry> = repels
+                .iter()
+                .copied()
+                .filter(|r| {
+                    members
+                        .iter()
+                        .any(|m| 1.0 - cosine_similarity(&r.vector, &m.vector) <= epsilon)
+                })
+                .collect();
+
+            let support: f32 = members
+                .iter()
+                .map(|e| member_mass(e, &medoid.vector, now, epsilon))
+                .sum();
+            let against: f32 = dissent
+                .iter()
+                .map(|e| member_mass(e, &medoid.vector, now, epsilon))
+                .sum();
+
+            cluster.mass = (support - against).max(0.0);
+            cluster.dissent = dissent.iter().map(|e| e.rationale_hash.clone()).collect();
+            let agents: HashSet<&str> = dissent.iter().map(|e| e.agent_id.as_str()).collect();
+            cluster.dissenters = agents.len();
         }
     }
 
@@ -152,117 +611,147 @@ impl ClusterEngine {
         self.clusters
             .iter()
             .filter(|(_, c)| {
-                c.persistence >= self.min_persistence && c.members.len() >= self.min_members
+                c.persistence >= self.config.persistence_min(c.tempo)
+                    && c.mass >= self.config.min_mass
             })
             .map(|(id, _)| id.clone())
             .collect()
     }
 
-    /// Get cluster by ID
-    pub fn get_cluster(&self, id: &str) -> Option<&Cluster> {
-        self.clusters.get(id)
+    /// DBSCAN label of an entry at the last tick (None if not in the live window)
+    pub fn point_type(&self, rationale_hash: &str) -> Option<PointType> {
+        self.labels.get(rationale_hash).copied()
     }
 
-    /// Compute medoid for a cluster
-    /// Medoid = member with minimum sum of distances to all other members
-    pub fn compute_medoid(
-        &self,
-        cluster_id: &str,
-        ledger: &Ledger,
-    ) -> Option<String> {
-        let cluster = self.clusters.get(cluster_id)?;
-
-        if cluster.members.is_empty() {
-            return None;
+    /// Amplitude multiplier for a new packet at `vector`: `damp_factor`
+    /// inside a peak still within its damping window, 1.0 elsewhere
+    pub fn damping(&self, ledger: &Ledger, vector: &[f32], now: u64) -> f32 {
+        let Some(window) = self.config.peak.damp_ms else {
+            return 1.0;
+        };
+        let damped = self.clusters.values().any(|c| {
+            let Some(peak) = &c.peak else {
+                return false;
+            };
+            let Some(medoid) = ledger.get(&c.medoid_hash) else {
+                return false;
+            };
+            now <= peak.since + window
+                && 1.0 - cosine_similarity(vector, &medoid.vector) <= peak.radius
+        });
+        if damped {
+            self.config.peak.damp_factor
+        } else {
+            1.0
         }
+    }
 
-        // Get all member vectors
-        let member_entries: Vec<&LedgerEntry> = cluster
-            .members
-            .iter()
-            .filter_map(|hash| ledger.get(hash))
-            .collect();
+    /// Lifecycle events from the last tick, in a stable order
+    pub fn events(&self) -> &[ClusterEvent] {
+        &self.events
+    }
 
-        if member_entries.is_empty() {
-            return None;
-        }
+    /// Ridges between mature clusters as of the last tick
+    pub fn ridges(&self) -> &RidgeTracker {
+        &self.ridges
+    }
 
-        // Find medoid (member with min total distance to others)
-        let mut best_hash = cluster.members[0].clone();
-        let mut min_total_dist = f32::MAX;
+    /// Get cluster by ID
+    pub fn get_cluster(&self, id: &str) -> Option<&Cluster> {
+        self.clusters.get(id)
+    }
 
-        for candidate in member_entries.iter() {
-            let mut total_dist = 0.0;
+    /// All live clusters (unordered)
+    pub fn clusters(&self) -> impl Iterator<Item = &Cluster> {
+        self.clusters.values()
+    }
 
-            for other in member_entries.iter() {
-                if candidate.rationale_hash != other.rationale_hash {
-                    let sim = cosine_similarity(&candidate.vector, &other.vector);
-                    // Distance = 1 - similarity
-                    total_dist += 1.0 - sim;
-                }
-            }
+    /// Number of live clusters
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
 
-            if total_dist < min_total_dist {
-                min_total_dist = total_dist;
-                best_hash = candidate.rationale_hash.clone();
-            }
-        }
+    /// Check if there are no live clusters
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+}
 
-        Some(best_hash)
+impl Default for ClusterEngine {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    /// Compute cohesion (simplified silhouette score)
-    /// Returns average similarity within cluster
-    pub fn compute_cohesion(
-        &self,
-        cluster_id: &str,
-        ledger: &Ledger,
-    ) -> f32 {
-        let cluster = match self.clusters.get(cluster_id) {
-            Some(c) => c,
-            None => return 0.0,
-        };
+/// Tempo decay weight of an entry at `now` (Urgent never decays)
+pub fn decay_weight(entry: &LedgerEntry, now: u64) -> f32 {
+    let tau = entry.tempo.tau();
+    if tau <= 0.0 {
+        return 1.0;
+    }
+    let dt = now.saturating_sub(entry.timestamp) as f32 / 1000.0; // seconds
+    (-dt / tau).exp()
+}
 
-        if cluster.members.len() < 2 {
-            return 1.0; // single member = perfect cohesion
-        }
+/// Mass one member adds to a cluster centred on `center` at `now`:
+/// amp · exp(-dt/τ) · exp(-d²/2(σε)²), d = cosine distance to the center
+/// A larger sigma widens the kernel, so a far member still counts fully
+pub fn member_mass(entry: &LedgerEntry, center: &[f32], now: u64, epsilon: f32) -> f32 {
+    let d = 1.0 - cosine_similarity(&entry.vector, center);
+    let width = (entry.sigma * epsilon).max(f32::EPSILON);
+    entry.amp * decay_weight(entry, now) * (-(d * d) / (2.0 * width * width)).exp()
+}
 
-        let member_entries: Vec<&LedgerEntry> = cluster
-            .members
-            .iter()
-            .filter_map(|hash| ledger.get(hash))
-            .collect();
+/// Medoid = member nearest the Fréchet mean, as the validator reports it
+fn medoid_index(entries: &[&LedgerEntry]) -> usize {
+    medoid::nearest(entries, &medoid::frechet_mean(entries)).unwrap_or(0)
+}
 
-        if member_entries.len() < 2 {
-            return 0.0;
+/// Unit-normalized mean of member vectors
+fn centroid(entries: &[&LedgerEntry]) -> Vec<f32> {
+    let mut sum = vec![0.0; entries[0].vector.len()];
+    for entry in entries {
+        for (s, x) in sum.iter_mut().zip(&entry.vector) {
+            *s += x;
         }
+    }
+    normalize_vector(&mut sum);
+    sum
+}
 
-        // Compute average pairwise similarity
-        let mut total_sim = 0.0;
-        let mut count = 0;
-
-        for i in 0..member_entries.len() {
-            for j in (i + 1)..member_entries.len() {
-                let sim = cosine_similarity(
-                    &member_entries[i].vector,
-                    &member_entries[j].vector,
-                );
-                total_sim += sim;
-                count += 1;
-            }
+/// Most common tempo among members (ties go to the earliest seen)
+fn dominant_tempo(entries: &[&LedgerEntry]) -> Tempo {
+    let mut counts: Vec<(Tempo, usize)> = Vec::new();
+    for entry in entries {
+        match counts.iter_mut().find(|(t, _)| *t == entry.tempo) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((entry.tempo, 1)),
         }
+    }
 
-        if count > 0 {
-            total_sim / count as f32
-        } else {
-            0.0
+    let mut best = counts[0];
+    for &(tempo, n) in &counts[1..] {
+        if n > best.1 {
+            best = (tempo, n);
         }
     }
+    best.0
 }
 
-impl Default for ClusterEngine {
-    fn default() -> Self {
-        Self::new()
+/// Average pairwise cosine similarity (1.0 for fewer than two entries)
+fn mean_similarity(entries: &[&LedgerEntry]) -> f32 {
+    let mut total = 0.0;
+    let mut pairs = 0;
+    for (i, a) in entries.iter().enumerate() {
+        for b in &entries[i + 1..] {
+            total += cosine_similarity(&a.vector, &b.vector);
+            pairs += 1;
+        }
+    }
+    if pairs > 0 {
+        total / pairs as f32
+    } else {
+        1.0
     }
 }
 
@@ -275,6 +764,71 @@ fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{ConceptPacket, Polarity};
+
+    /// Slow, attracting amp-0.8 packet whose phrase is its hash
+    fn packet_at(hash: &str, timestamp: u64) -> ConceptPacket {
+        ConceptPacket {
+            phrase: hash.to_string(),
+            amp: 0.8,
+            sigma: 1.0,
+            polarity: Polarity::Attract,
+            tempo: Tempo::Slow,
+            provenance: "test".to_string(),
+            agent_id: "agent1".to_string(),
+            rationale_hash: hash.to_string(),
+            timestamp,
+            center: None,
+            model: None,
+        }
+    }
+
+    /// Append `packet` with a 2-d unit vector at `degrees`
+    fn append_at(
+        ledger: &mut Ledger,
+        phrases: &mut HashMap<String, String>,
+        packet: ConceptPacket,
+        degrees: f32,
+    ) {
+        let rad = degrees.to_radians();
+        phrases.insert(packet.rationale_hash.clone(), packet.phrase.clone());
+        ledger.append(packet, vec![rad.cos(), rad.sin()]).unwrap();
+    }
+
+    fn place(
+        ledger: &mut Ledger,
+        phrases: &mut HashMap<String, String>,
+        hash: &str,
+        degrees: f32,
+        timestamp: u64,
+    ) {
+        append_at(ledger, phrases, packet_at(hash, timestamp), degrees);
+    }
+
+    fn place_weighted(
+        ledger: &mut Ledger,
+        phrases: &mut HashMap<String, String>,
+        hash: &str,
+        degrees: f32,
+        timestamp: u64,
+        amp: f32,
+        sigma: f32,
+    ) {
+        let packet = ConceptPacket {
+            amp,
+            sigma,
+            ..packet_at(hash, timestamp)
+        };
+        append_at(ledger, phrases, packet, degrees);
+    }
+
+    fn engine_with(epsilon: f32, min_points: usize) -> ClusterEngine {
+        ClusterEngine::with_config(ClusterConfig {
+            epsilon,
+            min_points,
+            ..Default::default()
+        })
+    }
 
     #[test]
     fn test_cosine_similarity() {
@@ -317,11 +871,13 @@ mod tests {
             agent_id: "agent1".to_string(),
             rationale_hash: "hash_fast".to_string(),
             timestamp: 0,
+            center: None,
+            model: None,
         };
 
         let vector_fast = embed.embed(&packet_fast.phrase);
         phrases.insert("hash_fast".to_string(), packet_fast.phrase.clone());
-        ledger.append(packet_fast, vector_fast);
+        ledger.append(packet_fast, vector_fast).unwrap();
 
         // Add Slow tempo packet at t=0
         let packet_slow = ConceptPacket {
@@ -334,11 +890,16 @@ mod tests {
             agent_id: "agent2".to_string(),
             rationale_hash: "hash_slow".to_string(),
             timestamp: 0,
+            center: None,
+            model: None,
         };
 
         let vector_slow = embed.embed(&packet_slow.phrase);
         phrases.insert("hash_slow".to_string(), packet_slow.phrase.clone());
-        ledger.append(packet_slow, vector_slow);
+        ledger.append(packet_slow, vector_slow).unwrap();
+
+        // Singletons count as clusters here
+        engine.config.min_points = 1;
 
         // Process at t=0
         engine.tick(&ledger, 0, &phrases);
@@ -358,97 +919,456 @@ mod tests {
     }
 
     #[test]
-    fn test_medoid_computation() {
-        use crate::embed::EmbedService;
-        use crate::types::{ConceptPacket, Polarity};
+    fn test_medoid_matches_validator() {
+        let mut engine = engine_with(0.5, 2);
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
 
-        let mut engine = ClusterEngine::new();
+        // Mean of 0°, 10°, 20° and 60° is ~22°: the validator picks 20°
+        for (hash, deg) in [("a0", 0.0), ("a1", 10.0), ("a2", 20.0), ("a3", 60.0)] {
+            place(&mut ledger, &mut phrases, hash, deg, 1000);
+        }
+        engine.tick(&ledger, 1000, &phrases);
+
+        let cluster = engine.clusters().next().unwrap();
+        let basin = crate::validator::validate_basin(&cluster.members, &ledger, &[]).unwrap();
+        assert_eq!(cluster.medoid_hash, basin.medoid);
+        assert_eq!(cluster.medoid_hash, "a2");
+    }
+
+    #[test]
+    fn test_dbscan_finds_dense_clusters() {
+        let mut engine = engine_with(0.01, 3);
         let mut ledger = Ledger::new();
         let mut phrases = HashMap::new();
-        let embed = EmbedService::new();
 
-        // Create a cluster with 3 similar concepts
-        let concepts = [
-            "memory safety",
-            "borrow checker",
-            "zero cost abstractions",
-        ];
+        // Tight group of five within ~4°, two isolated points
+        for (i, deg) in [0.0, 1.0, 2.0, 3.0, 4.0].iter().enumerate() {
+            let hash = format!("dense{}", i);
+            place(&mut ledger, &mut phrases, &hash, *deg, 1000);
+        }
+        place(&mut ledger, &mut phrases, "noise0", 60.0, 1000);
+        place(&mut ledger, &mut phrases, "noise1", 120.0, 1000);
+
+        // Found on the first tick, mature once it survives a second
+        assert!(engine.tick(&ledger, 1000, &phrases).is_empty());
+        assert_eq!(engine.len(), 1);
+        let mature = engine.tick(&ledger, 1100, &phrases);
+        assert_eq!(mature.len(), 1);
+
+        let cluster = engine.get_cluster(&mature[0]).unwrap();
+        assert_eq!(cluster.members.len(), 5);
+        assert_eq!(cluster.medoid_hash, "dense2");
+        assert_eq!(engine.point_type("noise0"), Some(PointType::Noise));
+        assert_eq!(engine.point_type("noise1"), Some(PointType::Noise));
+    }
 
-        for (i, phrase) in concepts.iter().enumerate() {
-            let packet = ConceptPacket {
-                phrase: phrase.to_string(),
-                amp: 0.8,
-                sigma: 1.0,
-                polarity: Polarity::Attract,
-                tempo: Tempo::Slow,
-                provenance: "test".to_string(),
-                agent_id: format!("agent{}", i),
-                rationale_hash: format!("hash{}", i),
-                timestamp: 1000,
-            };
+    #[test]
+    fn test_core_vs_border_classification() {
+        // eps 0.02 ≈ 11.5°: 0-8, 8-16 and 16-26 are neighbours, nothing else
+        let mut engine = engine_with(0.02, 3);
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
 
-            let vector = embed.embed(phrase);
-            phrases.insert(format!("hash{}", i), phrase.to_string());
-            ledger.append(packet, vector);
+        place(&mut ledger, &mut phrases, "a", 0.0, 1000);
+        place(&mut ledger, &mut phrases, "b", 8.0, 1000);
+        place(&mut ledger, &mut phrases, "c", 16.0, 1000);
+        place(&mut ledger, &mut phrases, "d", 26.0, 1000);
+        place(&mut ledger, &mut phrases, "far", 90.0, 1000);
+
+        engine.tick(&ledger, 1000, &phrases);
+
+        assert_eq!(engine.point_type("a"), Some(PointType::Border));
+        assert_eq!(engine.point_type("b"), Some(PointType::Core));
+        assert_eq!(engine.point_type("c"), Some(PointType::Core));
+        assert_eq!(engine.point_type("d"), Some(PointType::Border));
+        assert_eq!(engine.point_type("far"), Some(PointType::Noise));
+
+        assert_eq!(engine.len(), 1);
+        let cluster = engine.clusters().next().unwrap();
+        assert_eq!(cluster.members, vec!["a", "b", "c", "d"]);
+    }
+
+    #[test]
+    fn test_epsilon_sensitivity() {
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
+        for (hash, deg) in [("a", 0.0), ("b", 5.0), ("c", 30.0), ("d", 35.0)] {
+            place(&mut ledger, &mut phrases, hash, deg, 1000);
         }
 
-        // Force all into same cluster by using very low threshold
-        engine.cosine_threshold = -1.0; // accept all (cosine similarity ranges from -1 to 1)
+        let mut tight = engine_with(0.01, 2);
+        tight.tick(&ledger, 1000, &phrases);
+        assert_eq!(tight.len(), 2);
+
+        let mut loose = engine_with(0.5, 2);
+        loose.tick(&ledger, 1000, &phrases);
+        assert_eq!(loose.len(), 1);
+
+        let mut strict = engine_with(0.0001, 2);
+        strict.tick(&ledger, 1000, &phrases);
+        assert!(strict.is_empty(), "no neighbours → all noise");
+    }
+
+    #[test]
+    fn test_cluster_id_stable_across_ticks() {
+        let mut engine = engine_with(0.01, 2);
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
+
+        place(&mut ledger, &mut phrases, "a", 0.0, 1000);
+        place(&mut ledger, &mut phrases, "b", 2.0, 1000);
+        place(&mut ledger, &mut phrases, "x", 90.0, 1000);
+        place(&mut ledger, &mut phrases, "y", 92.0, 1000);
         engine.tick(&ledger, 1000, &phrases);
+        let id_of = |engine: &ClusterEngine, hash: &str| {
+            engine
+                .clusters()
+                .find(|c| c.members.iter().any(|m| m == hash))
+                .map(|c| c.id.clone())
+        };
+        let first = id_of(&engine, "a").unwrap();
+        let other = id_of(&engine, "x").unwrap();
 
-        // May have multiple clusters depending on similarity
-        // Just verify we have at least one cluster
-        assert!(!engine.clusters.is_empty());
+        // A third member joins; the cluster keeps its id
+        place(&mut ledger, &mut phrases, "c", 1.0, 1100);
+        engine.tick(&ledger, 1100, &phrases);
 
-        let cluster_id = engine.clusters.keys().next().unwrap().clone();
+        assert_eq!(id_of(&engine, "c"), Some(first.clone()));
+        assert_eq!(id_of(&engine, "x"), Some(other));
+        assert_eq!(engine.get_cluster(&first).unwrap().members.len(), 3);
+    }
+
+    #[test]
+    fn test_tick_absorbs_only_new_entries() {
+        let mut engine = engine_with(0.01, 2);
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
+
+        place(&mut ledger, &mut phrases, "a", 0.0, 1000);
+        place(&mut ledger, &mut phrases, "b", 1.0, 1000);
+        engine.tick(&ledger, 1000, &phrases);
+        assert_eq!(engine.watermark(), 2);
+
+        // Quiet ticks: nothing re-scored, persistence counts ticks survived
+        for t in 1..=3 {
+            engine.tick(&ledger, 1000 + t * 100, &phrases);
+        }
+        let cluster = engine.clusters().next().unwrap();
+        assert_eq!(cluster.persistence, 4);
+        assert_eq!(engine.watermark(), 2);
+
+        // A new member joins without resetting persistence
+        place(&mut ledger, &mut phrases, "c", 2.0, 1400);
+        engine.tick(&ledger, 1400, &phrases);
+        let cluster = engine.clusters().next().unwrap();
+        assert_eq!(cluster.members.len(), 3);
+        assert_eq!(cluster.persistence, 5);
+        assert_eq!(engine.watermark(), 3);
+
+        // Appended already decayed: skipped, never revisited
+        place(&mut ledger, &mut phrases, "stale", 3.0, 0);
+        engine.tick(&ledger, 200_000, &phrases);
+        assert_eq!(engine.point_type("stale"), None);
+        assert!(engine.is_empty(), "slow entries decayed after 200s");
+    }
 
-        // Compute medoid
-        let medoid_hash = engine.compute_medoid(&cluster_id, &ledger);
-        assert!(medoid_hash.is_some(), "Should find a medoid");
+    #[test]
+    fn test_new_epsilon_rescores_only_the_live_window() {
+        let mut engine = engine_with(0.01, 2); // ~8° neighbourhoods
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
 
-        // Verify medoid is one of the members
-        let cluster = engine.get_cluster(&cluster_id).unwrap();
-        assert!(cluster.members.contains(&medoid_hash.unwrap()));
+        place(&mut ledger, &mut phrases, "old", 0.0, 0);
+        place(&mut ledger, &mut phrases, "a", 0.0, 100_000);
+        place(&mut ledger, &mut phrases, "b", 1.0, 100_000);
+        place(&mut ledger, &mut phrases, "far", 15.0, 100_000);
+        engine.tick(&ledger, 100_000, &phrases);
+        assert_eq!(engine.point_type("far"), Some(PointType::Noise));
+        assert_eq!(engine.live_points(), 3, "old is outside the window");
+
+        // ~18° neighbourhoods: far joins, old stays out, nothing re-read
+        let config = ClusterConfig {
+            epsilon: 0.05,
+            ..engine.config().clone()
+        };
+        engine.set_config(config);
+        engine.tick(&ledger, 100_100, &phrases);
+        assert_eq!(engine.watermark(), 4);
+        assert_eq!(engine.live_points(), 3);
+        assert_eq!(engine.point_type("old"), None);
+        assert_ne!(engine.point_type("far"), Some(PointType::Noise));
+        assert_eq!(engine.clusters().next().unwrap().members.len(), 3);
     }
 
     #[test]
-    fn test_cohesion_computation() {
+    fn test_ann_path_matches_brute_force() {
         use crate::embed::EmbedService;
-        use crate::types::{ConceptPacket, Polarity};
 
-        let mut engine = ClusterEngine::new();
+        // Twenty noisy copies of four topics, 80 points
+        let embed = EmbedService::with_dim(32);
         let mut ledger = Ledger::new();
         let mut phrases = HashMap::new();
-        let embed = EmbedService::new();
+        for topic in 0..4 {
+            let base = embed.embed(&format!("topic {}", topic));
+            for i in 0..20 {
+                let jitter = embed.embed(&format!("jitter {} {}", topic, i));
+                let mut v: Vec<f32> = base.iter().zip(&jitter).map(|(b, j)| b + 0.2 * j).collect();
+                normalize_vector(&mut v);
+
+                let hash = format!("t{}_{}", topic, i);
+                phrases.insert(hash.clone(), hash.clone());
+                let packet = packet_at(&hash, 1000);
+                ledger.append(packet, v).unwrap();
+            }
+        }
+
+        let memberships = |ann_threshold: usize| {
+            let mut engine = ClusterEngine::with_config(ClusterConfig {
+                epsilon: 0.1,
+                ann_threshold,
+                ..Default::default()
+            });
+            engine.tick(&ledger, 1000, &phrases);
+            let mut groups: Vec<Vec<String>> =
+                engine.clusters().map(|c| c.members.clone()).collect();
+            groups.sort();
+            (groups, engine.indexed())
+        };
+
+        // The index only exists once the window reaches the threshold
+        let (exact, indexed) = memberships(usize::MAX);
+        assert_eq!((exact.len(), indexed), (4, 0));
+        assert_eq!(memberships(0), (exact.clone(), 80));
+        assert_eq!(memberships(40), (exact, 80));
+    }
+
+    #[test]
+    fn test_mass_not_member_count_decides_maturity() {
+        let mut engine = engine_with(0.01, 2);
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
+
+        // Two confident signals vs ten faint ones
+        for i in 0..2 {
+            let hash = format!("strong{}", i);
+            place_weighted(&mut ledger, &mut phrases, &hash, i as f32, 1000, 1.0, 1.0);
+        }
+        for i in 0..10 {
+            let hash = format!("faint{}", i);
+            let deg = 90.0 + i as f32 * 0.2;
+            place_weighted(&mut ledger, &mut phrases, &hash, deg, 1000, 0.05, 1.0);
+        }
+
+        engine.tick(&ledger, 1000, &phrases);
+        let mature = engine.tick(&ledger, 1100, &phrases);
+        assert_eq!(engine.len(), 2);
+        assert_eq!(mature.len(), 1);
+
+        let strong = engine.get_cluster(&mature[0]).unwrap();
+        assert_eq!(strong.members.len(), 2);
+        let faint = engine.clusters().find(|c| c.id != mature[0]).unwrap();
+        assert_eq!(faint.members.len(), 10);
+        assert!(faint.mass < 0.5 && strong.mass > 1.5);
+    }
 
-        // Create similar concepts
-        let concepts = ["memory safety", "memory safety again"];
+    #[test]
+    fn test_mass_decays_with_tempo() {
+        let mut engine = engine_with(0.01, 2);
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
+        place(&mut ledger, &mut phrases, "a", 0.0, 0);
+        place(&mut ledger, &mut phrases, "b", 0.0, 0);
+
+        engine.tick(&ledger, 0, &phrases);
+        let fresh = engine.clusters().next().unwrap().mass;
+        assert!((fresh - 1.6).abs() < 1e-4);
+
+        // One Slow τ later: quiet tick, same members, mass down by e
+        engine.tick(&ledger, 30_000, &phrases);
+        let later = engine.clusters().next().unwrap().mass;
+        assert!((later - 1.6 / std::f32::consts::E).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_sigma_widens_kernel() {
+        let center = [1.0, 0.0];
+        let rad = 8f32.to_radians();
+        let entry = |sigma: f32| LedgerEntry {
+            vector: vec![rad.cos(), rad.sin()],
+            rationale_hash: "h".to_string(),
+            agent_id: "agent1".to_string(),
+            provenance: "test".to_string(),
+            phrase: "h".to_string(),
+            timestamp: 0,
+            tempo: Tempo::Slow,
+            polarity: Polarity::Attract,
+            amp: 1.0,
+            sigma,
+            coords_2d: None,
+        };
 
-        for (i, phrase) in concepts.iter().enumerate() {
+        let narrow = member_mass(&entry(0.5), &center, 0, 0.01);
+        let normal = member_mass(&entry(1.0), &center, 0, 0.01);
+        let wide = member_mass(&entry(4.0), &center, 0, 0.01);
+        assert!(narrow < normal && normal < wide);
+        assert!(wide > 0.95 && wide <= 1.0);
+        let on_center = member_mass(&entry(1.0), &[rad.cos(), rad.sin()], 0, 0.01);
+        assert!((on_center - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_repel_subtracts_mass_and_counts_dissent() {
+        let mut engine = engine_with(0.01, 2);
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
+
+        place_weighted(&mut ledger, &mut phrases, "leak0", 0.0, 1000, 0.5, 1.0);
+        place_weighted(&mut ledger, &mut phrases, "leak1", 1.0, 1000, 0.5, 1.0);
+        place_weighted(&mut ledger, &mut phrases, "leak2", 2.0, 1000, 0.5, 1.0);
+        engine.tick(&ledger, 1000, &phrases);
+        let mature = engine.tick(&ledger, 1100, &phrases);
+        assert_eq!(mature.len(), 1);
+        let before = engine.get_cluster(&mature[0]).unwrap().mass;
+
+        // "NOT a memory leak": lands inside the basin, far one is ignored;
+        // the same agent repeating itself is still one dissenter
+        for (hash, degrees) in [("not_leak", 1.0), ("not_leak_again", 1.5), ("not_gc", 90.0)] {
             let packet = ConceptPacket {
-                phrase: phrase.to_string(),
-                amp: 0.8,
-                sigma: 1.0,
-                polarity: Polarity::Attract,
-                tempo: Tempo::Slow,
-                provenance: "test".to_string(),
-                agent_id: format!("agent{}", i),
-                rationale_hash: format!("hash{}", i),
-                timestamp: 1000,
+                amp: 0.9,
+                polarity: Polarity::Repel,
+                ..packet_at(hash, 1200)
             };
+            append_at(&mut ledger, &mut phrases, packet, degrees);
+        }
+        let mature = engine.tick(&ledger, 1200, &phrases);
+
+        assert!(mature.is_empty(), "dissent pushed mass below min_mass");
+        let cluster = engine.clusters().next().unwrap();
+        assert_eq!(cluster.members.len(), 3, "repels never join");
+        assert_eq!(cluster.dissent, vec!["not_leak", "not_leak_again"]);
+        assert_eq!(cluster.dissenters, 1);
+        assert!(cluster.mass < before - 0.8);
+        assert_eq!(engine.point_type("not_leak"), None);
+    }
 
-            let vector = embed.embed(phrase);
-            phrases.insert(format!("hash{}", i), phrase.to_string());
-            ledger.append(packet, vector);
+    /// Two 3-point basins 55° apart in the xy-plane, plus `bridge` points
+    /// above and below their midpoint (off-plane, so they stay noise)
+    fn ridge_ledger(bridge: &[f32]) -> (Ledger, HashMap<String, String>) {
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
+        let mut add = |hash: &str, v: Vec<f32>| {
+            phrases.insert(hash.to_string(), hash.to_string());
+            ledger.append(packet_at(hash, 1000), v).unwrap();
+        };
+
+        let b = 55f32.to_radians();
+        let m = 27.5f32.to_radians();
+        for i in 0..3 {
+            add(&format!("a{}", i), vec![1.0, 0.0, 0.0]);
+            add(&format!("b{}", i), vec![b.cos(), b.sin(), 0.0]);
+        }
+        for (i, degrees) in bridge.iter().enumerate() {
+            let phi = degrees.to_radians();
+            let v = vec![phi.cos() * m.cos(), phi.cos() * m.sin(), phi.sin()];
+            add(&format!("bridge{}", i), v);
         }
+        (ledger, phrases)
+    }
+
+    #[test]
+    fn test_ridge_needs_sustained_boundary_density() {
+        let (ledger, phrases) = ridge_ledger(&[35.0, -35.0]);
+        let mut engine = ClusterEngine::new();
 
-        engine.cosine_threshold = 0.0;
+        assert_eq!(engine.tick(&ledger, 1000, &phrases).len(), 0);
+        assert_eq!(engine.tick(&ledger, 1100, &phrases).len(), 2);
+        assert_eq!(engine.point_type("bridge0"), Some(PointType::Noise));
+        assert!(engine.ridges().mature().is_empty(), "seen on one tick only");
+
+        engine.tick(&ledger, 1200, &phrases);
+        let ridges = engine.ridges().mature();
+        assert_eq!(ridges.len(), 1);
+        let ridge = ridges[0];
+        assert_eq!(ridge.boundary, vec!["bridge0", "bridge1"]);
+        let mut ends = ridge.endpoints.to_vec();
+        ends.sort();
+        assert_eq!(ends, vec!["a0", "b0"]);
+
+        // Axis runs from endpoint A's medoid to endpoint B's
+        let a = ledger.get(&ridge.endpoints[0]).unwrap();
+        let b = ledger.get(&ridge.endpoints[1]).unwrap();
+        for k in 0..3 {
+            assert!((ridge.axis[k] - (b.vector[k] - a.vector[k])).abs() < 1e-6);
+        }
+
+        // A lone point between the basins is not a ridge
+        let (ledger, phrases) = ridge_ledger(&[35.0]);
+        let mut engine = ClusterEngine::new();
+        for now in [1000, 1100, 1200, 1300] {
+            engine.tick(&ledger, now, &phrases);
+        }
+        assert_eq!(engine.len(), 2);
+        assert_eq!(engine.ridges().ridges().count(), 0);
+    }
+
+    #[test]
+    fn test_lifecycle_events() {
+        let mut engine = engine_with(0.01, 2); // ~8° neighbourhoods
+        let mut ledger = Ledger::new();
+        let mut phrases = HashMap::new();
+        let (a, b, c) = ("cluster_0", "cluster_1", "cluster_2");
+        let born = |id: &str| ClusterEvent::Born { id: id.to_string() };
+        let decayed = |id: &str| ClusterEvent::Decayed { id: id.to_string() };
+
+        for (hash, degrees) in [("a0", 0.0), ("a1", 1.0), ("b0", 12.0), ("b1", 13.0)] {
+            place(&mut ledger, &mut phrases, hash, degrees, 1000);
+        }
         engine.tick(&ledger, 1000, &phrases);
+        assert_eq!(engine.events(), [born(a), born(b)]);
+
+        engine.tick(&ledger, 1050, &phrases);
+        assert!(engine.events().is_empty(), "quiet tick");
+
+        place(&mut ledger, &mut phrases, "a2", 2.0, 1100);
+        engine.tick(&ledger, 1100, &phrases);
+        let grew = ClusterEvent::Grew {
+            id: a.to_string(),
+            from: 2,
+            to: 3,
+        };
+        assert_eq!(engine.events(), [grew]);
 
-        let cluster_id = engine.clusters.keys().next().unwrap().clone();
-        let cohesion = engine.compute_cohesion(&cluster_id, &ledger);
+        // A short-lived bridge joins the two...
+        let bridge = ConceptPacket {
+            tempo: Tempo::Fast,
+            ..packet_at("bridge", 1200)
+        };
+        append_at(&mut ledger, &mut phrases, bridge, 6.5);
+        engine.tick(&ledger, 1200, &phrases);
+        let merged = ClusterEvent::Merged {
+            from: vec![b.to_string()],
+            into: a.to_string(),
+        };
+        let grew = ClusterEvent::Grew {
+            id: a.to_string(),
+            from: 3,
+            to: 6,
+        };
+        assert_eq!(engine.events(), [merged, grew]);
+        assert_eq!(engine.get_cluster(a).unwrap().lineage, [b]);
+
+        // ...and once it fades they split apart again
+        engine.tick(&ledger, 6200, &phrases);
+        let split = ClusterEvent::Split {
+            from: a.to_string(),
+            into: vec![c.to_string()],
+        };
+        assert_eq!(engine.events(), [split]);
+        assert_eq!(engine.get_cluster(c).unwrap().lineage, [b, a]);
 
-        // Cohesion should be in [0, 1] range
-        assert!((0.0..=1.0).contains(&cohesion));
+        // Everything leaves the window
+        engine.tick(&ledger, 62_000, &phrases);
+        assert_eq!(engine.events(), [decayed(a), decayed(c)]);
     }
 }
@@ -0,0 +1,176 @@
+// Peak detection: large clusters with low cohesion
+//
+// A cluster of at least `min_members` whose mean pairwise similarity is
+// below `max_cohesion` is an overload (many loosely related concepts
+// chained together) rather than a consensus. It is split with k-medoids
+// to suggest sub-basins, and new packets landing inside it can have their
+// amplitude damped for `damp_ms` after it was first classified.
+
+use super::{cosine_similarity, mean_similarity, medoid_index};
+use crate::types::LedgerEntry;
+
+/// Overload thresholds and damping window
+#[derive(Debug, Clone)]
+pub struct PeakConfig {
+    pub min_members: usize,   // clusters smaller than this are never peaks
+    pub max_cohesion: f32,    // mean pairwise similarity below this is overload
+    pub max_parts: usize,     // sub-basins suggested by decomposition
+    pub damp_factor: f32,     // amp multiplier for packets landing in a peak
+    pub damp_ms: Option<u64>, // damping window after detection (None = off)
+}
+
+impl Default for PeakConfig {
+    fn default() -> Self {
+        Self {
+            min_members: 8,
+            max_cohesion: 0.6,
+            max_parts: 3,
+            damp_factor: 0.5,
+            damp_ms: Some(10_000),
+        }
+    }
+}
+
+/// Why and how a cluster should be decomposed
+#[derive(Debug, Clone)]
+pub struct Peak {
+    pub cohesion: f32,               // mean pairwise similarity of the members
+    pub radius: f32,                 // furthest member from the medoid
+    pub decompose_into: Vec<String>, // sub-cluster medoid rationale_hashes
+    pub since: u64,                  // tick at which it became a peak
+}
+
+impl PeakConfig {
+    /// Classify one cluster (`medoid` indexes `members`)
+    /// `since` is kept from the previous tick when it was already a peak
+    pub fn classify(&self, members: &[&LedgerEntry], medoid: usize, since: u64) -> Option<Peak> {
+        if members.len() < self.min_members.max(2) {
+            return None;
+        }
+        let cohesion = mean_similarity(members);
+        if cohesion >= self.max_cohesion {
+            return None;
+        }
+
+        let center = &members[medoid].vector;
+        let radius = members
+            .iter()
+            .map(|e| 1.0 - cosine_similarity(center, &e.vector))
+            .fold(0.0, f32::max);
+
+        Some(Peak {
+            cohesion,
+            radius,
+            decompose_into: decompose(members, medoid, self.max_parts)
+                .into_iter()
+                .map(|i| members[i].rationale_hash.clone())
+                .collect(),
+            since,
+        })
+    }
+}
+
+/// k-medoids split: farthest-first seeds from the medoid, then alternate
+/// assignment and medoid update until stable; returns medoid indices
+fn decompose(members: &[&LedgerEntry], medoid: usize, k: usize) -> Vec<usize> {
+    let distance =
+        |i: usize, j: usize| 1.0 - cosine_similarity(&members[i].vector, &members[j].vector);
+
+    let gap = |i: usize, seeds: &[usize]| {
+        seeds
+            .iter()
+            .map(|&m| distance(i, m))
+            .fold(f32::MAX, f32::min)
+    };
+
+    let mut medoids = vec![medoid];
+    while medoids.len() < k.min(members.len()) {
+        let far = (0..members.len())
+            .map(|i| (i, gap(i, &medoids)))
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)));
+        match far {
+            Some((i, d)) if d > 0.0 => medoids.push(i),
+            _ => break, // remaining members duplicate a seed
+        }
+    }
+
+    for _ in 0..10 {
+        let mut parts: Vec<Vec<&LedgerEntry>> = vec![Vec::new(); medoids.len()];
+        let mut indices: Vec<Vec<usize>> = vec![Vec::new(); medoids.len()];
+        for (i, &member) in members.iter().enumerate() {
+            let nearest = (0..medoids.len())
+                .min_by(|&a, &b| distance(i, medoids[a]).total_cmp(&distance(i, medoids[b])))
+                .unwrap();
+            parts[nearest].push(member);
+            indices[nearest].push(i);
+        }
+
+        let next: Vec<usize> = parts
+            .iter()
+            .zip(&indices)
+            .map(|(part, idx)| idx[medoid_index(part)])
+            .collect();
+        if next == medoids {
+            break;
+        }
+        medoids = next;
+    }
+
+    medoids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Polarity, Tempo};
+
+    fn entry(hash: &str, degrees: f32) -> LedgerEntry {
+        let rad = degrees.to_radians();
+        LedgerEntry {
+            vector: vec![rad.cos(), rad.sin()],
+            rationale_hash: hash.to_string(),
+            agent_id: "agent1".to_string(),
+            provenance: "test".to_string(),
+            phrase: hash.to_string(),
+            timestamp: 1000,
+            tempo: Tempo::Slow,
+            polarity: Polarity::Attract,
+            amp: 0.8,
+            sigma: 1.0,
+            coords_2d: None,
+        }
+    }
+
+    #[test]
+    fn test_tight_cluster_is_not_a_peak() {
+        let entries: Vec<LedgerEntry> = (0..10)
+            .map(|i| entry(&format!("e{}", i), i as f32))
+            .collect();
+        let members: Vec<&LedgerEntry> = entries.iter().collect();
+
+        assert!(PeakConfig::default().classify(&members, 5, 0).is_none());
+    }
+
+    #[test]
+    fn test_spread_cluster_decomposes_into_its_lobes() {
+        // Three lobes of three at 0°, 90° and 180°
+        let mut entries = Vec::new();
+        for (lobe, center) in [0.0, 90.0, 180.0].iter().enumerate() {
+            for k in 0..3 {
+                entries.push(entry(&format!("l{}_{}", lobe, k), center + k as f32 - 1.0));
+            }
+        }
+        let members: Vec<&LedgerEntry> = entries.iter().collect();
+        let medoid = medoid_index(&members);
+
+        let peak = PeakConfig::default()
+            .classify(&members, medoid, 1200)
+            .unwrap();
+        assert!(peak.cohesion < 0.6);
+        assert_eq!(peak.since, 1200);
+
+        let mut parts = peak.decompose_into;
+        parts.sort();
+        assert_eq!(parts, vec!["l0_1", "l1_1", "l2_1"]);
+    }
+}
@@ -0,0 +1,308 @@
+// Ridge detection: sustained density on the boundary between two basins
+//
+// For each pair of mature clusters whose medoids are at most `max_gap`
+// clustering epsilons apart, a live point is on their boundary when it sits
+// in the middle band: no further than `band` × gap from either medoid.
+// A pair whose band holds at least `min_points` entries for
+// `min_persistence` consecutive ticks is a ridge, i.e. a tradeoff between
+// the two basins.
+
+use super::{cosine_similarity, dominant_tempo, mean_similarity, medoid_index, Cluster};
+use crate::ledger::store::Ledger;
+use crate::types::{LedgerEntry, Tempo};
+use std::collections::BTreeMap;
+
+/// Boundary band and maturity parameters
+#[derive(Debug, Clone)]
+pub struct RidgeConfig {
+    pub max_gap: f32,         // max medoid distance, in clustering epsilons
+    pub band: f32,            // boundary = within band × gap of both medoids
+    pub min_points: usize,    // boundary entries needed on a tick
+    pub min_persistence: u32, // consecutive ticks before emitting
+}
+
+impl Default for RidgeConfig {
+    fn default() -> Self {
+        Self {
+            max_gap: 2.0, // only clusters that nearly touch share a boundary
+            band: 0.75,   // excludes points hugging either medoid
+            min_points: 2,
+            min_persistence: 2,
+        }
+    }
+}
+
+/// Boundary between two clusters
+#[derive(Debug, Clone)]
+pub struct Ridge {
+    pub id: String,
+    pub clusters: [String; 2],  // cluster ids, ordered
+    pub endpoints: [String; 2], // medoid rationale_hashes, same order
+    pub boundary: Vec<String>,  // rationale_hashes in the band
+    pub rep_hash: String,       // medoid of the boundary entries
+    pub rep_phrase: String,
+    pub cohesion: f32,    // mean pairwise similarity of the boundary
+    pub radius: f32,      // furthest boundary entry from rep
+    pub tempo: Tempo,     // dominant tempo of the boundary
+    pub axis: Vec<f32>,   // medoid B - medoid A
+    pub gap: f32,         // cosine distance between the medoids
+    pub persistence: u32, // consecutive ticks with a dense band
+}
+
+/// Ridges between live clusters, tracked across ticks
+pub struct RidgeTracker {
+    config: RidgeConfig,
+    ridges: BTreeMap<[String; 2], Ridge>, // keyed by cluster id pair
+    ridge_counter: u32,
+}
+
+impl RidgeTracker {
+    pub fn new() -> Self {
+        Self::with_config(RidgeConfig::default())
+    }
+
+    pub fn with_config(config: RidgeConfig) -> Self {
+        Self {
+            config,
+            ridges: BTreeMap::new(),
+            ridge_counter: 0,
+        }
+    }
+
+    pub fn config(&self) -> &RidgeConfig {
+        &self.config
+    }
+
+    /// New thresholds take effect on the next `update`
+    pub fn set_config(&mut self, config: RidgeConfig) {
+        self.config = config;
+    }
+
+    /// Rescan boundaries between `clusters` using the live `points`
+    /// Only pairs with medoids within `max_gap` × `epsilon` are candidates
+    /// Pairs whose band thinned out (or whose clusters died) are dropped
+    pub fn update(
+        &mut self,
+        clusters: &[&Cluster],
+        points: &[&LedgerEntry],
+        ledger: &Ledger,
+        epsilon: f32,
+    ) {
+        let mut medoids: Vec<(&Cluster, &LedgerEntry)> = clusters
+            .iter()
+            .filter_map(|&c| Some((c, ledger.get(&c.medoid_hash)?)))
+            .collect();
+        medoids.sort_by(|a, b| a.0.id.cmp(&b.0.id));
+        let max_gap = self.config.max_gap * epsilon;
+
+        let mut previous = std::mem::take(&mut self.ridges);
+        for (i, &(a, ma)) in medoids.iter().enumerate() {
+            for &(b, mb) in &medoids[i + 1..] {
+                let gap = 1.0 - cosine_similarity(&ma.vector, &mb.vector);
+                if gap > max_gap {
+                    continue;
+                }
+
+                let reach = self.config.band * gap;
+                let boundary: Vec<&LedgerEntry> = points
+                    .iter()
+                    .copied()
+                    .filter(|p| {
+                        1.0 - cosine_similarity(&p.vector, &ma.vector) <= reach
+                            && 1.0 - cosine_similarity(&p.vector, &mb.vector) <= reach
+                    })
+                    .collect();
+                if boundary.is_empty() || boundary.len() < self.config.min_points {
+                    continue;
+                }
+
+                let key = [a.id.clone(), b.id.clone()];
+                let (id, persistence) = match previous.remove(&key) {
+                    Some(prev) => (prev.id, prev.persistence + 1),
+                    None => {
+                        let id = format!("ridge_{}", self.ridge_counter);
+                        self.ridge_counter += 1;
+                        (id, 1)
+                    }
+                };
+                let axis = mb
+                    .vector
+                    .iter()
+                    .zip(&ma.vector)
+                    .map(|(b, a)| b - a)
+                    .collect();
+                let rep = boundary[medoid_index(&boundary)];
+
+                self.ridges.insert(
+                    key.clone(),
+                    Ridge {
+                        id,
+                        clusters: key,
+                        endpoints: [ma.rationale_hash.clone(), mb.rationale_hash.clone()],
+                        boundary: boundary.iter().map(|e| e.rationale_hash.clone()).collect(),
+                        rep_hash: rep.rationale_hash.clone(),
+                        rep_phrase: rep.phrase.clone(),
+                        cohesion: mean_similarity(&boundary),
+                        radius: boundary
+                            .iter()
+                            .map(|e| 1.0 - cosine_similarity(&rep.vector, &e.vector))
+                            .fold(0.0, f32::max),
+                        tempo: dominant_tempo(&boundary),
+                        axis,
+                        gap,
+                        persistence,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Ridges whose band has stayed dense long enough
+    pub fn mature(&self) -> Vec<&Ridge> {
+        self.ridges
+            .values()
+            .filter(|r| r.persistence >= self.config.min_persistence)
+            .collect()
+    }
+
+    /// Get ridge by ID
+    pub fn get(&self, id: &str) -> Option<&Ridge> {
+        self.ridges.values().find(|r| r.id == id)
+    }
+
+    /// All live ridges, ordered by cluster pair
+    pub fn ridges(&self) -> impl Iterator<Item = &Ridge> {
+        self.ridges.values()
+    }
+}
+
+impl Default for RidgeTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ConceptPacket, Polarity};
+
+    const EPSILON: f32 = 0.25;
+
+    /// Ledger of 2-d unit vectors, one per (hash, degrees)
+    fn ledger_at(points: &[(&str, f32)]) -> Ledger {
+        let mut ledger = Ledger::new();
+        for &(hash, degrees) in points {
+            let packet = ConceptPacket {
+                phrase: hash.to_string(),
+                amp: 0.8,
+                sigma: 1.0,
+                polarity: Polarity::Attract,
+                tempo: Tempo::Slow,
+                provenance: "test".to_string(),
+                agent_id: "agent1".to_string(),
+                rationale_hash: hash.to_string(),
+                timestamp: 1000,
+                center: None,
+                model: None,
+            };
+            let rad = degrees.to_radians();
+            ledger.append(packet, vec![rad.cos(), rad.sin()]).unwrap();
+        }
+        ledger
+    }
+
+    fn cluster(id: &str, medoid: &str) -> Cluster {
+        Cluster {
+            id: id.to_string(),
+            members: vec![medoid.to_string()],
+            medoid_hash: medoid.to_string(),
+            medoid_phrase: medoid.to_string(),
+            centroid: Vec::new(),
+            persistence: 2,
+            mass: 1.0,
+            dissent: Vec::new(),
+            dissenters: 0,
+            tempo: Tempo::Slow,
+            peak: None,
+            lineage: Vec::new(),
+            last_update: 1000,
+        }
+    }
+
+    fn entries<'a>(ledger: &'a Ledger, hashes: &[&str]) -> Vec<&'a LedgerEntry> {
+        hashes.iter().map(|h| ledger.get(h).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_band_detection() {
+        // Medoids 40° apart; "hug" sits 2° from a, outside the band
+        let ledger = ledger_at(&[
+            ("a", 0.0),
+            ("b", 40.0),
+            ("m0", 19.0),
+            ("m1", 21.0),
+            ("hug", 2.0),
+        ]);
+        let (a, b) = (cluster("cluster_0", "a"), cluster("cluster_1", "b"));
+        let points = entries(&ledger, &["a", "b", "m0", "m1", "hug"]);
+        let mut ridges = RidgeTracker::new();
+
+        ridges.update(&[&a, &b], &points, &ledger, EPSILON);
+        assert!(ridges.mature().is_empty(), "seen on one tick only");
+
+        ridges.update(&[&b, &a], &points, &ledger, EPSILON);
+        let mature = ridges.mature();
+        assert_eq!(mature.len(), 1);
+        let ridge = mature[0];
+        assert_eq!(ridge.clusters, ["cluster_0", "cluster_1"]);
+        assert_eq!(ridge.endpoints, ["a", "b"]);
+        assert_eq!(ridge.boundary, vec!["m0", "m1"]);
+        assert_eq!(ridge.persistence, 2);
+    }
+
+    #[test]
+    fn test_far_medoids_are_not_candidates() {
+        // 70° apart is beyond 2·epsilon, however dense the middle
+        let ledger = ledger_at(&[("a", 0.0), ("b", 70.0), ("m0", 34.0), ("m1", 36.0)]);
+        let (a, b) = (cluster("cluster_0", "a"), cluster("cluster_1", "b"));
+        let points = entries(&ledger, &["a", "b", "m0", "m1"]);
+        let mut ridges = RidgeTracker::new();
+
+        for _ in 0..3 {
+            ridges.update(&[&a, &b], &points, &ledger, EPSILON);
+        }
+        assert_eq!(ridges.ridges().count(), 0);
+
+        // A wider clustering epsilon brings them into range
+        ridges.update(&[&a, &b], &points, &ledger, 0.5);
+        assert_eq!(ridges.ridges().count(), 1);
+    }
+
+    #[test]
+    fn test_expiry() {
+        let ledger = ledger_at(&[("a", 0.0), ("b", 40.0), ("m0", 19.0), ("m1", 21.0)]);
+        let (a, b) = (cluster("cluster_0", "a"), cluster("cluster_1", "b"));
+        let dense = entries(&ledger, &["a", "b", "m0", "m1"]);
+        let thin = entries(&ledger, &["a", "b", "m0"]);
+        let mut ridges = RidgeTracker::new();
+
+        ridges.update(&[&a, &b], &dense, &ledger, EPSILON);
+        ridges.update(&[&a, &b], &dense, &ledger, EPSILON);
+        let id = ridges.mature()[0].id.clone();
+
+        // The band thinned out: the ridge is dropped, not just paused
+        ridges.update(&[&a, &b], &thin, &ledger, EPSILON);
+        assert!(ridges.get(&id).is_none());
+
+        // Re-forming starts over under a new id
+        ridges.update(&[&a, &b], &dense, &ledger, EPSILON);
+        let ridge = ridges.ridges().next().unwrap();
+        assert_ne!(ridge.id, id);
+        assert_eq!(ridge.persistence, 1);
+
+        // One endpoint cluster died
+        ridges.update(&[&a], &dense, &ledger, EPSILON);
+        assert_eq!(ridges.ridges().count(), 0);
+    }
+}
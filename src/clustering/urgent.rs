@@ -0,0 +1,233 @@
+// Urgent fast-path: high-amp Urgent packets raise alerts on ingest
+//
+// Loud urgent Attract packets bypass density clustering: one at or above
+// `min_amp` raises an alert immediately, unless it lands within `epsilon` of an alert
+// that is still active, in which case it reinforces that alert instead.
+// An alert expires `ttl_ms` after its last reinforcement. Quieter or Repel
+// urgent packets are left to ordinary clustering.
+
+use super::cosine_similarity;
+use crate::types::{LedgerEntry, Polarity, Tempo};
+
+/// Thresholds for the urgent path
+#[derive(Debug, Clone)]
+pub struct UrgentConfig {
+    pub min_amp: f32, // quieter urgent packets are clustered instead
+    pub epsilon: f32, // cosine distance within which alerts deduplicate
+    pub ttl_ms: u64,  // alert lifetime without reinforcement
+}
+
+impl Default for UrgentConfig {
+    fn default() -> Self {
+        Self {
+            min_amp: 0.7,
+            epsilon: 0.25,
+            ttl_ms: 10_000,
+        }
+    }
+}
+
+impl UrgentConfig {
+    /// Whether `entry` takes the fast path (and so skips clustering)
+    pub fn takes(&self, entry: &LedgerEntry) -> bool {
+        entry.tempo == Tempo::Urgent
+            && entry.polarity == Polarity::Attract
+            && entry.amp >= self.min_amp
+    }
+}
+
+/// One active alert
+#[derive(Debug, Clone)]
+pub struct UrgentAlert {
+    pub id: String,
+    pub rep_hash: String, // packet that raised it
+    pub rep_phrase: String,
+    pub vector: Vec<f32>,
+    pub contributors: Vec<String>, // raising packet + reinforcements
+    pub raised_at: u64,
+    pub last_seen: u64, // latest reinforcement (ms epoch)
+}
+
+/// Active alerts with dedup and expiry
+pub struct UrgentTracker {
+    config: UrgentConfig,
+    alerts: Vec<UrgentAlert>,
+    alert_counter: u32,
+}
+
+impl UrgentTracker {
+    pub fn new() -> Self {
+        Self::with_config(UrgentConfig::default())
+    }
+
+    pub fn with_config(config: UrgentConfig) -> Self {
+        Self {
+            config,
+            alerts: Vec::new(),
+            alert_counter: 0,
+        }
+    }
+
+    pub fn config(&self) -> &UrgentConfig {
+        &self.config
+    }
+
+    /// Feed one freshly stored entry, seen at engine time `now`
+    /// Returns the alert if this entry raised a new one
+    pub fn observe(&mut self, entry: &LedgerEntry, now: u64) -> Option<&UrgentAlert> {
+        if !self.config.takes(entry) {
+            return None;
+        }
+
+        self.expire(now);
+
+        let epsilon = self.config.epsilon;
+        let existing = self
+            .alerts
+            .iter_mut()
+            .find(|a| 1.0 - cosine_similarity(&a.vector, &entry.vector) <= epsilon);
+        if let Some(alert) = existing {
+            alert.contributors.push(entry.rationale_hash.clone());
+            alert.last_seen = alert.last_seen.max(now);
+            return None;
+        }
+
+        let id = format!("urgent_{}", self.alert_counter);
+        self.alert_counter += 1;
+        self.alerts.push(UrgentAlert {
+            id,
+            rep_hash: entry.rationale_hash.clone(),
+            rep_phrase: entry.phrase.clone(),
+            vector: entry.vector.clone(),
+            contributors: vec![entry.rationale_hash.clone()],
+            raised_at: now,
+            last_seen: now,
+        });
+        self.alerts.last()
+    }
+
+    /// Drop alerts not reinforced within the TTL; returns their ids
+    pub fn expire(&mut self, now: u64) -> Vec<String> {
+        let ttl = self.config.ttl_ms;
+        let mut expired = Vec::new();
+        self.alerts.retain(|a| {
+            let alive = now.saturating_sub(a.last_seen) <= ttl;
+            if !alive {
+                expired.push(a.id.clone());
+            }
+            alive
+        });
+        expired
+    }
+
+    /// Active alerts, oldest first
+    pub fn alerts(&self) -> &[UrgentAlert] {
+        &self.alerts
+    }
+
+    /// Get an active alert by ID
+    pub fn get(&self, id: &str) -> Option<&UrgentAlert> {
+        self.alerts.iter().find(|a| a.id == id)
+    }
+}
+
+impl Default for UrgentTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: &str, vector: Vec<f32>, amp: f32, timestamp: u64) -> LedgerEntry {
+        LedgerEntry {
+            vector,
+            rationale_hash: hash.to_string(),
+            agent_id: "agent1".to_string(),
+            provenance: "test".to_string(),
+            phrase: format!("phrase {}", hash),
+            timestamp,
+            tempo: Tempo::Urgent,
+            polarity: Polarity::Attract,
+            amp,
+            sigma: 1.0,
+            coords_2d: None,
+        }
+    }
+
+    #[test]
+    fn test_raises_once_then_reinforces() {
+        let mut urgent = UrgentTracker::new();
+
+        let id = urgent
+            .observe(&entry("a", vec![1.0, 0.0], 0.9, 1000), 1000)
+            .unwrap()
+            .id
+            .clone();
+
+        // Same direction: deduplicated into the existing alert
+        assert!(urgent
+            .observe(&entry("b", vec![1.0, 0.0], 0.9, 2000), 2000)
+            .is_none());
+        let alert = urgent.get(&id).unwrap();
+        assert_eq!(alert.contributors, vec!["a", "b"]);
+        assert_eq!(alert.last_seen, 2000);
+
+        // Orthogonal: a separate alert
+        assert!(urgent
+            .observe(&entry("c", vec![0.0, 1.0], 0.9, 2000), 2000)
+            .is_some());
+        assert_eq!(urgent.alerts().len(), 2);
+    }
+
+    #[test]
+    fn test_ignores_quiet_and_non_urgent() {
+        let mut urgent = UrgentTracker::new();
+
+        assert!(urgent
+            .observe(&entry("quiet", vec![1.0, 0.0], 0.3, 1000), 1000)
+            .is_none());
+
+        let mut slow = entry("slow", vec![1.0, 0.0], 0.9, 1000);
+        slow.tempo = Tempo::Slow;
+        assert!(urgent.observe(&slow, 1000).is_none());
+
+        let mut repel = entry("repel", vec![1.0, 0.0], 0.9, 1000);
+        repel.polarity = Polarity::Repel;
+        assert!(urgent.observe(&repel, 1000).is_none());
+
+        assert!(urgent.alerts().is_empty());
+    }
+
+    #[test]
+    fn test_expires_without_reinforcement() {
+        let mut urgent = UrgentTracker::new();
+        urgent.observe(&entry("a", vec![1.0, 0.0], 0.9, 1000), 1000);
+
+        // Reinforced at 9s keeps it alive past 11s
+        urgent.observe(&entry("b", vec![1.0, 0.0], 0.9, 9000), 9000);
+        assert!(urgent.expire(11_000).is_empty());
+
+        assert_eq!(urgent.expire(19_001), vec!["urgent_0"]);
+        assert!(urgent.alerts().is_empty());
+
+        // Expired alerts can be raised again
+        assert!(urgent
+            .observe(&entry("c", vec![1.0, 0.0], 0.9, 20_000), 20_000)
+            .is_some());
+    }
+
+    #[test]
+    fn test_uses_engine_clock_not_packet_timestamp() {
+        let mut urgent = UrgentTracker::new();
+
+        // A packet stamped long ago is still alerted as of now
+        let alert = urgent
+            .observe(&entry("late", vec![1.0, 0.0], 0.9, 1000), 50_000)
+            .unwrap();
+        assert_eq!(alert.raised_at, 50_000);
+        assert!(urgent.expire(55_000).is_empty());
+    }
+}
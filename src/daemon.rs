@@ -0,0 +1,671 @@
+// Long-running engine behind a Unix domain socket
+//
+// Protocol: newline-delimited JSON, one Request per line in,
+// one Response per line out. Many clients may connect at once;
+// all packets land in the same shared Engine. `serve_http` adds an
+// HTTP front door (see api.rs) over the same engine.
+
+use crate::api::{self, Streams};
+use crate::embed::{EmbedService, Embedder};
+use crate::engine::{self, Engine};
+use crate::now_ms;
+use crate::types::{BasinFeedback, BasinType, ConceptPacket, Tempo};
+use crate::viz::projection::Refit;
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Client → daemon message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    Emit { packet: ConceptPacket },
+    Status,
+    Top,
+}
+
+/// Daemon → client message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Response {
+    Accepted {
+        ledger_len: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        alert: Option<String>, // urgent alert raised by this packet
+    },
+    Status(StatusReport),
+    Top(TopReport),
+    Error {
+        message: String,
+    },
+}
+
+/// Feedback queued for the serve loop before the tick loop waits on it
+pub const FEEDBACK_QUEUE: usize = 1024;
+
+/// Seconds of ingest history in a TopReport
+pub const INGEST_WINDOW: usize = engine::INGEST_WINDOW;
+
+/// Snapshot of the shared field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusReport {
+    pub ledger_len: usize,
+    pub durable: bool,
+    pub basins: Vec<BasinSummary>,
+}
+
+/// One live cluster as shown by `sefi status`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasinSummary {
+    pub basin_id: String,
+    pub rep_phrase: String,
+    pub members: usize,
+    pub persistence: u32,
+    #[serde(default)]
+    pub mass: f32,
+    #[serde(default)]
+    pub dissent: usize,
+    pub tempo: Tempo,
+    #[serde(default)]
+    pub cohesion: f32, // mean silhouette when the basin was reported (0 before)
+    #[serde(default)]
+    pub agents: usize, // distinct agent_ids among the members
+    #[serde(default)]
+    pub coords_2d: Option<[f32; 2]>, // projected medoid (None before the first fit)
+}
+
+/// Everything `sefi top` draws in one frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopReport {
+    pub status: StatusReport,
+    pub now: u64,            // daemon clock, epoch ms
+    pub ingest: Vec<u32>,    // packets per second, oldest first, INGEST_WINDOW long
+    pub map: Vec<[f32; 2]>,  // projected live attracting entries
+    pub feed: Vec<FeedLine>, // recently emitted feedback, oldest first
+}
+
+/// One emitted BasinFeedback, as listed in the `sefi top` feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedLine {
+    pub timestamp: u64,
+    pub basin_id: String,
+    pub type_: BasinType,
+    pub rep_phrase: String,
+    pub cohesion: f32,
+    pub contributors: usize,
+    pub tempo: Tempo,
+}
+
+impl From<&BasinFeedback> for FeedLine {
+    fn from(basin: &BasinFeedback) -> Self {
+        Self {
+            timestamp: basin.timestamp,
+            basin_id: basin.basin_id.clone(),
+            type_: basin.type_,
+            rep_phrase: basin.rep_phrase.clone(),
+            cohesion: basin.nd_cohesion,
+            contributors: basin.contributors.len(),
+            tempo: basin.tempo,
+        }
+    }
+}
+
+impl StatusReport {
+    /// Summarize an engine's current state (heaviest basins first)
+    /// Cheap enough to build under the engine lock: cohesion is the one
+    /// reported when a basin matured (0 until then), not recomputed
+    pub fn from_engine<E: Embedder>(engine: &Engine<E>) -> Self {
+        let ledger = engine.ledger();
+        let mut basins: Vec<BasinSummary> = engine
+            .clusters()
+            .clusters()
+            .map(|c| {
+                let mut agents: Vec<&str> = ledger
+                    .get_batch(&c.members)
+                    .iter()
+                    .map(|e| e.agent_id.as_str())
+                    .collect();
+                agents.sort_unstable();
+                agents.dedup();
+
+                BasinSummary {
+                    basin_id: c.id.clone(),
+                    rep_phrase: c.medoid_phrase.clone(),
+                    members: c.members.len(),
+                    persistence: c.persistence,
+                    mass: c.mass,
+                    dissent: c.dissenters,
+                    tempo: c.tempo,
+                    cohesion: engine.reported_cohesion(&c.id).unwrap_or(0.0),
+                    agents: agents.len(),
+                    coords_2d: ledger
+                        .get(&c.medoid_hash)
+                        .and_then(|m| engine.projector().project(&m.vector)),
+                }
+            })
+            .collect();
+        basins.sort_by(|a, b| {
+            b.mass
+                .total_cmp(&a.mass)
+                .then_with(|| a.basin_id.cmp(&b.basin_id))
+        });
+
+        Self {
+            ledger_len: engine.ledger().len(),
+            durable: engine.ledger().is_durable(),
+            basins,
+        }
+    }
+}
+
+impl TopReport {
+    /// Status plus ingest rate, projected points and feed at `now`
+    pub fn from_engine<E: Embedder>(engine: &Engine<E>, now: u64) -> Self {
+        let map = match engine.projector().projection() {
+            Some(projection) => engine
+                .live_entries()
+                .map(|e| projection.project(&e.vector))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        Self {
+            status: StatusReport::from_engine(engine),
+            now,
+            ingest: engine.ingest_rate(now),
+            map,
+            feed: engine.recent().map(FeedLine::from).collect(),
+        }
+    }
+}
+
+/// Running daemon: socket acceptor + tick loop
+pub struct Daemon<E: Embedder + Send + Sync + 'static = EmbedService> {
+    socket: PathBuf,
+    engine: Arc<Mutex<Engine<E>>>,
+    embedder: Arc<E>, // the engine's, used outside its lock
+    shutdown: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+    feedback: Receiver<BasinFeedback>,
+    outlet: Outlet, // shared with the front doors added by serve_http
+    http: Option<SocketAddr>,
+}
+
+impl<E: Embedder + Send + Sync + 'static> Daemon<E> {
+    /// Bind `socket` and start serving `engine`, ticking every `tick`
+    /// A stale socket file from a dead daemon is replaced
+    pub fn spawn(engine: Engine<E>, socket: &Path, tick: Duration) -> io::Result<Self> {
+        if socket.exists() {
+            if UnixStream::connect(socket).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("daemon already listening on {}", socket.display()),
+                ));
+            }
+            std::fs::remove_file(socket)?;
+        }
+        let listener = UnixListener::bind(socket)?;
+
+        let embedder = engine.shared_embedder();
+        let engine = Arc::new(Mutex::new(engine));
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let (tx, feedback) = mpsc::sync_channel(FEEDBACK_QUEUE);
+        let outlet = Outlet {
+            tx,
+            streams: Streams::new(),
+        };
+
+        let acceptor = {
+            let engine = Arc::clone(&engine);
+            let embedder = Arc::clone(&embedder);
+            let shutdown = Arc::clone(&shutdown);
+            let outlet = outlet.clone(); // urgent alerts skip the tick loop
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(stream) = stream else { continue };
+                    let engine = Arc::clone(&engine);
+                    let embedder = Arc::clone(&embedder);
+                    let outlet = outlet.clone();
+                    thread::spawn(move || {
+                        // A dropped client is not the daemon's problem
+                        let _ = handle_client(stream, &engine, &*embedder, &outlet);
+                    });
+                }
+            })
+        };
+
+        let ticker = {
+            let engine = Arc::clone(&engine);
+            let shutdown = Arc::clone(&shutdown);
+            let outlet = outlet.clone();
+            thread::spawn(move || {
+                while !shutdown.load(Ordering::SeqCst) {
+                    thread::sleep(tick);
+                    let now = now_ms();
+                    // Fitting the display basis is slow; keep the engine free meanwhile
+                    let refit = engine.lock().unwrap().prepare_refit(now);
+                    if let Some(fitted) = refit.and_then(Refit::run) {
+                        engine.lock().unwrap().install_refit(fitted);
+                    }
+                    let basins = engine.lock().unwrap().tick(now);
+                    for basin in basins {
+                        if !outlet.forward(basin, &shutdown) {
+                            return; // nobody is listening any more
+                        }
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            socket: socket.to_path_buf(),
+            engine,
+            embedder,
+            shutdown,
+            threads: vec![acceptor, ticker],
+            feedback,
+            outlet,
+            http: None,
+        })
+    }
+
+    /// Feedback emitted by the tick loop and urgent alerts
+    pub fn feedback(&self) -> &Receiver<BasinFeedback> {
+        &self.feedback
+    }
+
+    /// Also serve the HTTP API on `addr` (port 0 picks one); returns the bound address
+    pub fn serve_http(&mut self, addr: &str) -> io::Result<SocketAddr> {
+        let listener = TcpListener::bind(addr)?;
+        let local = listener.local_addr()?;
+        self.threads.push(api::spawn(
+            listener,
+            Arc::clone(&self.engine),
+            Arc::clone(&self.embedder),
+            self.outlet.clone(),
+            Arc::clone(&self.shutdown),
+        ));
+        self.http = Some(local);
+        Ok(local)
+    }
+
+    /// Subscribers of the HTTP feedback stream
+    /// Every basin reaches them before it is queued for `feedback`
+    pub fn streams(&self) -> Streams {
+        self.outlet.streams().clone()
+    }
+
+    /// Shared engine (lock to inspect)
+    pub fn engine(&self) -> &Arc<Mutex<Engine<E>>> {
+        &self.engine
+    }
+
+    /// Stop both loops, flush the ledger and remove the socket file
+    pub fn shutdown(mut self) -> io::Result<()> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> io::Result<()> {
+        if self.shutdown.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        // Wake the acceptors so they see the flag
+        let _ = UnixStream::connect(&self.socket);
+        if let Some(addr) = self.http {
+            let _ = TcpStream::connect(addr);
+        }
+        self.outlet.streams().close();
+        for handle in self.threads.drain(..) {
+            let _ = handle.join();
+        }
+        let _ = std::fs::remove_file(&self.socket);
+        self.engine.lock().unwrap().sync()
+    }
+}
+
+impl<E: Embedder + Send + Sync + 'static> Drop for Daemon<E> {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// Where feedback leaves the daemon: HTTP stream subscribers, then the serve loop
+#[derive(Clone)]
+pub(crate) struct Outlet {
+    tx: SyncSender<BasinFeedback>,
+    streams: Streams,
+}
+
+impl Outlet {
+    /// Publish `basin` and queue it, blocking while the queue is full
+    /// False once the receiver is gone (only at shutdown)
+    pub(crate) fn send(&self, basin: BasinFeedback) -> bool {
+        self.streams.publish(&basin);
+        self.tx.send(basin).is_ok()
+    }
+
+    /// HTTP stream subscribers fed by this outlet
+    pub(crate) fn streams(&self) -> &Streams {
+        &self.streams
+    }
+
+    /// `send` for the tick loop: gives up on a full queue at shutdown
+    fn forward(&self, basin: BasinFeedback, shutdown: &AtomicBool) -> bool {
+        self.streams.publish(&basin);
+        let mut basin = basin;
+        loop {
+            match self.tx.try_send(basin) {
+                Ok(()) => return true,
+                Err(TrySendError::Disconnected(_)) => return false,
+                Err(TrySendError::Full(back)) => {
+                    if shutdown.load(Ordering::SeqCst) {
+                        return false;
+                    }
+                    basin = back;
+                    thread::sleep(Duration::from_millis(10));
+                }
+            }
+        }
+    }
+}
+
+/// Serve one connection until the client hangs up
+fn handle_client<E: Embedder>(
+    stream: UnixStream,
+    engine: &Mutex<Engine<E>>,
+    embedder: &E,
+    outlet: &Outlet,
+) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => dispatch(request, engine, embedder, outlet),
+            Err(e) => Response::Error {
+                message: format!("bad request: {}", e),
+            },
+        };
+
+        let mut out = serde_json::to_vec(&response)?;
+        out.push(b'\n');
+        writer.write_all(&out)?;
+    }
+
+    Ok(())
+}
+
+/// Answer one request; packets are embedded before the engine is locked
+pub(crate) fn dispatch<E: Embedder>(
+    request: Request,
+    engine: &Mutex<Engine<E>>,
+    embedder: &E,
+    outlet: &Outlet,
+) -> Response {
+    match request {
+        Request::Emit { mut packet } => {
+            let checked = engine.lock().unwrap().check(&packet);
+            let result = checked
+                .and_then(|()| engine::vectorize(embedder, &mut packet))
+                .and_then(|vector| {
+                    let mut engine = engine.lock().unwrap();
+                    let alert = engine.ingest_vector(packet, vector)?;
+                    Ok((alert, engine.ledger().len()))
+                });
+            match result {
+                Ok((alert, ledger_len)) => {
+                    let id = alert.as_ref().map(|a| a.basin_id.clone());
+                    if let Some(alert) = alert {
+                        // A full queue blocks this client, never the engine
+                        outlet.send(alert); // receiver gone only at shutdown
+                    }
+                    Response::Accepted {
+                        ledger_len,
+                        alert: id,
+                    }
+                }
+                Err(e) => Response::Error {
+                    message: e.to_string(),
+                },
+            }
+        }
+        Request::Status => Response::Status(StatusReport::from_engine(&engine.lock().unwrap())),
+        Request::Top => Response::Top(TopReport::from_engine(&engine.lock().unwrap(), now_ms())),
+    }
+}
+
+/// Send a single request to a running daemon and wait for the reply
+pub fn request(socket: &Path, request: &Request) -> io::Result<Response> {
+    let mut stream = UnixStream::connect(socket)?;
+
+    let mut line = serde_json::to_vec(request)?;
+    line.push(b'\n');
+    stream.write_all(&line)?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "daemon closed the connection",
+        ));
+    }
+    serde_json::from_str(&reply).map_err(io::Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embed::{EmbedError, EmbedService};
+    use crate::ledger::store::Ledger;
+    use crate::types::Polarity;
+
+    fn packet(phrase: &str, hash: &str) -> ConceptPacket {
+        ConceptPacket {
+            phrase: phrase.to_string(),
+            amp: 0.8,
+            sigma: 1.0,
+            polarity: Polarity::Attract,
+            tempo: Tempo::Slow,
+            provenance: "test".to_string(),
+            agent_id: "agent1".to_string(),
+            rationale_hash: hash.to_string(),
+            timestamp: now_ms(),
+            center: None,
+            model: None,
+        }
+    }
+
+    fn socket_path() -> PathBuf {
+        std::env::temp_dir().join(format!("sefi_{}.sock", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_emit_and_status_over_socket() {
+        let socket = socket_path();
+        let daemon = Daemon::spawn(Engine::new(), &socket, Duration::from_millis(10)).unwrap();
+
+        for i in 0..3 {
+            let reply = request(
+                &socket,
+                &Request::Emit {
+                    packet: packet("memory safety", &format!("h{}", i)),
+                },
+            )
+            .unwrap();
+            assert!(matches!(
+                reply,
+                Response::Accepted { ledger_len, alert: None } if ledger_len == i + 1
+            ));
+        }
+
+        // Duplicate rationale is reported, not fatal
+        let reply = request(
+            &socket,
+            &Request::Emit {
+                packet: packet("memory safety", "h0"),
+            },
+        )
+        .unwrap();
+        assert!(matches!(reply, Response::Error { .. }));
+
+        // Tick loop turns the three identical packets into one basin
+        let basin = daemon
+            .feedback()
+            .recv_timeout(Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(basin.rep_phrase, "memory safety");
+
+        // The basin may have matured before the third emit; wait for it to join
+        let deadline = std::time::Instant::now() + Duration::from_secs(2);
+        loop {
+            match request(&socket, &Request::Status).unwrap() {
+                Response::Status(report) => {
+                    assert_eq!(report.ledger_len, 3);
+                    assert_eq!(report.basins.len(), 1);
+                    if report.basins[0].members == 3 {
+                        break;
+                    }
+                    assert!(
+                        std::time::Instant::now() < deadline,
+                        "third member never joined"
+                    );
+                }
+                other => panic!("unexpected reply: {:?}", other),
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let Response::Top(top) = request(&socket, &Request::Top).unwrap() else {
+            panic!("expected a top report");
+        };
+        assert_eq!(top.ingest.len(), INGEST_WINDOW);
+        assert_eq!(top.ingest.iter().sum::<u32>(), 3);
+        assert_eq!(top.status.basins[0].agents, 1);
+        assert!(top.status.basins[0].cohesion > 0.99);
+        assert_eq!(top.feed.len(), 1);
+        assert_eq!(top.feed[0].rep_phrase, "memory safety");
+
+        daemon.shutdown().unwrap();
+        assert!(!socket.exists());
+    }
+
+    #[test]
+    fn test_urgent_alert_skips_tick_loop() {
+        let socket = socket_path();
+        // Urgent packets never cluster, so only the fast path can deliver this
+        let daemon = Daemon::spawn(Engine::new(), &socket, Duration::from_millis(10)).unwrap();
+
+        let mut urgent = packet("prod is down", "u0");
+        urgent.tempo = Tempo::Urgent;
+        urgent.amp = 0.9;
+        let reply = request(&socket, &Request::Emit { packet: urgent }).unwrap();
+        let Response::Accepted {
+            alert: Some(id), ..
+        } = reply
+        else {
+            panic!("unexpected reply: {:?}", reply);
+        };
+
+        let basin = daemon
+            .feedback()
+            .recv_timeout(Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(basin.basin_id, id);
+        assert_eq!(basin.tempo, Tempo::Urgent);
+
+        daemon.shutdown().unwrap();
+    }
+
+    /// Mock embedder that holds each call until the test releases it
+    struct Gated {
+        inner: EmbedService,
+        entered: Mutex<mpsc::Sender<()>>,
+        release: Mutex<Receiver<()>>,
+    }
+
+    impl Embedder for Gated {
+        fn embed(&self, text: &str) -> Result<Vec<f32>, EmbedError> {
+            self.entered.lock().unwrap().send(()).unwrap();
+            self.release.lock().unwrap().recv().unwrap();
+            Ok(self.inner.embed(text))
+        }
+
+        fn dim(&self) -> usize {
+            Embedder::dim(&self.inner)
+        }
+
+        fn model_id(&self) -> &str {
+            Embedder::model_id(&self.inner)
+        }
+    }
+
+    #[test]
+    fn test_embeds_outside_the_engine_lock() {
+        let (entered_tx, entered) = mpsc::channel();
+        let (release, release_rx) = mpsc::channel();
+        let gated = Gated {
+            inner: EmbedService::new(),
+            entered: Mutex::new(entered_tx),
+            release: Mutex::new(release_rx),
+        };
+        let socket = socket_path();
+        let engine = Engine::with_embedder(Ledger::new(), gated);
+        let daemon = Daemon::spawn(engine, &socket, Duration::from_millis(10)).unwrap();
+
+        let client = {
+            let socket = socket.clone();
+            thread::spawn(move || {
+                request(
+                    &socket,
+                    &Request::Emit {
+                        packet: packet("memory safety", "h0"),
+                    },
+                )
+            })
+        };
+
+        // While the embedder is busy, status and ticks still get the engine
+        entered.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert!(daemon.engine().try_lock().is_ok());
+        assert!(matches!(
+            request(&socket, &Request::Status).unwrap(),
+            Response::Status(report) if report.ledger_len == 0
+        ));
+        release.send(()).unwrap();
+        assert!(matches!(
+            client.join().unwrap().unwrap(),
+            Response::Accepted { ledger_len: 1, .. }
+        ));
+
+        daemon.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_refuses_live_socket() {
+        let socket = socket_path();
+        let daemon = Daemon::spawn(Engine::new(), &socket, Duration::from_millis(10)).unwrap();
+
+        let err = Daemon::spawn(Engine::new(), &socket, Duration::from_millis(10))
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+        daemon.shutdown().unwrap();
+    }
+}
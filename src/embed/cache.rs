@@ -0,0 +1,487 @@
+// LRU/TTL embedding cache wrapping any Embedder
+
+use super::{normalize_phrase, EmbedError, Embedder};
+use crate::now_ms;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Size and age bounds for `CachedEmbedder`
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub capacity: usize,       // max cached phrases (LRU beyond this)
+    pub ttl: Option<Duration>, // None = never expire
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10_000,
+            ttl: Some(Duration::from_secs(24 * 3600)),
+        }
+    }
+}
+
+/// Counters since the cache was created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,   // dropped by the LRU bound
+    pub expirations: u64, // dropped by the TTL bound
+    pub entries: usize,
+}
+
+/// Cache key: model id + normalized phrase
+type Key = (String, String);
+
+struct Slot {
+    vector: Vec<f32>,
+    inserted_ms: u64, // wall clock, so TTL survives restarts
+    last_used: u64,   // LRU clock value
+}
+
+#[derive(Default)]
+struct State {
+    slots: HashMap<Key, Slot>,
+    lru: BTreeMap<u64, Key>, // last_used → key, oldest first
+    clock: u64,
+    stats: CacheStats,
+}
+
+/// On-disk form of one cached vector
+#[derive(Clone, Serialize, Deserialize)]
+struct Persisted {
+    model_id: String,
+    phrase: String,
+    vector: Vec<f32>,
+    inserted_ms: u64,
+}
+
+/// Memoizes another embedder's vectors
+/// Keys use the same lowercase/trim normalization as the mock embedder,
+/// plus the inner model id so vectors from different models never mix
+pub struct CachedEmbedder<E> {
+    inner: E,
+    config: CacheConfig,
+    path: Option<PathBuf>,   // persistence file, if any
+    foreign: Vec<Persisted>, // other models' entries in that file, written back as loaded
+    state: Mutex<State>,
+}
+
+impl<E: Embedder> CachedEmbedder<E> {
+    /// In-memory cache
+    pub fn new(inner: E, config: CacheConfig) -> Self {
+        Self {
+            inner,
+            config,
+            path: None,
+            foreign: Vec::new(),
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Cache persisted at `path`: loaded now, written by `save` and on drop
+    /// A missing file starts empty; entries past TTL are dropped, and entries
+    /// for other models are kept aside (outside the LRU) and saved back as-is
+    pub fn persistent(
+        inner: E,
+        config: CacheConfig,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, EmbedError> {
+        let mut cache = Self::new(inner, config);
+        cache.path = Some(path.as_ref().to_path_buf());
+
+        let bytes = match fs::read(path.as_ref()) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(cache),
+            Err(e) => return Err(e.into()),
+        };
+        let persisted: Vec<Persisted> = serde_json::from_slice(&bytes).map_err(io::Error::from)?;
+
+        let now = now_ms();
+        let dim = cache.inner.dim();
+        let model_id = cache.inner.model_id().to_string();
+        {
+            let state = cache.state.get_mut().unwrap();
+            for p in persisted {
+                if cache_expired(&cache.config, p.inserted_ms, now) {
+                    continue;
+                }
+                if p.model_id != model_id {
+                    cache.foreign.push(p);
+                    continue;
+                }
+                if p.vector.len() != dim {
+                    continue;
+                }
+                insert(
+                    state,
+                    &cache.config,
+                    (p.model_id, p.phrase),
+                    p.vector,
+                    p.inserted_ms,
+                );
+            }
+        }
+
+        Ok(cache)
+    }
+
+    /// Hit/miss counters and current size
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats {
+            entries: state.slots.len(),
+            ..state.stats
+        }
+    }
+
+    /// Wrapped embedder
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    fn key(&self, text: &str) -> Key {
+        (self.inner.model_id().to_string(), normalize_phrase(text))
+    }
+
+    /// Cached vector for `key`, refreshing its LRU position
+    fn lookup(&self, key: &Key) -> Option<Vec<f32>> {
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+
+        let expired = match state.slots.get(key) {
+            None => {
+                state.stats.misses += 1;
+                return None;
+            }
+            Some(slot) => cache_expired(&self.config, slot.inserted_ms, now_ms()),
+        };
+        if expired {
+            let slot = state.slots.remove(key).unwrap();
+            state.lru.remove(&slot.last_used);
+            state.stats.expirations += 1;
+            state.stats.misses += 1;
+            return None;
+        }
+
+        state.clock += 1;
+        let clock = state.clock;
+        let slot = state.slots.get_mut(key).unwrap();
+        state.lru.remove(&slot.last_used);
+        slot.last_used = clock;
+        state.lru.insert(clock, key.clone());
+        state.stats.hits += 1;
+
+        Some(slot.vector.clone())
+    }
+
+    fn store(&self, key: Key, vector: Vec<f32>) {
+        let mut state = self.state.lock().unwrap();
+        insert(&mut state, &self.config, key, vector, now_ms());
+    }
+}
+
+impl<E: Embedder> Embedder for CachedEmbedder<E> {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbedError> {
+        let key = self.key(text);
+        if let Some(vector) = self.lookup(&key) {
+            return Ok(vector);
+        }
+
+        let vector = self.inner.embed(text)?;
+        self.store(key, vector.clone());
+        Ok(vector)
+    }
+
+    fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
+        let keys: Vec<Key> = texts.iter().map(|t| self.key(t)).collect();
+        let mut out: Vec<Option<Vec<f32>>> = keys.iter().map(|k| self.lookup(k)).collect();
+
+        // One inner batch for all misses
+        let missing: Vec<usize> = (0..texts.len()).filter(|&i| out[i].is_none()).collect();
+        if !missing.is_empty() {
+            let batch: Vec<String> = missing.iter().map(|&i| texts[i].clone()).collect();
+            let vectors = self.inner.embed_batch(&batch)?;
+            if vectors.len() != batch.len() {
+                return Err(EmbedError::BadResponse(format!(
+                    "{} vectors for {} phrases",
+                    vectors.len(),
+                    batch.len()
+                )));
+            }
+            for (&i, vector) in missing.iter().zip(vectors) {
+                self.store(keys[i].clone(), vector.clone());
+                out[i] = Some(vector);
+            }
+        }
+
+        Ok(out.into_iter().flatten().collect())
+    }
+
+    fn dim(&self) -> usize {
+        self.inner.dim()
+    }
+
+    fn model_id(&self) -> &str {
+        self.inner.model_id()
+    }
+}
+
+impl<E> CachedEmbedder<E> {
+    /// Write all live entries, plus other models' entries, to the persistence file
+    /// No-op when in-memory
+    pub fn save(&self) -> Result<(), EmbedError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let persisted: Vec<Persisted> = {
+            let state = self.state.lock().unwrap();
+            // Oldest first, so a reload rebuilds the same LRU order
+            let own = state.lru.values().map(|key| {
+                let slot = &state.slots[key];
+                Persisted {
+                    model_id: key.0.clone(),
+                    phrase: key.1.clone(),
+                    vector: slot.vector.clone(),
+                    inserted_ms: slot.inserted_ms,
+                }
+            });
+            self.foreign.iter().cloned().chain(own).collect()
+        };
+
+        // Write-then-rename so a crash never leaves a half-written cache
+        let tmp = path.with_extension("tmp");
+        fs::write(
+            &tmp,
+            serde_json::to_vec(&persisted).map_err(io::Error::from)?,
+        )?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+impl<E> Drop for CachedEmbedder<E> {
+    fn drop(&mut self) {
+        // Best effort; call save() to observe errors
+        let _ = self.save();
+    }
+}
+
+/// Insert (or replace) an entry, evicting the least recently used beyond capacity
+fn insert(state: &mut State, config: &CacheConfig, key: Key, vector: Vec<f32>, inserted_ms: u64) {
+    if config.capacity == 0 {
+        return;
+    }
+
+    state.clock += 1;
+    let clock = state.clock;
+    if let Some(old) = state.slots.insert(
+        key.clone(),
+        Slot {
+            vector,
+            inserted_ms,
+            last_used: clock,
+        },
+    ) {
+        state.lru.remove(&old.last_used);
+    }
+    state.lru.insert(clock, key);
+
+    while state.slots.len() > config.capacity {
+        let (_, oldest) = state.lru.pop_first().unwrap();
+        state.slots.remove(&oldest);
+        state.stats.evictions += 1;
+    }
+}
+
+fn cache_expired(config: &CacheConfig, inserted_ms: u64, now: u64) -> bool {
+    match config.ttl {
+        Some(ttl) => now.saturating_sub(inserted_ms) > ttl.as_millis() as u64,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embed::EmbedService;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Mock embedder that counts how often it is actually called
+    struct Counting {
+        inner: EmbedService,
+        calls: AtomicUsize,
+    }
+
+    impl Counting {
+        fn new() -> Self {
+            Self {
+                inner: EmbedService::with_dim(8),
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Embedder for Counting {
+        fn embed(&self, text: &str) -> Result<Vec<f32>, EmbedError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self.inner.embed(text))
+        }
+
+        fn dim(&self) -> usize {
+            8
+        }
+
+        fn model_id(&self) -> &str {
+            "counting"
+        }
+    }
+
+    #[test]
+    fn test_hits_use_normalized_phrase() {
+        let cache = CachedEmbedder::new(Counting::new(), CacheConfig::default());
+
+        let a = cache.embed("Memory Safety").unwrap();
+        let b = cache.embed("  memory safety ").unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(cache.inner().calls(), 1);
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let config = CacheConfig {
+            capacity: 2,
+            ttl: None,
+        };
+        let cache = CachedEmbedder::new(Counting::new(), config);
+
+        cache.embed("a").unwrap();
+        cache.embed("b").unwrap();
+        cache.embed("a").unwrap(); // a is now most recent
+        cache.embed("c").unwrap(); // evicts b
+
+        assert_eq!(cache.stats().evictions, 1);
+        cache.embed("a").unwrap();
+        assert_eq!(cache.inner().calls(), 3);
+        cache.embed("b").unwrap();
+        assert_eq!(cache.inner().calls(), 4);
+    }
+
+    #[test]
+    fn test_ttl_expiry() {
+        let config = CacheConfig {
+            capacity: 10,
+            ttl: Some(Duration::from_millis(5)),
+        };
+        let cache = CachedEmbedder::new(Counting::new(), config);
+
+        cache.embed("memory safety").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.embed("memory safety").unwrap();
+
+        assert_eq!(cache.inner().calls(), 2);
+        assert_eq!(cache.stats().expirations, 1);
+    }
+
+    #[test]
+    fn test_batch_only_fetches_misses() {
+        let cache = CachedEmbedder::new(Counting::new(), CacheConfig::default());
+        cache.embed("a").unwrap();
+
+        let texts = vec!["a".to_string(), "b".to_string(), "A ".to_string()];
+        let vectors = cache.embed_batch(&texts).unwrap();
+
+        assert_eq!(vectors.len(), 3);
+        assert_eq!(vectors[0], vectors[2]);
+        assert_eq!(cache.inner().calls(), 2); // "a" once, "b" once
+    }
+
+    /// Backend whose batches come back one vector short
+    struct Short(Counting);
+
+    impl Embedder for Short {
+        fn embed(&self, text: &str) -> Result<Vec<f32>, EmbedError> {
+            self.0.embed(text)
+        }
+
+        fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
+            let mut vectors: Vec<Vec<f32>> = texts
+                .iter()
+                .map(|t| self.0.embed(t))
+                .collect::<Result<_, _>>()?;
+            vectors.pop();
+            Ok(vectors)
+        }
+
+        fn dim(&self) -> usize {
+            8
+        }
+
+        fn model_id(&self) -> &str {
+            "short"
+        }
+    }
+
+    #[test]
+    fn test_batch_rejects_short_reply() {
+        let cache = CachedEmbedder::new(Short(Counting::new()), CacheConfig::default());
+        let texts = vec!["a".to_string(), "b".to_string()];
+        assert!(matches!(
+            cache.embed_batch(&texts),
+            Err(EmbedError::BadResponse(_))
+        ));
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_persists_across_restarts() {
+        let path = std::env::temp_dir().join(format!("sefi_cache_{}.json", uuid::Uuid::new_v4()));
+
+        {
+            let cache =
+                CachedEmbedder::persistent(Counting::new(), CacheConfig::default(), &path).unwrap();
+            cache.embed("memory safety").unwrap();
+        } // saved on drop
+
+        let cache =
+            CachedEmbedder::persistent(Counting::new(), CacheConfig::default(), &path).unwrap();
+        assert_eq!(cache.stats().entries, 1);
+        cache.embed("memory safety").unwrap();
+        assert_eq!(cache.inner().calls(), 0);
+        drop(cache);
+
+        // A different model never sees these vectors, nor wipes them on save
+        let other =
+            CachedEmbedder::persistent(EmbedService::with_dim(8), CacheConfig::default(), &path)
+                .unwrap();
+        assert_eq!(other.stats().entries, 0);
+        other.embed("consensus").unwrap();
+        drop(other);
+
+        let cache =
+            CachedEmbedder::persistent(Counting::new(), CacheConfig::default(), &path).unwrap();
+        assert_eq!(cache.stats().entries, 1);
+        drop(cache);
+        let other =
+            CachedEmbedder::persistent(EmbedService::with_dim(8), CacheConfig::default(), &path)
+                .unwrap();
+        assert_eq!(other.stats().entries, 1);
+        drop(other);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,231 @@
+// HTTP client for the LAN embedding service
+//
+// Wire format (see ARCHITECTURE.md §3):
+//   POST /embed   body: ["phrase", ...]   →   [[f32; dim], ...]
+
+use super::{normalize_vector, EmbedError, Embedder};
+use crate::http::{self, Url};
+use std::thread;
+use std::time::Duration;
+
+/// Settings for `HttpEmbedder`
+#[derive(Debug, Clone)]
+pub struct HttpEmbedderConfig {
+    pub url: String,       // e.g. http://embedding-host:8000/embed
+    pub model_id: String,  // reported via Embedder::model_id
+    pub dim: usize,        // expected vector dimension
+    pub batch_size: usize, // max phrases per request
+    pub max_retries: u32,  // retries after the first attempt
+    pub backoff: Duration, // first retry delay, doubled each time
+    pub timeout: Duration, // connect/read/write timeout per request
+}
+
+impl Default for HttpEmbedderConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:8000/embed".to_string(),
+            model_id: "lan-embed".to_string(),
+            dim: 768,
+            batch_size: 32,
+            max_retries: 3,
+            backoff: Duration::from_millis(100),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Embedder backed by a remote `/embed` endpoint
+/// Batches phrases, retries transient failures with exponential backoff,
+/// checks every vector's dimension and unit-normalizes the result
+pub struct HttpEmbedder {
+    url: Url,
+    config: HttpEmbedderConfig,
+}
+
+impl HttpEmbedder {
+    pub fn new(config: HttpEmbedderConfig) -> Result<Self, EmbedError> {
+        let url = Url::parse(&config.url)?;
+        Ok(Self { url, config })
+    }
+
+    pub fn config(&self) -> &HttpEmbedderConfig {
+        &self.config
+    }
+
+    /// One request for one batch, with retries
+    fn request_batch(&self, batch: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
+        let body = serde_json::to_vec(batch).map_err(|e| EmbedError::BadResponse(e.to_string()))?;
+
+        let mut delay = self.config.backoff;
+        let mut attempt = 0;
+        loop {
+            match self.try_once(&body, batch.len()) {
+                Ok(vectors) => return Ok(vectors),
+                Err(e) if attempt < self.config.max_retries && is_transient(&e) => {
+                    thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn try_once(&self, body: &[u8], expected: usize) -> Result<Vec<Vec<f32>>, EmbedError> {
+        let response = http::post_json(&self.url, body, self.config.timeout)?;
+        if response.status != 200 {
+            return Err(EmbedError::Http {
+                status: response.status,
+                body: String::from_utf8_lossy(&response.body).into_owned(),
+            });
+        }
+
+        let mut vectors: Vec<Vec<f32>> = serde_json::from_slice(&response.body)
+            .map_err(|e| EmbedError::BadResponse(format!("not a JSON float matrix: {}", e)))?;
+
+        if vectors.len() != expected {
+            return Err(EmbedError::BadResponse(format!(
+                "sent {} phrases, got {} vectors",
+                expected,
+                vectors.len()
+            )));
+        }
+        for v in vectors.iter_mut() {
+            if v.len() != self.config.dim {
+                return Err(EmbedError::DimensionMismatch {
+                    expected: self.config.dim,
+                    got: v.len(),
+                });
+            }
+            if v.iter().all(|x| *x == 0.0) || v.iter().any(|x| !x.is_finite()) {
+                return Err(EmbedError::BadResponse("degenerate vector".to_string()));
+            }
+            normalize_vector(v);
+        }
+
+        Ok(vectors)
+    }
+}
+
+/// Network errors and 5xx are worth retrying; everything else is final
+fn is_transient(err: &EmbedError) -> bool {
+    match err {
+        EmbedError::Io(_) => true,
+        EmbedError::Http { status, .. } => *status >= 500 || *status == 429,
+        _ => false,
+    }
+}
+
+impl Embedder for HttpEmbedder {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbedError> {
+        let mut vectors = self.request_batch(&[text.to_string()])?;
+        Ok(vectors.remove(0))
+    }
+
+    fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
+        let mut out = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.config.batch_size.max(1)) {
+            out.extend(self.request_batch(batch)?);
+        }
+        Ok(out)
+    }
+
+    fn dim(&self) -> usize {
+        self.config.dim
+    }
+
+    fn model_id(&self) -> &str {
+        &self.config.model_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embed::mock_server::MockEmbedServer;
+    use crate::embed::EmbedService;
+
+    fn config(server: &MockEmbedServer, dim: usize) -> HttpEmbedderConfig {
+        HttpEmbedderConfig {
+            url: server.url(),
+            dim,
+            batch_size: 2,
+            max_retries: 2,
+            backoff: Duration::from_millis(1),
+            timeout: Duration::from_secs(2),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_embeds_and_normalizes() {
+        let server = MockEmbedServer::start(16).unwrap();
+        let client = HttpEmbedder::new(config(&server, 16)).unwrap();
+
+        let v = client.embed("memory safety").unwrap();
+        assert_eq!(v.len(), 16);
+        let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!(
+            (norm - 1.0).abs() < 1e-5,
+            "server scales vectors; client must normalize"
+        );
+
+        // Same vector as the in-process mock, modulo scaling
+        let local = EmbedService::with_dim(16).embed("memory safety");
+        assert!(EmbedService::cosine_similarity(&v, &local) > 0.9999);
+    }
+
+    #[test]
+    fn test_batches_requests() {
+        let server = MockEmbedServer::start(8).unwrap();
+        let client = HttpEmbedder::new(config(&server, 8)).unwrap();
+
+        let phrases: Vec<String> = (0..5).map(|i| format!("phrase {}", i)).collect();
+        let vectors = client.embed_batch(&phrases).unwrap();
+
+        assert_eq!(vectors.len(), 5);
+        assert_eq!(server.batch_sizes(), vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_retries_transient_failures() {
+        let server = MockEmbedServer::start(8).unwrap();
+        server.fail_next(2);
+        let client = HttpEmbedder::new(config(&server, 8)).unwrap();
+
+        assert!(client.embed("memory safety").is_ok());
+        assert_eq!(server.requests(), 3);
+
+        server.fail_next(3);
+        assert!(matches!(
+            client.embed("memory safety"),
+            Err(EmbedError::Http { status: 503, .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_wrong_dimension() {
+        let server = MockEmbedServer::start(8).unwrap();
+        let client = HttpEmbedder::new(config(&server, 768)).unwrap();
+
+        assert!(matches!(
+            client.embed("memory safety"),
+            Err(EmbedError::DimensionMismatch {
+                expected: 768,
+                got: 8
+            })
+        ));
+        assert_eq!(server.requests(), 1, "bad dimensions are not retried");
+    }
+
+    #[test]
+    fn test_unreachable_service_is_io_error() {
+        let server = MockEmbedServer::start(8).unwrap();
+        let mut cfg = config(&server, 8);
+        drop(server);
+        cfg.max_retries = 0;
+
+        let client = HttpEmbedder::new(cfg).unwrap();
+        assert!(matches!(client.embed("x"), Err(EmbedError::Io(_))));
+    }
+}
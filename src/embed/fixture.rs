@@ -0,0 +1,192 @@
+// Fixture embedder: hand-placed or recorded vectors, no model needed
+
+use super::{normalize_phrase, normalize_vector, EmbedError, Embedder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Lookup-table embedder for tests and replayable runs
+/// Keys are matched after the same lowercase/trim normalization the mock uses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureEmbedder {
+    model_id: String,
+    dim: usize,
+    vectors: HashMap<String, Vec<f32>>, // normalized phrase → unit vector
+}
+
+impl FixtureEmbedder {
+    /// Empty fixture; populate with `insert`
+    pub fn new(model_id: impl Into<String>, dim: usize) -> Self {
+        Self {
+            model_id: model_id.into(),
+            dim,
+            vectors: HashMap::new(),
+        }
+    }
+
+    /// Place a vector for a phrase, scaled to unit length
+    /// Zero or non-finite vectors are refused
+    pub fn insert(&mut self, phrase: &str, vector: Vec<f32>) -> Result<(), EmbedError> {
+        let vector = unit(self.dim, phrase, vector)?;
+        self.vectors.insert(normalize_phrase(phrase), vector);
+        Ok(())
+    }
+
+    /// Builder-style `insert` for hand-written test doubles
+    /// Panics on a dimension mismatch
+    pub fn with(mut self, phrase: &str, vector: Vec<f32>) -> Self {
+        self.insert(phrase, vector)
+            .expect("fixture vector dimension");
+        self
+    }
+
+    /// Record vectors for `phrases` from another embedder
+    pub fn record<E: Embedder + ?Sized>(
+        source: &E,
+        phrases: &[String],
+    ) -> Result<Self, EmbedError> {
+        let mut fixture = Self::new(source.model_id(), source.dim());
+        let vectors = source.embed_batch(phrases)?;
+        for (phrase, vector) in phrases.iter().zip(vectors) {
+            fixture.insert(phrase, vector)?;
+        }
+        Ok(fixture)
+    }
+
+    /// Load a fixture saved with `save` (or written by hand)
+    /// Vectors are checked and scaled to unit length like `insert` does
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, EmbedError> {
+        let bytes = fs::read(path)?;
+        let mut fixture: Self = serde_json::from_slice(&bytes).map_err(io::Error::from)?;
+        let dim = fixture.dim;
+        fixture.vectors = std::mem::take(&mut fixture.vectors)
+            .into_iter()
+            .map(|(phrase, vector)| Ok((phrase.clone(), unit(dim, &phrase, vector)?)))
+            .collect::<Result<_, EmbedError>>()?;
+        Ok(fixture)
+    }
+
+    /// Write the fixture as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), EmbedError> {
+        let bytes = serde_json::to_vec(self).map_err(io::Error::from)?;
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Number of phrases in the fixture
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    /// Check if the fixture is empty
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+}
+
+impl Embedder for FixtureEmbedder {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbedError> {
+        self.vectors
+            .get(&normalize_phrase(text))
+            .cloned()
+            .ok_or_else(|| EmbedError::UnknownPhrase(text.to_string()))
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+}
+
+/// `vector` checked against `dim` and scaled to unit length
+fn unit(dim: usize, phrase: &str, mut vector: Vec<f32>) -> Result<Vec<f32>, EmbedError> {
+    if vector.len() != dim {
+        return Err(EmbedError::DimensionMismatch {
+            expected: dim,
+            got: vector.len(),
+        });
+    }
+    if vector.iter().any(|x| !x.is_finite()) || vector.iter().all(|x| *x == 0.0) {
+        return Err(EmbedError::BadResponse(format!(
+            "fixture vector for {:?} is zero or not finite",
+            phrase
+        )));
+    }
+    normalize_vector(&mut vector);
+    Ok(vector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embed::EmbedService;
+
+    #[test]
+    fn test_hand_placed_vectors() {
+        let fixture = FixtureEmbedder::new("hand", 2)
+            .with("memory safety", vec![1.0, 0.0])
+            .with("borrow checker", vec![0.0, 1.0]);
+
+        assert_eq!(fixture.embed("  Memory Safety ").unwrap(), vec![1.0, 0.0]);
+        assert!(matches!(
+            fixture.embed("gc pauses"),
+            Err(EmbedError::UnknownPhrase(_))
+        ));
+
+        let mut fixture = fixture;
+        assert!(matches!(
+            fixture.insert("bad", vec![1.0]),
+            Err(EmbedError::DimensionMismatch {
+                expected: 2,
+                got: 1
+            })
+        ));
+        assert!(matches!(
+            fixture.insert("bad", vec![0.0, 0.0]),
+            Err(EmbedError::BadResponse(_))
+        ));
+
+        // Raw model outputs are scaled to unit length
+        fixture.insert("gc pauses", vec![3.0, 4.0]).unwrap();
+        assert_eq!(fixture.embed("gc pauses").unwrap(), vec![0.6, 0.8]);
+    }
+
+    #[test]
+    fn test_load_normalizes_hand_written_vectors() {
+        let path = std::env::temp_dir().join(format!("sefi_fixture_{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            r#"{"model_id": "hand", "dim": 2, "vectors": {"memory safety": [0.0, 2.0]}}"#,
+        )
+        .unwrap();
+        let loaded = FixtureEmbedder::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.embed("memory safety").unwrap(), vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_record_save_load_roundtrip() {
+        let source = EmbedService::with_dim(16);
+        let phrases = vec!["memory safety".to_string(), "borrow checker".to_string()];
+        let recorded = FixtureEmbedder::record(&source, &phrases).unwrap();
+
+        let path = std::env::temp_dir().join(format!("sefi_fixture_{}.json", uuid::Uuid::new_v4()));
+        recorded.save(&path).unwrap();
+        let loaded = FixtureEmbedder::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.model_id(), source.model_id());
+        assert_eq!(loaded.dim(), 16);
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(
+            loaded.embed("borrow checker").unwrap(),
+            source.embed("borrow checker")
+        );
+    }
+}
@@ -0,0 +1,127 @@
+// In-process stand-in for the LAN `/embed` service (offline tests, demos)
+
+use super::EmbedService;
+use crate::http;
+use std::io::{self, BufReader};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Serves `POST /embed` on 127.0.0.1 with SHA-256 mock vectors
+/// Vectors are deliberately scaled (not unit length) so clients must normalize
+pub struct MockEmbedServer {
+    addr: SocketAddr,
+    state: Arc<State>,
+    handle: Option<JoinHandle<()>>,
+}
+
+struct State {
+    embed: EmbedService,
+    shutdown: AtomicBool,
+    fail_next: AtomicU32,           // answer 503 to this many requests
+    requests: AtomicU32,            // total requests seen
+    batch_sizes: Mutex<Vec<usize>>, // phrases per successful request
+}
+
+impl MockEmbedServer {
+    /// Start on an ephemeral port, serving `dim`-dimensional vectors
+    pub fn start(dim: usize) -> io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+
+        let state = Arc::new(State {
+            embed: EmbedService::with_dim(dim),
+            shutdown: AtomicBool::new(false),
+            fail_next: AtomicU32::new(0),
+            requests: AtomicU32::new(0),
+            batch_sizes: Mutex::new(Vec::new()),
+        });
+
+        let handle = {
+            let state = Arc::clone(&state);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if state.shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        let _ = serve(stream, &state);
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            addr,
+            state,
+            handle: Some(handle),
+        })
+    }
+
+    /// Full endpoint URL, e.g. http://127.0.0.1:40123/embed
+    pub fn url(&self) -> String {
+        format!("http://{}/embed", self.addr)
+    }
+
+    /// Make the next `n` requests fail with 503
+    pub fn fail_next(&self, n: u32) {
+        self.state.fail_next.store(n, Ordering::SeqCst);
+    }
+
+    /// Requests received so far (including failed ones)
+    pub fn requests(&self) -> u32 {
+        self.state.requests.load(Ordering::SeqCst)
+    }
+
+    /// Batch size of each successfully answered request
+    pub fn batch_sizes(&self) -> Vec<usize> {
+        self.state.batch_sizes.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockEmbedServer {
+    fn drop(&mut self) {
+        self.state.shutdown.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.addr); // wake the accept loop
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn serve(stream: TcpStream, state: &State) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let Some(request) = http::read_request(&mut BufReader::new(stream))? else {
+        return Ok(());
+    };
+    state.requests.fetch_add(1, Ordering::SeqCst);
+
+    if request.method != "POST" || request.path != "/embed" {
+        return http::write_response(&mut writer, 404, "text/plain", b"not found");
+    }
+
+    let failing = state
+        .fail_next
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        .is_ok();
+    if failing {
+        return http::write_response(&mut writer, 503, "text/plain", b"warming up");
+    }
+
+    let phrases: Vec<String> = match serde_json::from_slice(&request.body) {
+        Ok(phrases) => phrases,
+        Err(e) => {
+            return http::write_response(&mut writer, 422, "text/plain", e.to_string().as_bytes())
+        }
+    };
+    state.batch_sizes.lock().unwrap().push(phrases.len());
+
+    let vectors: Vec<Vec<f32>> = phrases
+        .iter()
+        .map(|p| state.embed.embed(p).into_iter().map(|x| x * 3.0).collect())
+        .collect();
+    let body = serde_json::to_vec(&vectors)?;
+
+    http::write_response(&mut writer, 200, "application/json", &body)
+}
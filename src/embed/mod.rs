@@ -1,39 +1,134 @@
 // Embedding service client (mock in Phase 1)
 
+pub mod cache;
+pub mod client;
+pub mod fixture;
+pub mod mock_server;
+
 use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io;
 
 const EMBEDDING_DIM: usize = 768;
 
+/// Why an embedding could not be produced
+#[derive(Debug)]
+pub enum EmbedError {
+    DimensionMismatch { expected: usize, got: usize },
+    UnknownPhrase(String),              // fixture has no vector for this phrase
+    Http { status: u16, body: String }, // non-200 from the embedding service
+    BadResponse(String),                // malformed or inconsistent reply
+    Io(io::Error),
+}
+
+impl fmt::Display for EmbedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmbedError::DimensionMismatch { expected, got } => {
+                write!(f, "embedding has {} dims, expected {}", got, expected)
+            }
+            EmbedError::UnknownPhrase(phrase) => write!(f, "no embedding for {:?}", phrase),
+            EmbedError::Http { status, body } => {
+                write!(f, "embedding service returned {}: {}", status, body)
+            }
+            EmbedError::BadResponse(msg) => write!(f, "bad embedding response: {}", msg),
+            EmbedError::Io(err) => write!(f, "embedding I/O failed: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for EmbedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EmbedError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EmbedError {
+    fn from(err: io::Error) -> Self {
+        EmbedError::Io(err)
+    }
+}
+
+/// Anything that maps phrases to fixed-dimension unit vectors
+/// Engine, ledger and clustering only ever see the vectors this returns, and
+/// score similarity as a plain dot product: implementations must return
+/// vectors of L2 norm 1 (see `normalize_vector`)
+pub trait Embedder {
+    /// Embed a single phrase
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbedError>;
+
+    /// Embed many phrases (override when the backend batches natively)
+    fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
+        texts.iter().map(|t| self.embed(t)).collect()
+    }
+
+    /// Dimension of every returned vector
+    fn dim(&self) -> usize;
+
+    /// Stable identifier of the model behind the vectors
+    fn model_id(&self) -> &str;
+}
+
+impl<E: Embedder + ?Sized> Embedder for Box<E> {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbedError> {
+        (**self).embed(text)
+    }
+
+    fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, EmbedError> {
+        (**self).embed_batch(texts)
+    }
+
+    fn dim(&self) -> usize {
+        (**self).dim()
+    }
+
+    fn model_id(&self) -> &str {
+        (**self).model_id()
+    }
+}
+
 /// Mock embedding service for Phase 1
-/// Generates deterministic 768d vectors from text using hash-based approach
+/// Generates deterministic vectors (768d by default) from text using hash-based approach
 /// Properties:
 /// - Deterministic (same text → same vector)
 /// - Unit normalized (||v|| = 1)
-/// - Semantic similarity approximated by character overlap
+/// - No semantics: different phrases are near-orthogonal
 pub struct EmbedService {
-    _placeholder: (),
+    dim: usize,
+    model_id: String,
 }
 
 impl EmbedService {
     pub fn new() -> Self {
-        Self { _placeholder: () }
+        Self::with_dim(EMBEDDING_DIM)
+    }
+
+    /// Mock service producing `dim`-dimensional vectors
+    pub fn with_dim(dim: usize) -> Self {
+        Self {
+            dim,
+            model_id: format!("sefi-mock-sha256-{}", dim),
+        }
     }
 
     /// Generate embedding vector for a phrase
-    /// Returns a normalized 768d vector
+    /// Returns a normalized vector of `dim` dimensions
     pub fn embed(&self, text: &str) -> Vec<f32> {
         // Normalize text
-        let normalized = text.to_lowercase().trim().to_string();
+        let normalized = normalize_phrase(text);
 
         // Generate base hash
         let mut hasher = Sha256::new();
         hasher.update(normalized.as_bytes());
         let hash = hasher.finalize();
 
-        // Expand hash to 768 dimensions using multiple rounds
-        let mut vector = Vec::with_capacity(EMBEDDING_DIM);
+        // Expand hash to `dim` dimensions using multiple rounds
+        let mut vector = Vec::with_capacity(self.dim);
 
-        for i in 0..EMBEDDING_DIM {
+        for i in 0..self.dim {
             let mut round_hasher = Sha256::new();
             round_hasher.update(hash);
             round_hasher.update((i as u32).to_le_bytes());
@@ -75,8 +170,28 @@ impl Default for EmbedService {
     }
 }
 
+impl Embedder for EmbedService {
+    fn embed(&self, text: &str) -> Result<Vec<f32>, EmbedError> {
+        Ok(EmbedService::embed(self, text))
+    }
+
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+}
+
+/// Canonical form of a phrase: lowercase, surrounding whitespace trimmed
+/// Shared by the mock, fixtures and the cache so keys always agree
+pub fn normalize_phrase(text: &str) -> String {
+    text.to_lowercase().trim().to_string()
+}
+
 /// Normalize vector to unit length (L2 norm = 1)
-fn normalize_vector(v: &mut [f32]) {
+pub fn normalize_vector(v: &mut [f32]) {
     let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
 
     if norm > 1e-10 {
@@ -129,4 +244,20 @@ mod tests {
         assert!((-1.0..=1.0).contains(&sim_diff), "Cosine similarity should be in [-1, 1]");
         assert!(sim_diff < 0.99, "Different texts should have different embeddings");
     }
+
+    #[test]
+    fn test_embedder_trait_reports_dim_and_model() {
+        let service = EmbedService::with_dim(64);
+        let embedder: &dyn Embedder = &service;
+
+        assert_eq!(embedder.dim(), 64);
+        assert_eq!(embedder.model_id(), "sefi-mock-sha256-64");
+
+        let batch = embedder
+            .embed_batch(&["memory safety".to_string(), "borrow checker".to_string()])
+            .unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0], service.embed("memory safety"));
+        assert!(batch.iter().all(|v| v.len() == 64));
+    }
 }
@@ -0,0 +1,1061 @@
+// Engine: ledger + embedding + clustering + feedback in one loop
+
+use crate::clustering::urgent::UrgentTracker;
+use crate::clustering::{ClusterEngine, TimedEvent};
+use crate::embed::{normalize_vector, EmbedError, EmbedService, Embedder};
+use crate::feedback;
+use crate::governor::{Adjustment, Governor};
+use crate::ledger::store::Ledger;
+use crate::types::{BasinFeedback, BasinType, ConceptPacket, LedgerEntry};
+use crate::validator;
+use crate::viz::projection::{Fitted, Projector, Refit};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+/// Feedback kept for `recent()` (e.g. the `sefi top` feed)
+pub const RECENT_FEEDBACK: usize = 32;
+
+/// Cluster lifecycle events kept for `events()`
+pub const RECENT_EVENTS: usize = 256;
+
+/// Seconds of ingest history kept for `ingest_rate()`
+pub const INGEST_WINDOW: usize = 60;
+
+/// What was last reported for a basin
+struct Reported {
+    type_: BasinType,
+    flipped: u32,  // consecutive mature ticks spent as another type
+    cohesion: f32, // nd_cohesion as reported
+}
+
+/// Reasons a packet can be rejected at ingest
+#[derive(Debug)]
+pub enum EngineError {
+    EmptyPhrase,
+    DuplicateRationale(String), // rationale_hash already in ledger
+    Embed(EmbedError),          // embedder failed or returned a bad vector
+    Storage(io::Error),         // durable ledger write failed
+    ModelMismatch {
+        expected: String,    // the engine's embedder, or the model in the ledger headers
+        got: Option<String>, // what the packet claimed for its center
+    },
+    BadCenter(String), // precomputed vector is zero or not finite
+    OutOfRange {
+        field: &'static str, // amp must lie in [0, 1], sigma be finite and > 0
+        value: f32,
+    },
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::EmptyPhrase => write!(f, "packet phrase is empty"),
+            EngineError::DuplicateRationale(hash) => {
+                write!(f, "rationale_hash already ingested: {}", hash)
+            }
+            EngineError::Embed(err) => write!(f, "embedding failed: {}", err),
+            EngineError::Storage(err) => write!(f, "ledger write failed: {}", err),
+            EngineError::ModelMismatch { expected, got } => match got {
+                Some(got) => write!(f, "center is from model {}, expected {}", got, expected),
+                None => write!(f, "center has no model id, expected {}", expected),
+            },
+            EngineError::BadCenter(reason) => write!(f, "bad center vector: {}", reason),
+            EngineError::OutOfRange { field, value } => {
+                write!(f, "packet {} out of range: {}", field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EngineError::Embed(err) => Some(err),
+            EngineError::Storage(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EngineError {
+    fn from(err: io::Error) -> Self {
+        EngineError::Storage(err)
+    }
+}
+
+impl From<EmbedError> for EngineError {
+    fn from(err: EmbedError) -> Self {
+        EngineError::Embed(err)
+    }
+}
+
+/// Single entry point: owns the ledger, embedder and cluster engine
+/// ingest() stores packets, tick() turns mature clusters into BasinFeedback
+/// High-amp Urgent packets skip the wait: ingest() returns their alert
+/// Generic over the embedder; defaults to the SHA-256 mock
+pub struct Engine<E = EmbedService> {
+    ledger: Ledger,
+    embedder: Arc<E>, // shared so callers can embed without holding the engine
+    clusters: ClusterEngine,
+    urgent: UrgentTracker,
+    phrases: HashMap<String, String>,   // rationale_hash -> phrase
+    emitted: HashMap<String, Reported>, // basin id -> last report
+    governor: Option<Governor>,         // adaptive thresholds (None = fixed)
+    adjustments: Vec<Adjustment>,       // governor changes not yet taken
+    projector: Projector,               // 2-D coordinates for feedback (display only)
+    recent: VecDeque<BasinFeedback>,    // last RECENT_FEEDBACK emitted, oldest first
+    events: VecDeque<TimedEvent>,       // last RECENT_EVENTS lifecycle events, oldest first
+    ingested: [u32; INGEST_WINDOW],     // packets per second, ring indexed by epoch second
+    ingested_at: u64,                   // epoch second of the newest bucket
+    clock: fn() -> u64,                 // engine time (ms epoch) seen by ingest()
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::with_ledger(Ledger::new())
+    }
+
+    /// Build an engine over an existing (e.g. recovered on-disk) ledger
+    pub fn with_ledger(ledger: Ledger) -> Self {
+        Self::with_embedder(ledger, EmbedService::new())
+    }
+}
+
+impl<E: Embedder> Engine<E> {
+    /// Build an engine with a specific embedder
+    pub fn with_embedder(ledger: Ledger, embedder: E) -> Self {
+        let phrases = ledger
+            .entries()
+            .iter()
+            .map(|e| (e.rationale_hash.clone(), e.phrase.clone()))
+            .collect();
+        // One urgent config: the tracker and clustering agree on what it takes
+        let clusters = ClusterEngine::new();
+        let urgent = UrgentTracker::with_config(clusters.config().urgent.clone());
+
+        Self {
+            ledger,
+            embedder: Arc::new(embedder),
+            clusters,
+            urgent,
+            phrases,
+            emitted: HashMap::new(),
+            governor: None,
+            adjustments: Vec::new(),
+            projector: Projector::new(),
+            recent: VecDeque::new(),
+            events: VecDeque::new(),
+            ingested: [0; INGEST_WINDOW],
+            ingested_at: 0,
+            clock: crate::now_ms,
+        }
+    }
+
+    /// Read engine time from `clock` instead of the wall clock
+    pub fn with_clock(mut self, clock: fn() -> u64) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Let `governor` retune the clustering thresholds after each tick
+    pub fn with_governor(mut self, governor: Governor) -> Self {
+        self.governor = Some(governor);
+        self
+    }
+
+    /// Embed a packet's phrase and append it to the ledger
+    /// A precomputed `center` from the embedder's model skips the embedding call
+    /// Packets landing in a fresh peak have their amp damped first
+    /// Returns alert feedback when the packet raises a new urgent alert
+    pub fn ingest(
+        &mut self,
+        mut packet: ConceptPacket,
+    ) -> Result<Option<BasinFeedback>, EngineError> {
+        self.check(&packet)?;
+        let vector = vectorize(&*self.embedder, &mut packet)?;
+        self.ingest_vector(packet, vector)
+    }
+
+    /// Reject a packet before spending an embedding on it
+    pub fn check(&self, packet: &ConceptPacket) -> Result<(), EngineError> {
+        if packet.phrase.trim().is_empty() {
+            return Err(EngineError::EmptyPhrase);
+        }
+        if !(0.0..=1.0).contains(&packet.amp) {
+            return Err(EngineError::OutOfRange {
+                field: "amp",
+                value: packet.amp,
+            });
+        }
+        if !(packet.sigma.is_finite() && packet.sigma > 0.0) {
+            return Err(EngineError::OutOfRange {
+                field: "sigma",
+                value: packet.sigma,
+            });
+        }
+        if self.ledger.get(&packet.rationale_hash).is_some() {
+            return Err(EngineError::DuplicateRationale(
+                packet.rationale_hash.clone(),
+            ));
+        }
+        // The embedder is checked in `vectorize`; the ledger needs engine state
+        if let (Some(_), Some(recorded)) = (&packet.center, self.ledger.model()) {
+            if packet.model.as_deref() != Some(recorded.model_id.as_str()) {
+                return Err(EngineError::ModelMismatch {
+                    expected: recorded.model_id.clone(),
+                    got: packet.model.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Append a packet whose vector came from `vectorize`
+    /// Checks run again: another caller may have ingested the same hash meanwhile
+    pub fn ingest_vector(
+        &mut self,
+        mut packet: ConceptPacket,
+        vector: Vec<f32>,
+    ) -> Result<Option<BasinFeedback>, EngineError> {
+        self.check(&packet)?;
+
+        packet.amp *= self
+            .clusters
+            .damping(&self.ledger, &vector, packet.timestamp);
+
+        let hash = packet.rationale_hash.clone();
+        let phrase = packet.phrase.clone();
+        self.ledger.append(packet, vector)?;
+        self.phrases.insert(hash.clone(), phrase);
+        let now = (self.clock)();
+        self.count_ingest(now);
+
+        let Some(entry) = self.ledger.get(&hash) else {
+            return Ok(None);
+        };
+        let mut alert = self
+            .urgent
+            .observe(entry, now)
+            .map(feedback::alert_feedback);
+        if let Some(basin) = &mut alert {
+            basin.coords_2d = self.projector.project(&entry.vector).unwrap_or_default();
+        }
+        if let Some(basin) = &alert {
+            self.remember(basin);
+        }
+        Ok(alert)
+    }
+
+    /// Advance clustering to `now` (ms epoch)
+    /// Returns feedback for clusters and ridges that matured since the last tick
+    pub fn tick(&mut self, now: u64) -> Vec<BasinFeedback> {
+        self.urgent.expire(now);
+        let mut mature = self.clusters.tick(&self.ledger, now, &self.phrases);
+        mature.sort(); // deterministic emission order
+        for event in self.clusters.events() {
+            if self.events.len() == RECENT_EVENTS {
+                self.events.pop_front();
+            }
+            self.events.push_back(TimedEvent {
+                at: now,
+                event: event.clone(),
+            });
+        }
+
+        let mut feedback = Vec::new();
+        for cluster_id in mature {
+            let Some(cluster) = self.clusters.get_cluster(&cluster_id) else {
+                continue;
+            };
+            // Reported again once a type change (e.g. a valley became a peak)
+            // has held as long as a new basin must, so flapping stays quiet
+            let type_ = cluster.basin_type();
+            let hold = self.clusters.config().persistence_min(cluster.tempo);
+            match self.emitted.get_mut(&cluster_id) {
+                Some(reported) if reported.type_ == type_ => {
+                    reported.flipped = 0;
+                    continue;
+                }
+                Some(reported) => {
+                    reported.flipped += 1;
+                    if reported.flipped < hold {
+                        continue;
+                    }
+                }
+                None => {}
+            }
+            if let Some(basin) = self.build_feedback(&cluster_id, now) {
+                feedback.push(basin);
+            }
+        }
+
+        for ridge in self.clusters.ridges().mature() {
+            if self.emitted.contains_key(&ridge.id) {
+                continue;
+            }
+            feedback.push(feedback::ridge_feedback(
+                ridge,
+                &self.ledger,
+                &self.clusters.config().snapshot(ridge.tempo),
+                now,
+            ));
+        }
+        for basin in &mut feedback {
+            if let Some(medoid) = self.ledger.get(&basin.rep_id) {
+                basin.coords_2d = self.projector.project(&medoid.vector).unwrap_or_default();
+            }
+            let reported = Reported {
+                type_: basin.type_,
+                flipped: 0,
+                cohesion: basin.nd_cohesion,
+            };
+            self.emitted.insert(basin.basin_id.clone(), reported);
+        }
+        for basin in &feedback {
+            self.remember(basin);
+        }
+
+        // Forget clusters and ridges that have decayed away
+        let clusters = &self.clusters;
+        self.emitted.retain(|id, _| {
+            clusters.get_cluster(id).is_some() || clusters.ridges().get(id).is_some()
+        });
+
+        if let Some(governor) = &mut self.governor {
+            governor.observe(&feedback, now);
+            let mut config = self.clusters.config().clone();
+            let live = self.clusters.live_points();
+            let adjustments = governor.adjust(&mut config, live, now);
+            if !adjustments.is_empty() {
+                self.clusters.set_config(config);
+                self.adjustments.extend(adjustments);
+            }
+        }
+
+        feedback
+    }
+
+    /// Count one accepted packet in the per-second ingest ring
+    fn count_ingest(&mut self, now: u64) {
+        let second = now / 1000;
+        if second + (INGEST_WINDOW as u64) <= self.ingested_at {
+            return; // clock went back past the window
+        }
+        // Clear the buckets of seconds skipped since the newest one
+        let gap = second
+            .saturating_sub(self.ingested_at)
+            .min(INGEST_WINDOW as u64);
+        for s in second + 1 - gap..=second {
+            self.ingested[s as usize % INGEST_WINDOW] = 0;
+        }
+        self.ingested_at = self.ingested_at.max(second);
+        self.ingested[second as usize % INGEST_WINDOW] += 1;
+    }
+
+    fn remember(&mut self, basin: &BasinFeedback) {
+        if self.recent.len() == RECENT_FEEDBACK {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(basin.clone());
+    }
+
+    /// Validate a mature cluster against its neighbours and package it
+    fn build_feedback(&self, cluster_id: &str, now: u64) -> Option<BasinFeedback> {
+        let cluster = self.clusters.get_cluster(cluster_id)?;
+        let others: Vec<Vec<String>> = self
+            .clusters
+            .clusters()
+            .filter(|c| c.id != cluster.id)
+            .map(|c| c.members.clone())
+            .collect();
+        let basin = validator::validate_basin(&cluster.members, &self.ledger, &others)?;
+
+        Some(feedback::basin_feedback(
+            cluster,
+            basin,
+            &self.ledger,
+            &self.clusters.config().snapshot(cluster.tempo),
+            now,
+        ))
+    }
+
+    /// Underlying ledger (read-only)
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    /// Flush the ledger to disk (no-op when in-memory)
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.ledger.sync()
+    }
+
+    /// Underlying cluster engine (read-only)
+    pub fn clusters(&self) -> &ClusterEngine {
+        &self.clusters
+    }
+
+    /// Active urgent alerts (read-only)
+    pub fn urgent(&self) -> &UrgentTracker {
+        &self.urgent
+    }
+
+    /// Display projection of the ledger (see `refit_projection`)
+    pub fn projector(&self) -> &Projector {
+        &self.projector
+    }
+
+    /// Refit the display projection if due; returns whether it did
+    /// Feedback from later ticks is placed with the new basis
+    pub fn refit_projection(&mut self, now: u64) -> bool {
+        self.projector.refit(&self.ledger, now)
+    }
+
+    /// Split form of `refit_projection`: take the sample here, run the
+    /// costly fit elsewhere (e.g. with the engine unlocked), then install it
+    pub fn prepare_refit(&self, now: u64) -> Option<Refit> {
+        self.projector.prepare(&self.ledger, now)
+    }
+
+    /// Adopt a basis fitted from `prepare_refit`
+    pub fn install_refit(&mut self, fitted: Fitted) {
+        self.projector.install(fitted);
+    }
+
+    /// Threshold governor, if enabled
+    pub fn governor(&self) -> Option<&Governor> {
+        self.governor.as_ref()
+    }
+
+    /// Governor changes made by `tick` since the last call, oldest first
+    /// Unlike the governor's bounded history, nothing is skipped; with a
+    /// governor enabled, call this regularly to keep the queue short
+    pub fn take_adjustments(&mut self) -> Vec<Adjustment> {
+        std::mem::take(&mut self.adjustments)
+    }
+
+    /// Feedback emitted lately by `tick` and `ingest`, oldest first
+    pub fn recent(&self) -> impl Iterator<Item = &BasinFeedback> {
+        self.recent.iter()
+    }
+
+    /// Packets accepted per second over the INGEST_WINDOW seconds up to `now`, oldest first
+    pub fn ingest_rate(&self, now: u64) -> Vec<u32> {
+        let second = now / 1000;
+        (0..INGEST_WINDOW as u64)
+            .rev()
+            .map(|age| match second.checked_sub(age) {
+                Some(s)
+                    if s <= self.ingested_at && s + (INGEST_WINDOW as u64) > self.ingested_at =>
+                {
+                    self.ingested[s as usize % INGEST_WINDOW]
+                }
+                _ => 0,
+            })
+            .collect()
+    }
+
+    /// nd_cohesion of a basin as last reported (None if never reported)
+    pub fn reported_cohesion(&self, basin_id: &str) -> Option<f32> {
+        self.emitted.get(basin_id).map(|r| r.cohesion)
+    }
+
+    /// Live attracting entries, as tracked by the cluster engine
+    pub fn live_entries(&self) -> impl Iterator<Item = &LedgerEntry> {
+        let entries = self.ledger.entries();
+        self.clusters.live().map(move |i| &entries[i])
+    }
+
+    /// Cluster lifecycle events of recent ticks, oldest first
+    pub fn events(&self) -> impl Iterator<Item = &TimedEvent> {
+        self.events.iter()
+    }
+
+    /// Embedder used for packet phrases
+    pub fn embedder(&self) -> &E {
+        &self.embedder
+    }
+
+    /// Handle on the embedder for use outside the engine (e.g. without its lock)
+    pub fn shared_embedder(&self) -> Arc<E> {
+        Arc::clone(&self.embedder)
+    }
+
+    /// Phrase recorded for a rationale_hash
+    pub fn phrase(&self, rationale_hash: &str) -> Option<&str> {
+        self.phrases.get(rationale_hash).map(|s| s.as_str())
+    }
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Vector for `packet`: its checked `center`, else the embedder's output
+/// Needs no engine state, so slow embedders never run under an engine lock
+pub fn vectorize<E: Embedder + ?Sized>(
+    embedder: &E,
+    packet: &mut ConceptPacket,
+) -> Result<Vec<f32>, EngineError> {
+    let vector = match packet.center.take() {
+        Some(center) => check_center(embedder, center, packet.model.as_deref())?,
+        None => embedder.embed(&packet.phrase)?,
+    };
+    check_dim(embedder, vector)
+}
+
+/// `vectorize` for many packets with a single `embed_batch` call
+/// Err only when that call fails; otherwise one result per packet, in order
+pub fn vectorize_batch<E: Embedder + ?Sized>(
+    embedder: &E,
+    packets: &mut [ConceptPacket],
+) -> Result<Vec<Result<Vec<f32>, EngineError>>, EngineError> {
+    let phrases: Vec<String> = packets
+        .iter()
+        .filter(|p| p.center.is_none())
+        .map(|p| p.phrase.clone())
+        .collect();
+    let mut embedded = if phrases.is_empty() {
+        Vec::new()
+    } else {
+        embedder.embed_batch(&phrases)?
+    }
+    .into_iter();
+
+    Ok(packets
+        .iter_mut()
+        .map(|packet| {
+            let vector = match packet.center.take() {
+                Some(center) => check_center(embedder, center, packet.model.as_deref())?,
+                None => embedded.next().ok_or_else(|| {
+                    EngineError::Embed(EmbedError::BadResponse(
+                        "batch returned too few vectors".to_string(),
+                    ))
+                })?,
+            };
+            check_dim(embedder, vector)
+        })
+        .collect())
+}
+
+/// A submitted vector must come from our model; normalized like embedder output
+/// Only the embedder is consulted here; `Engine::check` holds it to the ledger's model
+fn check_center<E: Embedder + ?Sized>(
+    embedder: &E,
+    mut center: Vec<f32>,
+    model: Option<&str>,
+) -> Result<Vec<f32>, EngineError> {
+    let expected = embedder.model_id();
+    if model != Some(expected) {
+        return Err(EngineError::ModelMismatch {
+            expected: expected.to_string(),
+            got: model.map(str::to_string),
+        });
+    }
+    if center.iter().any(|x| !x.is_finite()) {
+        return Err(EngineError::BadCenter("not finite".to_string()));
+    }
+    if center.iter().all(|x| *x == 0.0) {
+        return Err(EngineError::BadCenter("all zeros".to_string()));
+    }
+    normalize_vector(&mut center);
+    Ok(center)
+}
+
+fn check_dim<E: Embedder + ?Sized>(
+    embedder: &E,
+    vector: Vec<f32>,
+) -> Result<Vec<f32>, EngineError> {
+    if vector.len() != embedder.dim() {
+        return Err(EmbedError::DimensionMismatch {
+            expected: embedder.dim(),
+            got: vector.len(),
+        }
+        .into());
+    }
+    Ok(vector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clustering::ClusterEvent;
+    use crate::ledger::segment::ModelInfo;
+    use crate::types::{Action, Polarity, Tempo};
+
+    fn packet(phrase: &str, hash: &str, timestamp: u64) -> ConceptPacket {
+        ConceptPacket {
+            phrase: phrase.to_string(),
+            amp: 0.8,
+            sigma: 1.0,
+            polarity: Polarity::Attract,
+            tempo: Tempo::Slow,
+            provenance: "test".to_string(),
+            agent_id: "agent1".to_string(),
+            rationale_hash: hash.to_string(),
+            timestamp,
+            center: None,
+            model: None,
+        }
+    }
+
+    #[test]
+    fn test_ingest_stores_phrase_and_vector() {
+        let mut engine = Engine::new();
+        engine.ingest(packet("memory safety", "h1", 1000)).unwrap();
+
+        assert_eq!(engine.ledger().len(), 1);
+        assert_eq!(engine.phrase("h1"), Some("memory safety"));
+        assert_eq!(engine.ledger().get("h1").unwrap().vector.len(), 768);
+    }
+
+    #[test]
+    fn test_ingest_rejects_bad_packets() {
+        let mut engine = Engine::new();
+        engine.ingest(packet("memory safety", "h1", 1000)).unwrap();
+
+        assert!(matches!(
+            engine.ingest(packet("again", "h1", 1001)),
+            Err(EngineError::DuplicateRationale(h)) if h == "h1"
+        ));
+        assert!(matches!(
+            engine.ingest(packet("   ", "h2", 1002)),
+            Err(EngineError::EmptyPhrase)
+        ));
+        assert_eq!(engine.ledger().len(), 1);
+    }
+
+    #[test]
+    fn test_ingest_precomputed_center() {
+        let mut engine = Engine::new();
+        let model = engine.embedder().model_id().to_string();
+        let center = engine.embedder().embed("memory safety");
+
+        // Used as-is (after normalizing), whatever the phrase would embed to
+        let mut with_center = packet("some other words", "h1", 1000);
+        with_center.center = Some(center.iter().map(|x| x * 4.0).collect());
+        with_center.model = Some(model.clone());
+        engine.ingest(with_center.clone()).unwrap();
+        let stored = &engine.ledger().get("h1").unwrap().vector;
+        assert!(stored
+            .iter()
+            .zip(&center)
+            .all(|(a, b)| (a - b).abs() < 1e-6));
+
+        let reject = |engine: &mut Engine, center: Vec<f32>, model: Option<&str>| {
+            let mut p = with_center.clone();
+            p.rationale_hash = "h2".to_string();
+            p.center = Some(center);
+            p.model = model.map(str::to_string);
+            engine.ingest(p).unwrap_err()
+        };
+        assert!(matches!(
+            reject(&mut engine, center.clone(), Some("other-model")),
+            EngineError::ModelMismatch { got: Some(m), .. } if m == "other-model"
+        ));
+        assert!(matches!(
+            reject(&mut engine, center.clone(), None),
+            EngineError::ModelMismatch { got: None, .. }
+        ));
+        assert!(matches!(
+            reject(&mut engine, vec![1.0; 3], Some(&model)),
+            EngineError::Embed(EmbedError::DimensionMismatch { got: 3, .. })
+        ));
+        assert!(matches!(
+            reject(&mut engine, vec![0.0; center.len()], Some(&model)),
+            EngineError::BadCenter(_)
+        ));
+        let mut nan = center.clone();
+        nan[0] = f32::NAN;
+        assert!(matches!(
+            reject(&mut engine, nan, Some(&model)),
+            EngineError::BadCenter(_)
+        ));
+        assert_eq!(engine.ledger().len(), 1);
+    }
+
+    #[test]
+    fn test_tick_emits_mature_basin_once() {
+        let mut engine = Engine::new();
+
+        // Same phrase from three agents → identical vectors → one cluster
+        for i in 0..3 {
+            engine
+                .ingest(packet("memory safety", &format!("h{}", i), 1000))
+                .unwrap();
+        }
+
+        // Found on the first tick, reported once it survives the second
+        assert!(engine.tick(1000).is_empty());
+        let feedback = engine.tick(1100);
+        assert_eq!(feedback.len(), 1);
+
+        let basin = &feedback[0];
+        assert_eq!(basin.type_, BasinType::Valley);
+        assert_eq!(basin.rep_phrase, "memory safety");
+        assert_eq!(basin.contributors.len(), 3);
+        assert!(basin.nd_radius < 1e-5);
+        assert!((basin.nd_cohesion - 1.0).abs() < 1e-5, "lone, tight basin");
+        let card = basin.precard.as_ref().unwrap();
+        assert!(card.summary.starts_with("Consensus: memory safety."));
+        assert_eq!(card.top_phrases, vec!["memory safety"]);
+        assert_eq!(basin.thresholds.as_ref().unwrap().persistence_min, 2);
+
+        // Already reported → not emitted again
+        assert!(engine.tick(1200).is_empty());
+        let recent: Vec<&str> = engine.recent().map(|b| b.rep_phrase.as_str()).collect();
+        assert_eq!(recent, vec!["memory safety"]);
+
+        // Lifecycle events outlive the tick that reported them
+        let born = TimedEvent {
+            at: 1000,
+            event: ClusterEvent::Born {
+                id: basin.basin_id.clone(),
+            },
+        };
+        assert_eq!(engine.events().collect::<Vec<_>>(), vec![&born]);
+    }
+
+    #[test]
+    fn test_ingest_rate_counts_arrivals_per_second() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static NOW: AtomicU64 = AtomicU64::new(5_000);
+        let mut engine = Engine::new().with_clock(|| NOW.load(Ordering::SeqCst));
+
+        let arrive = |engine: &mut Engine, hash: &str, at: u64| {
+            NOW.store(at, Ordering::SeqCst);
+            engine.ingest(packet(hash, hash, 1000)).unwrap();
+        };
+        arrive(&mut engine, "a", 5_000);
+        arrive(&mut engine, "b", 5_900);
+        arrive(&mut engine, "c", 7_500);
+        let rate = engine.ingest_rate(8_000);
+        assert_eq!(rate.len(), INGEST_WINDOW);
+        assert_eq!(rate[INGEST_WINDOW - 4..], [2, 0, 1, 0]);
+
+        // A minute later the old seconds have rolled out of the ring
+        arrive(&mut engine, "d", 200_000);
+        let rate = engine.ingest_rate(200_000);
+        assert_eq!(rate.iter().sum::<u32>(), 1);
+        assert_eq!(rate[INGEST_WINDOW - 1], 1);
+    }
+
+    #[test]
+    fn test_generic_over_embedder() {
+        use crate::embed::fixture::FixtureEmbedder;
+
+        // Hand-placed 3-d vectors: two near-duplicates and one outlier
+        let fixture = FixtureEmbedder::new("hand", 3)
+            .with("memory safety", vec![1.0, 0.0, 0.0])
+            .with("memory safe code", vec![0.99, 0.141, 0.0])
+            .with("gc pauses", vec![0.0, 0.0, 1.0]);
+        let mut engine = Engine::with_embedder(Ledger::new(), fixture);
+
+        engine.ingest(packet("memory safety", "h0", 1000)).unwrap();
+        engine
+            .ingest(packet("memory safe code", "h1", 1000))
+            .unwrap();
+        engine.ingest(packet("gc pauses", "h2", 1000)).unwrap();
+        assert!(matches!(
+            engine.ingest(packet("unplaced", "h3", 1000)),
+            Err(EngineError::Embed(_))
+        ));
+
+        engine.tick(1000);
+        let feedback = engine.tick(1100);
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].contributors.len(), 2);
+        assert_eq!(engine.ledger().dim(), Some(3));
+    }
+
+    #[test]
+    fn test_with_ledger_restores_phrases() {
+        let dir = std::env::temp_dir().join(format!("sefi_engine_{}", uuid::Uuid::new_v4()));
+
+        let mock = EmbedService::new();
+        let model = ModelInfo::new(mock.model_id(), mock.dim());
+        {
+            let mut engine = Engine::with_ledger(Ledger::open(&dir, model.clone()).unwrap());
+            engine.ingest(packet("memory safety", "h1", 1000)).unwrap();
+            engine.sync().unwrap();
+        }
+
+        let engine = Engine::with_ledger(Ledger::open(&dir, model).unwrap());
+        assert_eq!(engine.ledger().len(), 1);
+        assert_eq!(engine.phrase("h1"), Some("memory safety"));
+
+        // Vectors from another model never mix into this ledger
+        let other = ModelInfo::new("sefi-mock-sha256-16", 16);
+        assert!(Ledger::open(&dir, other).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_center_must_match_ledger_model() {
+        let dir = std::env::temp_dir().join(format!("sefi_engine_{}", uuid::Uuid::new_v4()));
+        let recorded = ModelInfo::new("recorded-model", EmbedService::new().dim());
+        let mut engine = Engine::with_ledger(Ledger::open(&dir, recorded).unwrap());
+
+        // The embedder would accept it, but the durable ledger was written by another model
+        let mut p = packet("memory safety", "h1", 1000);
+        p.center = Some(engine.embedder().embed("memory safety"));
+        p.model = Some(engine.embedder().model_id().to_string());
+        assert!(matches!(
+            engine.ingest(p.clone()),
+            Err(EngineError::ModelMismatch { expected, .. }) if expected == "recorded-model"
+        ));
+
+        // Phrases are still embedded as usual
+        p.center = None;
+        engine.ingest(p).unwrap();
+        assert_eq!(engine.ledger().len(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_out_of_range_amp_and_sigma() {
+        let mut engine = Engine::new();
+        let cases = [
+            ("amp", 1.5, 1.0),
+            ("amp", -0.1, 1.0),
+            ("amp", f32::NAN, 1.0),
+            ("sigma", 0.5, 0.0),
+            ("sigma", 0.5, f32::INFINITY),
+            ("sigma", 0.5, f32::NAN),
+        ];
+        for (i, (field, amp, sigma)) in cases.into_iter().enumerate() {
+            let mut bad = packet("memory safety", &format!("h{}", i), 1000);
+            bad.amp = amp;
+            bad.sigma = sigma;
+            match engine.ingest(bad) {
+                Err(EngineError::OutOfRange { field: got, .. }) => assert_eq!(got, field),
+                other => panic!("expected {} out of range, got {:?}", field, other),
+            }
+        }
+        assert!(engine.ledger().is_empty());
+
+        let mut edge = packet("memory safety", "edge", 1000);
+        edge.amp = 1.0;
+        edge.sigma = 0.01;
+        assert!(engine.ingest(edge).is_ok());
+    }
+
+    #[test]
+    fn test_feedback_counts_dissent() {
+        let mut engine = Engine::new();
+        for i in 0..3 {
+            engine
+                .ingest(packet("memory leak", &format!("h{}", i), 1000))
+                .unwrap();
+        }
+        let mut repel = packet("memory leak", "not", 1000);
+        repel.polarity = Polarity::Repel;
+        repel.amp = 0.2;
+        engine.ingest(repel).unwrap();
+
+        engine.tick(1000);
+        let feedback = engine.tick(1100);
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].contributors.len(), 3);
+        assert_eq!(feedback[0].dissent, 1);
+    }
+
+    #[test]
+    fn test_urgent_packet_alerts_on_ingest() {
+        let mut engine = Engine::new().with_clock(|| 1000);
+        let mut urgent = packet("prod is down", "u0", 1000);
+        urgent.tempo = Tempo::Urgent;
+        urgent.amp = 0.9;
+
+        let alert = engine.ingest(urgent.clone()).unwrap().unwrap();
+        assert_eq!(alert.tempo, Tempo::Urgent);
+        assert_eq!(alert.rep_phrase, "prod is down");
+        assert_eq!(alert.contributors, vec!["u0"]);
+
+        // A repeat reinforces the open alert instead of raising another
+        urgent.rationale_hash = "u1".to_string();
+        assert!(engine.ingest(urgent.clone()).unwrap().is_none());
+        assert_eq!(engine.urgent().alerts()[0].contributors.len(), 2);
+
+        // Quiet urgent packets skip the fast path and cluster normally
+        for hash in ["u2", "u3"] {
+            urgent.rationale_hash = hash.to_string();
+            urgent.amp = 0.5;
+            assert!(engine.ingest(urgent.clone()).unwrap().is_none());
+        }
+        engine.tick(1000);
+        let feedback = engine.tick(1100);
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].contributors.len(), 2);
+        assert!(!feedback[0].contributors.contains(&"u0".to_string()));
+
+        // Unreinforced alerts lapse
+        engine.tick(60_000);
+        assert!(engine.urgent().alerts().is_empty());
+    }
+
+    #[test]
+    fn test_tick_emits_ridge_between_basins() {
+        use crate::embed::fixture::FixtureEmbedder;
+
+        // Two basins 55° apart, two bridge phrases off-plane between them
+        let (b, m, phi) = (55f32.to_radians(), 27.5f32.to_radians(), 35f32.to_radians());
+        let fixture = FixtureEmbedder::new("hand", 3)
+            .with("rust", vec![1.0, 0.0, 0.0])
+            .with("go", vec![b.cos(), b.sin(), 0.0])
+            .with(
+                "rust with gc",
+                vec![phi.cos() * m.cos(), phi.cos() * m.sin(), phi.sin()],
+            )
+            .with(
+                "go with generics",
+                vec![phi.cos() * m.cos(), phi.cos() * m.sin(), -phi.sin()],
+            );
+        let mut engine = Engine::with_embedder(Ledger::new(), fixture);
+
+        for i in 0..3 {
+            engine
+                .ingest(packet("rust", &format!("r{}", i), 1000))
+                .unwrap();
+            engine
+                .ingest(packet("go", &format!("g{}", i), 1000))
+                .unwrap();
+        }
+        engine.ingest(packet("rust with gc", "x0", 1000)).unwrap();
+        engine
+            .ingest(packet("go with generics", "x1", 1000))
+            .unwrap();
+
+        assert!(engine.tick(1000).is_empty());
+        assert_eq!(engine.tick(1100).len(), 2, "both valleys");
+        let feedback = engine.tick(1200);
+        assert_eq!(feedback.len(), 1);
+
+        let ridge = &feedback[0];
+        assert_eq!(ridge.type_, BasinType::Ridge);
+        assert_eq!(ridge.recommended_action, Action::PairedExperiment);
+        assert_eq!(ridge.contributors, vec!["x0", "x1"]);
+        let mut endpoints = ridge.endpoints.clone().unwrap();
+        endpoints.sort();
+        assert_eq!(endpoints, vec!["g0", "r0"]);
+        assert_eq!(ridge.axis.as_ref().unwrap().len(), 3);
+        let card = ridge.precard.as_ref().unwrap();
+        assert!(card.summary.starts_with("Tradeoff: rust vs go."));
+
+        assert!(engine.tick(1300).is_empty(), "reported once");
+    }
+
+    #[test]
+    fn test_overloaded_cluster_is_a_peak_and_damps_newcomers() {
+        use crate::embed::fixture::FixtureEmbedder;
+
+        // Nine topics 20° apart chain into one wide, incoherent cluster
+        let mut fixture = FixtureEmbedder::new("hand", 2);
+        for i in 0..9 {
+            let rad = (20.0 * i as f32).to_radians();
+            fixture = fixture.with(&format!("topic {}", i), vec![rad.cos(), rad.sin()]);
+        }
+        let mut engine = Engine::with_embedder(Ledger::new(), fixture);
+        for i in 0..9 {
+            let phrase = format!("topic {}", i);
+            engine
+                .ingest(packet(&phrase, &format!("h{}", i), 1000))
+                .unwrap();
+        }
+
+        engine.tick(1000);
+        assert!(engine.refit_projection(1100));
+        let feedback = engine.tick(1100);
+        assert_eq!(feedback.len(), 1);
+        let peak = &feedback[0];
+        assert_eq!(peak.type_, BasinType::Peak);
+        assert_eq!(peak.recommended_action, Action::Decompose);
+        assert_eq!(peak.decompose_into.as_ref().unwrap().len(), 3);
+
+        // Placed on the display at its medoid
+        let medoid = engine.ledger().get(&peak.rep_id).unwrap();
+        assert_eq!(
+            Some(peak.coords_2d),
+            engine.projector().project(&medoid.vector)
+        );
+        assert_ne!(peak.coords_2d, [0.0, 0.0]);
+
+        // Inside the damping window new packets in the region lose amp
+        engine.ingest(packet("topic 4", "late", 1200)).unwrap();
+        assert!((engine.ledger().get("late").unwrap().amp - 0.4).abs() < 1e-6);
+        engine
+            .ingest(packet("topic 4", "later", 1000 + 10_001))
+            .unwrap();
+        assert!((engine.ledger().get("later").unwrap().amp - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_type_flip_is_reported_once_it_holds() {
+        use crate::embed::fixture::FixtureEmbedder;
+
+        // Topics 20° apart: the middle three form a valley, all nine a peak
+        let mut fixture = FixtureEmbedder::new("hand", 2);
+        for i in 0..9 {
+            let rad = (20.0 * i as f32).to_radians();
+            fixture = fixture.with(&format!("topic {}", i), vec![rad.cos(), rad.sin()]);
+        }
+        let mut engine = Engine::with_embedder(Ledger::new(), fixture);
+        let add = |engine: &mut Engine<FixtureEmbedder>, i: usize| {
+            let phrase = format!("topic {}", i);
+            engine
+                .ingest(packet(&phrase, &format!("h{}", i), 1000))
+                .unwrap();
+        };
+        for i in 3..6 {
+            add(&mut engine, i);
+        }
+        engine.tick(1000);
+        let feedback = engine.tick(1100);
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].type_, BasinType::Valley);
+
+        for i in (0..3).chain(6..9) {
+            add(&mut engine, i);
+        }
+        assert!(engine.tick(1200).is_empty(), "flip not held yet");
+        let feedback = engine.tick(1300);
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].type_, BasinType::Peak);
+        assert!(engine.tick(1400).is_empty(), "reported once");
+    }
+
+    #[test]
+    fn test_governor_relaxes_starved_thresholds() {
+        let mut engine = Engine::new().with_governor(Governor::new());
+        let phrases = ["memory safety", "tax policy", "jazz chords"];
+        for (i, phrase) in phrases.iter().enumerate() {
+            engine
+                .ingest(packet(phrase, &format!("h{}", i), 40_000))
+                .unwrap();
+        }
+
+        engine.tick(0); // governor starts its window
+        assert!(engine.tick(60_000).is_empty());
+
+        // Live points but no basins for a full window: everything relaxes
+        let config = engine.clusters().config();
+        assert_eq!(config.min_persistence, 1);
+        assert_eq!(config.min_persistence_fast, 1);
+        assert!((config.epsilon - 0.375).abs() < 1e-6);
+        let governor = engine.governor().unwrap();
+        assert_eq!(governor.total(), 3);
+        assert!(governor.history().all(|a| a.observed.live_points == 3));
+        let taken = engine.take_adjustments();
+        assert_eq!(taken.len(), 3);
+        assert!(engine.take_adjustments().is_empty(), "drained");
+
+        // The new epsilon re-scores the live window
+        engine.tick(61_000);
+        assert_eq!(engine.clusters().live_points(), 3);
+    }
+}
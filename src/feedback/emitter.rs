@@ -0,0 +1,349 @@
+// Feedback dispatch: fan-out to sinks with per-sink retry/drop policy
+//
+// Every BasinFeedback is offered to each enabled sink in order, on the
+// caller's thread. A failed send is retried `max_retries` times with
+// doubling backoff, sleeping inline; after that the packet is dropped for
+// that sink only, or the sink is disabled outright. Delivery is sequential:
+// `emit` blocks for up to the sum of every sink's I/O timeouts and retry
+// budget, and a slow sink delays the ones after it. Keep it off the ingest
+// path (the daemon queues feedback for a separate emitting thread).
+
+use super::sinks::{JsonlFileSink, TcpSink, TerminalSink, UdpSink, WebhookSink};
+use crate::types::BasinFeedback;
+use crate::wire::WireError;
+use std::fmt;
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+/// Why a sink could not deliver a packet
+#[derive(Debug)]
+pub enum SinkError {
+    Io(io::Error),
+    Encode(serde_json::Error),
+    Http { status: u16 }, // non-2xx from a webhook
+    BadSpec(String),      // unparseable sink specification
+}
+
+impl fmt::Display for SinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SinkError::Io(err) => write!(f, "{}", err),
+            SinkError::Encode(err) => write!(f, "cannot encode feedback: {}", err),
+            SinkError::Http { status } => write!(f, "webhook answered HTTP {}", status),
+            SinkError::BadSpec(spec) => write!(f, "bad sink spec: {}", spec),
+        }
+    }
+}
+
+impl std::error::Error for SinkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SinkError::Io(err) => Some(err),
+            SinkError::Encode(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SinkError {
+    fn from(err: io::Error) -> Self {
+        SinkError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SinkError {
+    fn from(err: serde_json::Error) -> Self {
+        SinkError::Encode(err)
+    }
+}
+
+impl From<WireError> for SinkError {
+    fn from(err: WireError) -> Self {
+        match err {
+            WireError::Io(err) => SinkError::Io(err),
+            WireError::Json(err) => SinkError::Encode(err),
+            other => SinkError::Io(io::Error::new(
+                io::ErrorKind::InvalidData,
+                other.to_string(),
+            )),
+        }
+    }
+}
+
+/// Destination for BasinFeedback packets
+pub trait FeedbackSink {
+    /// Label for stats and warnings, e.g. "tcp:10.0.0.5:7000"
+    fn name(&self) -> &str;
+
+    /// One delivery attempt (retries are the emitter's job)
+    fn send(&mut self, basin: &BasinFeedback) -> Result<(), SinkError>;
+}
+
+/// What happens once a packet has exhausted its retries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnFailure {
+    Drop,    // lose this packet, keep the sink
+    Disable, // stop sending to the sink altogether
+}
+
+/// Per-sink delivery policy
+#[derive(Debug, Clone)]
+pub struct SinkPolicy {
+    pub max_retries: u32,      // retries after the first attempt
+    pub backoff: Duration,     // first retry delay, doubled each time
+    pub on_failure: OnFailure, // after the last retry fails
+}
+
+impl Default for SinkPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            backoff: Duration::from_millis(50),
+            on_failure: OnFailure::Drop,
+        }
+    }
+}
+
+/// Delivery counters for one sink
+#[derive(Debug, Clone, Default)]
+pub struct SinkStats {
+    pub sent: u64,                  // packets delivered
+    pub retries: u64,               // extra attempts across all packets
+    pub dropped: u64,               // packets given up on
+    pub disabled: bool,             // OnFailure::Disable has fired
+    pub last_error: Option<String>, // most recent failure, if any
+}
+
+struct Slot {
+    sink: Box<dyn FeedbackSink + Send>,
+    policy: SinkPolicy,
+    stats: SinkStats,
+}
+
+/// Fan-out over a list of sinks
+#[derive(Default)]
+pub struct Emitter {
+    slots: Vec<Slot>,
+}
+
+impl Emitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder form of `add`
+    pub fn with_sink(mut self, sink: Box<dyn FeedbackSink + Send>, policy: SinkPolicy) -> Self {
+        self.add(sink, policy);
+        self
+    }
+
+    pub fn add(&mut self, sink: Box<dyn FeedbackSink + Send>, policy: SinkPolicy) {
+        self.slots.push(Slot {
+            sink,
+            policy,
+            stats: SinkStats::default(),
+        });
+    }
+
+    /// Offer one packet to every enabled sink; returns how many accepted it
+    /// Blocks until each sink has delivered or exhausted its retries
+    pub fn emit(&mut self, basin: &BasinFeedback) -> usize {
+        let mut delivered = 0;
+        for slot in self.slots.iter_mut().filter(|s| !s.stats.disabled) {
+            if slot.deliver(basin) {
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+
+    /// (sink name, counters) in the order sinks were added
+    pub fn stats(&self) -> Vec<(&str, &SinkStats)> {
+        self.slots
+            .iter()
+            .map(|s| (s.sink.name(), &s.stats))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+}
+
+impl Slot {
+    fn deliver(&mut self, basin: &BasinFeedback) -> bool {
+        let mut delay = self.policy.backoff;
+        let mut attempt = 0;
+        loop {
+            match self.sink.send(basin) {
+                Ok(()) => {
+                    self.stats.sent += 1;
+                    return true;
+                }
+                Err(e) if attempt < self.policy.max_retries => {
+                    self.stats.last_error = Some(e.to_string());
+                    self.stats.retries += 1;
+                    thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.stats.last_error = Some(e.to_string());
+                    self.stats.dropped += 1;
+                    if self.policy.on_failure == OnFailure::Disable {
+                        self.stats.disabled = true;
+                    }
+                    return false;
+                }
+            }
+        }
+    }
+}
+
+/// Build a sink from a spec string:
+///   stdout | jsonl:<path> | tcp:<host:port> | tcp+msgpack:<host:port>
+///   | udp:<addr:port> | webhook:<http url>
+pub fn parse_sink(spec: &str) -> Result<Box<dyn FeedbackSink + Send>, SinkError> {
+    let spec = spec.trim();
+    if spec == "stdout" {
+        return Ok(Box::new(TerminalSink::stdout()));
+    }
+    let Some((kind, target)) = spec.split_once(':') else {
+        return Err(SinkError::BadSpec(spec.to_string()));
+    };
+    if target.is_empty() {
+        return Err(SinkError::BadSpec(spec.to_string()));
+    }
+
+    Ok(match kind {
+        "jsonl" => Box::new(JsonlFileSink::open(target)?),
+        "tcp" => Box::new(TcpSink::new(target)),
+        "tcp+msgpack" => Box::new(TcpSink::msgpack(target)),
+        "udp" => Box::new(UdpSink::new(target)?),
+        "webhook" => Box::new(WebhookSink::new(target)?),
+        _ => return Err(SinkError::BadSpec(spec.to_string())),
+    })
+}
+
+/// Comma-separated `parse_sink` specs, all with the same policy
+pub fn parse_sinks(specs: &str, policy: &SinkPolicy) -> Result<Emitter, SinkError> {
+    let mut emitter = Emitter::new();
+    for spec in specs.split(',').filter(|s| !s.trim().is_empty()) {
+        emitter.add(parse_sink(spec)?, policy.clone());
+    }
+    Ok(emitter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::loopback::sample_feedback;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    /// Fails its first `fail` sends, then succeeds
+    struct FlakySink {
+        fail: u32,
+        attempts: Arc<AtomicU32>,
+    }
+
+    impl FeedbackSink for FlakySink {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn send(&mut self, _basin: &BasinFeedback) -> Result<(), SinkError> {
+            let n = self.attempts.fetch_add(1, Ordering::SeqCst);
+            if n < self.fail {
+                return Err(io::Error::new(io::ErrorKind::ConnectionRefused, "down").into());
+            }
+            Ok(())
+        }
+    }
+
+    fn flaky(fail: u32) -> (Box<dyn FeedbackSink + Send>, Arc<AtomicU32>) {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let sink = FlakySink {
+            fail,
+            attempts: Arc::clone(&attempts),
+        };
+        (Box::new(sink), attempts)
+    }
+
+    fn policy(max_retries: u32, on_failure: OnFailure) -> SinkPolicy {
+        SinkPolicy {
+            max_retries,
+            backoff: Duration::from_millis(1),
+            on_failure,
+        }
+    }
+
+    #[test]
+    fn test_retry_then_deliver() {
+        let (sink, attempts) = flaky(2);
+        let mut emitter = Emitter::new().with_sink(sink, policy(2, OnFailure::Drop));
+
+        assert_eq!(emitter.emit(&sample_feedback("valley_1")), 1);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        let (_, stats) = emitter.stats()[0];
+        assert_eq!((stats.sent, stats.retries, stats.dropped), (1, 2, 0));
+    }
+
+    #[test]
+    fn test_drop_keeps_sink_and_disable_stops_it() {
+        let (dropping, drop_attempts) = flaky(u32::MAX);
+        let (disabling, disable_attempts) = flaky(u32::MAX);
+        let (healthy, _) = flaky(0);
+        let mut emitter = Emitter::new()
+            .with_sink(dropping, policy(1, OnFailure::Drop))
+            .with_sink(disabling, policy(1, OnFailure::Disable))
+            .with_sink(healthy, policy(1, OnFailure::Drop));
+
+        assert_eq!(emitter.emit(&sample_feedback("valley_1")), 1);
+        assert_eq!(emitter.emit(&sample_feedback("valley_2")), 1);
+
+        // Dropping sink tried twice per packet; disabled one only for the first
+        assert_eq!(drop_attempts.load(Ordering::SeqCst), 4);
+        assert_eq!(disable_attempts.load(Ordering::SeqCst), 2);
+
+        let stats = emitter.stats();
+        assert_eq!(stats[0].1.dropped, 2);
+        assert!(!stats[0].1.disabled);
+        assert_eq!(stats[1].1.dropped, 1);
+        assert!(stats[1].1.disabled);
+        assert_eq!(stats[2].1.sent, 2);
+        assert!(stats[0].1.last_error.as_deref().unwrap().contains("down"));
+    }
+
+    #[test]
+    fn test_parse_sinks() {
+        let emitter = parse_sinks(
+            "stdout, tcp:127.0.0.1:9, tcp+msgpack:127.0.0.1:9, udp:239.1.2.3:7400",
+            &SinkPolicy::default(),
+        )
+        .unwrap();
+        let names: Vec<&str> = emitter.stats().iter().map(|(name, _)| *name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "stdout",
+                "tcp:127.0.0.1:9",
+                "tcp+msgpack:127.0.0.1:9",
+                "udp:239.1.2.3:7400"
+            ]
+        );
+
+        assert!(matches!(
+            parse_sink("carrier-pigeon:home"),
+            Err(SinkError::BadSpec(_))
+        ));
+        assert!(matches!(parse_sink("tcp:"), Err(SinkError::BadSpec(_))));
+        assert!(parse_sink("webhook:https://example.com/hook").is_err());
+    }
+}
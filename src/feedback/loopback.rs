@@ -0,0 +1,220 @@
+// Loopback receivers for the network sinks (offline tests)
+//
+// Listens on 127.0.0.1 for all three wire formats at once: JSON Lines over
+// TCP, JSON datagrams over UDP and webhook POSTs, and records every
+// BasinFeedback it decodes together with the transport it arrived on.
+
+use crate::http;
+use crate::types::{Action, BasinFeedback, BasinType, Tempo};
+use std::io::{self, BufRead, BufReader};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Which receiver a packet arrived on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Tcp,
+    Udp,
+    Webhook,
+}
+
+/// TCP, UDP and webhook receivers on ephemeral 127.0.0.1 ports
+pub struct Loopback {
+    tcp: SocketAddr,
+    udp: SocketAddr,
+    http: SocketAddr,
+    state: Arc<State>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+struct State {
+    shutdown: AtomicBool,
+    fail_next: AtomicU32, // answer 503 to this many webhook POSTs
+    received: Mutex<Vec<(Transport, BasinFeedback)>>,
+    tcp_clients: Mutex<Vec<TcpStream>>, // open sink connections
+}
+
+impl Loopback {
+    pub fn start() -> io::Result<Self> {
+        let tcp_listener = TcpListener::bind("127.0.0.1:0")?;
+        let http_listener = TcpListener::bind("127.0.0.1:0")?;
+        let udp_socket = UdpSocket::bind("127.0.0.1:0")?;
+        udp_socket.set_read_timeout(Some(Duration::from_millis(50)))?;
+
+        let state = Arc::new(State {
+            shutdown: AtomicBool::new(false),
+            fail_next: AtomicU32::new(0),
+            received: Mutex::new(Vec::new()),
+            tcp_clients: Mutex::new(Vec::new()),
+        });
+
+        let mut loopback = Self {
+            tcp: tcp_listener.local_addr()?,
+            udp: udp_socket.local_addr()?,
+            http: http_listener.local_addr()?,
+            state,
+            handles: Vec::new(),
+        };
+
+        let state = Arc::clone(&loopback.state);
+        loopback.handles.push(thread::spawn(move || {
+            for stream in tcp_listener.incoming() {
+                if state.shutdown.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else { continue };
+                if let Ok(clone) = stream.try_clone() {
+                    state.tcp_clients.lock().unwrap().push(clone);
+                }
+                let state = Arc::clone(&state);
+                thread::spawn(move || read_lines(stream, &state));
+            }
+        }));
+
+        let state = Arc::clone(&loopback.state);
+        loopback.handles.push(thread::spawn(move || {
+            let mut buf = vec![0u8; 65536];
+            while !state.shutdown.load(Ordering::SeqCst) {
+                if let Ok(n) = udp_socket.recv(&mut buf) {
+                    state.record(Transport::Udp, &buf[..n]);
+                }
+            }
+        }));
+
+        let state = Arc::clone(&loopback.state);
+        loopback.handles.push(thread::spawn(move || {
+            for stream in http_listener.incoming() {
+                if state.shutdown.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let _ = serve_webhook(stream, &state);
+                }
+            }
+        }));
+
+        Ok(loopback)
+    }
+
+    /// `host:port` for TcpSink
+    pub fn tcp_addr(&self) -> String {
+        self.tcp.to_string()
+    }
+
+    /// `host:port` for UdpSink
+    pub fn udp_addr(&self) -> String {
+        self.udp.to_string()
+    }
+
+    /// Full URL for WebhookSink, e.g. http://127.0.0.1:40123/feedback
+    pub fn webhook_url(&self) -> String {
+        format!("http://{}/feedback", self.http)
+    }
+
+    /// Make the next `n` webhook POSTs fail with 503
+    pub fn fail_next_webhooks(&self, n: u32) {
+        self.state.fail_next.store(n, Ordering::SeqCst);
+    }
+
+    /// Close every open TCP connection from the server side
+    pub fn drop_tcp_clients(&self) {
+        for stream in self.state.tcp_clients.lock().unwrap().drain(..) {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Everything received so far, in arrival order
+    pub fn received(&self) -> Vec<(Transport, BasinFeedback)> {
+        self.state.received.lock().unwrap().clone()
+    }
+
+    /// Wait until at least `n` packets arrived (or `timeout` passed)
+    pub fn wait_for(&self, n: usize, timeout: Duration) -> Vec<(Transport, BasinFeedback)> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let received = self.received();
+            if received.len() >= n || Instant::now() >= deadline {
+                return received;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+impl Drop for Loopback {
+    fn drop(&mut self) {
+        self.state.shutdown.store(true, Ordering::SeqCst);
+        self.drop_tcp_clients();
+        let _ = TcpStream::connect(self.tcp); // wake the accept loops
+        let _ = TcpStream::connect(self.http);
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl State {
+    fn record(&self, transport: Transport, bytes: &[u8]) {
+        if let Ok(basin) = serde_json::from_slice(bytes) {
+            self.received.lock().unwrap().push((transport, basin));
+        }
+    }
+}
+
+fn read_lines(stream: TcpStream, state: &State) {
+    for line in BufReader::new(stream).lines() {
+        match line {
+            Ok(line) => state.record(Transport::Tcp, line.as_bytes()),
+            Err(_) => break,
+        }
+    }
+}
+
+fn serve_webhook(stream: TcpStream, state: &State) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let Some(request) = http::read_request(&mut BufReader::new(stream))? else {
+        return Ok(());
+    };
+    if request.method != "POST" {
+        return http::write_response(&mut writer, 405, "text/plain", b"POST only");
+    }
+
+    let failing = state
+        .fail_next
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        .is_ok();
+    if failing {
+        return http::write_response(&mut writer, 503, "text/plain", b"busy");
+    }
+
+    state.record(Transport::Webhook, &request.body);
+    http::write_response(&mut writer, 204, "text/plain", b"")
+}
+
+/// Minimal valley packet for exercising sinks
+pub fn sample_feedback(basin_id: &str) -> BasinFeedback {
+    BasinFeedback {
+        basin_id: basin_id.to_string(),
+        type_: BasinType::Valley,
+        coords_2d: [0.0, 0.0],
+        rep_id: "rh_memory".to_string(),
+        rep_phrase: "memory safety".to_string(),
+        contributors: vec!["rh_memory".to_string(), "rh_borrowck".to_string()],
+        nd_cohesion: 0.8,
+        nd_radius: 0.1,
+        persistence: 3,
+        tempo: Tempo::Slow,
+        dissent: 0,
+        centroid: None,
+        endpoints: None,
+        axis: None,
+        decompose_into: None,
+        recommended_action: Action::PlanSpike,
+        precard: None,
+        thresholds: None,
+        timestamp: 1000,
+    }
+}
@@ -1,3 +1,137 @@
 // Basin feedback emission (with PreCard templates)
+//
+// Packages validated clusters (valleys and peaks), ridges and urgent
+// alerts into BasinFeedback packets, each carrying a Template-tier PreCard
+// and the thresholds in force when it matured. `emitter` fans the packets
+// out to terminal, file and network sinks.
 
-// TODO M1.5: Implement PreCard template generation
+pub mod emitter;
+#[cfg(test)]
+mod loopback;
+pub mod precard;
+pub mod sinks;
+
+use crate::clustering::ridge::Ridge;
+use crate::clustering::urgent::UrgentAlert;
+use crate::clustering::Cluster;
+use crate::ledger::store::Ledger;
+use crate::types::{Action, BasinFeedback, BasinType, Tempo, ThresholdSnapshot};
+use crate::validator::ValidatedBasin;
+
+/// Valley (or Peak) feedback for a validated cluster
+pub fn basin_feedback(
+    cluster: &Cluster,
+    basin: ValidatedBasin,
+    ledger: &Ledger,
+    thresholds: &ThresholdSnapshot,
+    now: u64,
+) -> BasinFeedback {
+    let members = ledger.get_batch(&basin.members);
+    let top = precard::top_phrases(&members, &basin.frechet_mean, precard::TOP_K);
+
+    let (decompose_into, recommended_action, card) = match &cluster.peak {
+        Some(peak) => {
+            let parts = phrases(ledger, &peak.decompose_into);
+            let card = precard::overload(&basin.medoid_phrase, &parts, top);
+            (Some(peak.decompose_into.clone()), Action::Decompose, card)
+        }
+        None => {
+            let card = precard::consensus(&basin.medoid_phrase, top);
+            (None, Action::PlanSpike, card)
+        }
+    };
+
+    BasinFeedback {
+        basin_id: cluster.id.clone(),
+        type_: cluster.basin_type(),
+        coords_2d: [0.0, 0.0], // set by the engine's projector
+        rep_id: basin.medoid,
+        rep_phrase: basin.medoid_phrase,
+        contributors: basin.members,
+        nd_cohesion: basin.cohesion,
+        nd_radius: basin.radius,
+        persistence: cluster.persistence,
+        tempo: cluster.tempo,
+        dissent: cluster.dissenters as u32,
+        centroid: Some(cluster.centroid.clone()),
+        endpoints: None,
+        axis: None,
+        decompose_into,
+        recommended_action,
+        precard: Some(card),
+        thresholds: Some(thresholds.clone()),
+        timestamp: now,
+    }
+}
+
+/// Ridge feedback: endpoints, tradeoff axis and a paired-experiment card
+pub fn ridge_feedback(
+    ridge: &Ridge,
+    ledger: &Ledger,
+    thresholds: &ThresholdSnapshot,
+    now: u64,
+) -> BasinFeedback {
+    let boundary = ledger.get_batch(&ridge.boundary);
+    let center = ledger
+        .get(&ridge.rep_hash)
+        .map(|e| e.vector.clone())
+        .unwrap_or_default();
+    let top = precard::top_phrases(&boundary, &center, precard::TOP_K);
+    let sides = phrases(ledger, &ridge.endpoints);
+
+    BasinFeedback {
+        basin_id: ridge.id.clone(),
+        type_: BasinType::Ridge,
+        coords_2d: [0.0, 0.0],
+        rep_id: ridge.rep_hash.clone(),
+        rep_phrase: ridge.rep_phrase.clone(),
+        contributors: ridge.boundary.clone(),
+        nd_cohesion: ridge.cohesion,
+        nd_radius: ridge.radius,
+        persistence: ridge.persistence,
+        tempo: ridge.tempo,
+        dissent: 0,
+        centroid: None,
+        endpoints: Some(ridge.endpoints.to_vec()),
+        axis: Some(ridge.axis.clone()),
+        decompose_into: None,
+        recommended_action: Action::PairedExperiment,
+        precard: Some(precard::tradeoff(&sides[0], &sides[1], top)),
+        thresholds: Some(thresholds.clone()),
+        timestamp: now,
+    }
+}
+
+/// Single-member feedback for a freshly raised urgent alert
+/// No thresholds: the urgent path bypasses them
+pub fn alert_feedback(alert: &UrgentAlert) -> BasinFeedback {
+    BasinFeedback {
+        basin_id: alert.id.clone(),
+        type_: BasinType::Valley,
+        coords_2d: [0.0, 0.0],
+        rep_id: alert.rep_hash.clone(),
+        rep_phrase: alert.rep_phrase.clone(),
+        contributors: alert.contributors.clone(),
+        nd_cohesion: 1.0,
+        nd_radius: 0.0,
+        persistence: 0, // bypassed
+        tempo: Tempo::Urgent,
+        dissent: 0,
+        centroid: Some(alert.vector.clone()),
+        endpoints: None,
+        axis: None,
+        decompose_into: None,
+        recommended_action: Action::PlanSpike,
+        precard: Some(precard::alert(&alert.rep_phrase)),
+        thresholds: None,
+        timestamp: alert.raised_at,
+    }
+}
+
+/// Phrases for rationale_hashes (empty for hashes not in the ledger)
+fn phrases(ledger: &Ledger, hashes: &[String]) -> Vec<String> {
+    hashes
+        .iter()
+        .map(|h| ledger.get(h).map(|e| e.phrase.clone()).unwrap_or_default())
+        .collect()
+}
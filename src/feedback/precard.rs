@@ -0,0 +1,150 @@
+// PreCard template generation (Template tier: instant, no LLM)
+//
+// Summaries follow SPEC §7, e.g.
+//   "Consensus: {medoid_phrase}. Contributors: {top_k}. Action: {suggested}"
+
+use crate::ledger::hnsw::cosine_distance;
+use crate::types::{LedgerEntry, PreCard, SynthTier};
+
+/// Contributor phrases shown on a card
+pub const TOP_K: usize = 5;
+
+/// Up to `k` distinct phrases, ranked by amp × centrality
+/// Centrality is the similarity to `center` mapped into [0, 1]
+pub fn top_phrases(entries: &[&LedgerEntry], center: &[f32], k: usize) -> Vec<String> {
+    let mut scored: Vec<(f32, &str)> = entries
+        .iter()
+        .map(|e| {
+            let centrality = 1.0 - cosine_distance(&e.vector, center) / 2.0;
+            (e.amp * centrality, e.phrase.as_str())
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(b.1)));
+
+    let mut phrases: Vec<String> = Vec::new();
+    for (_, phrase) in scored {
+        if phrases.len() == k {
+            break;
+        }
+        if !phrases.iter().any(|p| p == phrase) {
+            phrases.push(phrase.to_string());
+        }
+    }
+    phrases
+}
+
+/// Valley: agreement worth acting on
+pub fn consensus(rep_phrase: &str, top_phrases: Vec<String>) -> PreCard {
+    let action = format!("Plan spike on \"{}\"", rep_phrase);
+    card(format!("Consensus: {}", rep_phrase), top_phrases, action)
+}
+
+/// Ridge: two basins pulling against each other
+pub fn tradeoff(side_a: &str, side_b: &str, top_phrases: Vec<String>) -> PreCard {
+    let action = format!("Run paired experiment: \"{}\" vs \"{}\"", side_a, side_b);
+    card(
+        format!("Tradeoff: {} vs {}", side_a, side_b),
+        top_phrases,
+        action,
+    )
+}
+
+/// Peak: too many loosely related concepts in one place
+pub fn overload(rep_phrase: &str, parts: &[String], top_phrases: Vec<String>) -> PreCard {
+    let action = format!("Decompose into: {}", parts.join("; "));
+    card(
+        format!("Overload: {} ({} sub-basins)", rep_phrase, parts.len()),
+        top_phrases,
+        action,
+    )
+}
+
+/// Urgent alert: bypassed persistence
+pub fn alert(rep_phrase: &str) -> PreCard {
+    let action = format!("Respond now to \"{}\"", rep_phrase);
+    card(
+        format!("Alert: {}", rep_phrase),
+        vec![rep_phrase.to_string()],
+        action,
+    )
+}
+
+fn card(headline: String, top_phrases: Vec<String>, action: String) -> PreCard {
+    PreCard {
+        tier: SynthTier::Template,
+        summary: format!(
+            "{}. Contributors: {}. Action: {}",
+            headline,
+            top_phrases.join(", "),
+            action
+        ),
+        top_phrases,
+        suggested_action: action,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Polarity, Tempo};
+
+    fn entry(phrase: &str, degrees: f32, amp: f32) -> LedgerEntry {
+        let rad = degrees.to_radians();
+        LedgerEntry {
+            vector: vec![rad.cos(), rad.sin()],
+            rationale_hash: format!("{}@{}", phrase, degrees),
+            agent_id: "agent1".to_string(),
+            provenance: "test".to_string(),
+            phrase: phrase.to_string(),
+            timestamp: 1000,
+            tempo: Tempo::Slow,
+            polarity: Polarity::Attract,
+            amp,
+            sigma: 1.0,
+            coords_2d: None,
+        }
+    }
+
+    #[test]
+    fn test_top_phrases_rank_by_amp_and_centrality() {
+        let entries = [
+            entry("edge", 60.0, 1.0),
+            entry("loud center", 0.0, 0.9),
+            entry("quiet center", 0.0, 0.2),
+            entry("loud center", 5.0, 0.9), // same phrase from another agent
+            entry("near", 10.0, 0.8),
+        ];
+        let refs: Vec<&LedgerEntry> = entries.iter().collect();
+
+        let top = top_phrases(&refs, &[1.0, 0.0], 3);
+        assert_eq!(top, vec!["loud center", "near", "edge"]);
+    }
+
+    #[test]
+    fn test_templates() {
+        let card = consensus(
+            "memory safety",
+            vec!["memory safety".into(), "borrowck".into()],
+        );
+        assert_eq!(card.tier, SynthTier::Template);
+        assert_eq!(
+            card.summary,
+            "Consensus: memory safety. Contributors: memory safety, borrowck. \
+             Action: Plan spike on \"memory safety\""
+        );
+
+        let card = tradeoff("latency", "throughput", vec![]);
+        assert!(card.summary.starts_with("Tradeoff: latency vs throughput."));
+        assert_eq!(
+            card.suggested_action,
+            "Run paired experiment: \"latency\" vs \"throughput\""
+        );
+
+        let parts = vec!["gc".to_string(), "io".to_string()];
+        let card = overload("everything", &parts, vec![]);
+        assert!(card
+            .summary
+            .starts_with("Overload: everything (2 sub-basins)."));
+        assert_eq!(card.suggested_action, "Decompose into: gc; io");
+    }
+}
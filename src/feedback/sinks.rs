@@ -0,0 +1,496 @@
+// Built-in feedback sinks
+//
+// Network sinks speak JSON: one object per line over TCP and in JSONL
+// files, one object per datagram over UDP, one object per POST body for
+// webhooks. `TcpSink::msgpack` sends length-prefixed wire frames instead
+// (see wire/mod.rs). The terminal sink is the human-readable view
+// `sefi serve` prints by default.
+
+use super::emitter::{FeedbackSink, SinkError};
+use crate::http::{self, Url};
+use crate::types::{BasinFeedback, Tempo};
+use crate::wire;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Stdout, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Rotate JSONL files once they exceed this size
+pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Rotated JSONL files kept (path.1 .. path.N)
+pub const DEFAULT_KEEP: usize = 5;
+
+/// Connect/write timeout for TCP and webhook sinks
+pub const NETWORK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Human-readable basin reports
+pub struct TerminalSink<W: Write> {
+    name: String,
+    out: W,
+}
+
+impl TerminalSink<Stdout> {
+    pub fn stdout() -> Self {
+        Self::new("stdout", io::stdout())
+    }
+}
+
+impl<W: Write> TerminalSink<W> {
+    pub fn new(name: &str, out: W) -> Self {
+        Self {
+            name: name.to_string(),
+            out,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    fn write_basin(&mut self, basin: &BasinFeedback) -> io::Result<()> {
+        let out = &mut self.out;
+        if basin.tempo == Tempo::Urgent {
+            writeln!(out, "URGENT: \"{}\" ({})", basin.rep_phrase, basin.basin_id)?;
+            writeln!(out)?;
+            return out.flush();
+        }
+        writeln!(
+            out,
+            "Basin detected: {:?} ({})",
+            basin.type_, basin.basin_id
+        )?;
+        writeln!(out, "  Medoid: \"{}\"", basin.rep_phrase)?;
+        if let Some(endpoints) = &basin.endpoints {
+            writeln!(out, "  Endpoints: {}", endpoints.join(" <-> "))?;
+        }
+        if let Some(parts) = &basin.decompose_into {
+            writeln!(out, "  Decompose into: {} sub-basins", parts.len())?;
+        }
+        writeln!(out, "  Contributors: {} concepts", basin.contributors.len())?;
+        if basin.dissent > 0 {
+            writeln!(out, "  Dissent: {} agents", basin.dissent)?;
+        }
+        writeln!(out, "  Cohesion: {:.2} (silhouette)", basin.nd_cohesion)?;
+        if let Some(card) = &basin.precard {
+            writeln!(out, "  {}", card.summary)?;
+        }
+        writeln!(
+            out,
+            "  Persistence: {} ticks ({:?})",
+            basin.persistence, basin.tempo
+        )?;
+        writeln!(out)?;
+        out.flush()
+    }
+}
+
+impl<W: Write> FeedbackSink for TerminalSink<W> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send(&mut self, basin: &BasinFeedback) -> Result<(), SinkError> {
+        Ok(self.write_basin(basin)?)
+    }
+}
+
+/// Appends one JSON object per line, rotating by size
+/// `path` → `path.1` → ... → `path.{keep}`, oldest discarded
+pub struct JsonlFileSink {
+    name: String,
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: File,
+    written: u64, // bytes in the current file
+}
+
+impl JsonlFileSink {
+    /// Open (or create) with the default rotation settings
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::with_rotation(path, DEFAULT_MAX_BYTES, DEFAULT_KEEP)
+    }
+
+    pub fn with_rotation<P: AsRef<Path>>(path: P, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = append(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            name: format!("jsonl:{}", path.display()),
+            path,
+            max_bytes,
+            keep,
+            file,
+            written,
+        })
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.keep == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            for n in (1..self.keep).rev() {
+                let from = self.rotated(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated(1))?;
+            self.file = append(&self.path)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl FeedbackSink for JsonlFileSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send(&mut self, basin: &BasinFeedback) -> Result<(), SinkError> {
+        let mut line = serde_json::to_vec(basin)?;
+        line.push(b'\n');
+
+        if self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(&line)?;
+        self.file.flush()?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+}
+
+fn append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// JSON Lines over a TCP stream, reconnecting on the next send after a failure
+pub struct TcpSink {
+    name: String,
+    addr: String,
+    timeout: Duration,
+    framed: bool, // wire frames instead of JSON lines
+    stream: Option<TcpStream>,
+}
+
+impl TcpSink {
+    /// Connects lazily on the first send
+    pub fn new(addr: &str) -> Self {
+        Self {
+            name: format!("tcp:{}", addr),
+            addr: addr.to_string(),
+            timeout: NETWORK_TIMEOUT,
+            framed: false,
+            stream: None,
+        }
+    }
+
+    /// Length-prefixed MessagePack frames instead of JSON lines
+    pub fn msgpack(addr: &str) -> Self {
+        Self {
+            name: format!("tcp+msgpack:{}", addr),
+            framed: true,
+            ..Self::new(addr)
+        }
+    }
+
+    fn connect(&self) -> io::Result<TcpStream> {
+        let mut last = io::Error::new(io::ErrorKind::NotFound, "no address");
+        for addr in self.addr.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, self.timeout) {
+                Ok(stream) => {
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    stream.set_nodelay(true)?;
+                    return Ok(stream);
+                }
+                Err(e) => last = e,
+            }
+        }
+        Err(last)
+    }
+}
+
+impl FeedbackSink for TcpSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send(&mut self, basin: &BasinFeedback) -> Result<(), SinkError> {
+        let line = if self.framed {
+            let mut frame = Vec::new();
+            wire::write_frame(&mut frame, basin)?;
+            frame
+        } else {
+            let mut line = serde_json::to_vec(basin)?;
+            line.push(b'\n');
+            line
+        };
+
+        if self.stream.is_none() {
+            self.stream = Some(self.connect()?);
+        }
+        let stream = self.stream.as_mut().unwrap();
+        if let Err(e) = stream.write_all(&line).and_then(|_| stream.flush()) {
+            self.stream = None; // reconnect on the next attempt
+            return Err(e.into());
+        }
+        Ok(())
+    }
+}
+
+/// One JSON datagram per packet; multicast groups get TTL 1 (LAN only)
+pub struct UdpSink {
+    name: String,
+    socket: UdpSocket,
+    target: SocketAddr,
+}
+
+impl UdpSink {
+    pub fn new(target: &str) -> io::Result<Self> {
+        let addr = target
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, target.to_string()))?;
+
+        let socket = match addr {
+            SocketAddr::V4(_) => UdpSocket::bind("0.0.0.0:0")?,
+            SocketAddr::V6(_) => UdpSocket::bind("[::]:0")?,
+        };
+        if let SocketAddr::V4(v4) = addr {
+            if v4.ip().is_multicast() {
+                socket.set_multicast_ttl_v4(1)?;
+                socket.set_multicast_loop_v4(true)?;
+            }
+        }
+
+        Ok(Self {
+            name: format!("udp:{}", target),
+            socket,
+            target: addr,
+        })
+    }
+}
+
+impl FeedbackSink for UdpSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send(&mut self, basin: &BasinFeedback) -> Result<(), SinkError> {
+        let datagram = serde_json::to_vec(basin)?;
+        self.socket.send_to(&datagram, self.target)?;
+        Ok(())
+    }
+}
+
+/// HTTP POST of each packet; any non-2xx status counts as a failure
+pub struct WebhookSink {
+    name: String,
+    url: Url,
+    timeout: Duration,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> io::Result<Self> {
+        Ok(Self {
+            name: format!("webhook:{}", url),
+            url: Url::parse(url)?,
+            timeout: NETWORK_TIMEOUT,
+        })
+    }
+}
+
+impl FeedbackSink for WebhookSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send(&mut self, basin: &BasinFeedback) -> Result<(), SinkError> {
+        let body = serde_json::to_vec(basin)?;
+        let response = http::post_json(&self.url, &body, self.timeout)?;
+        if !(200..300).contains(&response.status) {
+            return Err(SinkError::Http {
+                status: response.status,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::emitter::{Emitter, OnFailure, SinkPolicy};
+    use crate::feedback::loopback::{sample_feedback, Loopback, Transport};
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sefi_{}_{}", tag, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_terminal_sink_formats_basins() {
+        let mut sink = TerminalSink::new("buffer", Vec::new());
+        sink.send(&sample_feedback("valley_1")).unwrap();
+        let mut urgent = sample_feedback("urgent_1");
+        urgent.tempo = Tempo::Urgent;
+        sink.send(&urgent).unwrap();
+
+        let text = String::from_utf8(sink.into_inner()).unwrap();
+        assert!(text.starts_with("Basin detected: Valley (valley_1)\n"));
+        assert!(text.contains("  Cohesion: 0.80 (silhouette)\n"));
+        assert!(text.contains("URGENT: \"memory safety\" (urgent_1)\n"));
+    }
+
+    #[test]
+    fn test_jsonl_rotation() {
+        let dir = temp_dir("jsonl");
+        let path = dir.join("feedback.jsonl");
+        let line_len = serde_json::to_vec(&sample_feedback("valley_0"))
+            .unwrap()
+            .len() as u64
+            + 1;
+
+        // Two lines per file, two rotated files kept
+        let mut sink = JsonlFileSink::with_rotation(&path, line_len * 2, 2).unwrap();
+        for i in 0..7 {
+            sink.send(&sample_feedback(&format!("valley_{}", i)))
+                .unwrap();
+        }
+
+        let ids = |p: &Path| -> Vec<String> {
+            fs::read_to_string(p)
+                .unwrap()
+                .lines()
+                .map(|l| serde_json::from_str::<BasinFeedback>(l).unwrap().basin_id)
+                .collect()
+        };
+        assert_eq!(ids(&path), vec!["valley_6"]);
+        assert_eq!(
+            ids(&dir.join("feedback.jsonl.1")),
+            vec!["valley_4", "valley_5"]
+        );
+        assert_eq!(
+            ids(&dir.join("feedback.jsonl.2")),
+            vec!["valley_2", "valley_3"]
+        );
+        assert!(!dir.join("feedback.jsonl.3").exists());
+
+        // Reopening resumes the size count instead of starting over
+        let sink = JsonlFileSink::with_rotation(&path, line_len * 2, 2).unwrap();
+        assert_eq!(sink.written, line_len);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_network_sinks_fan_out_over_loopback() {
+        let loopback = Loopback::start().unwrap();
+        let policy = SinkPolicy {
+            max_retries: 2,
+            backoff: Duration::from_millis(5),
+            on_failure: OnFailure::Drop,
+        };
+        let mut emitter = Emitter::new()
+            .with_sink(Box::new(TcpSink::new(&loopback.tcp_addr())), policy.clone())
+            .with_sink(
+                Box::new(UdpSink::new(&loopback.udp_addr()).unwrap()),
+                policy.clone(),
+            )
+            .with_sink(
+                Box::new(WebhookSink::new(&loopback.webhook_url()).unwrap()),
+                policy,
+            );
+
+        loopback.fail_next_webhooks(1); // first POST gets a 503 and is retried
+        assert_eq!(emitter.emit(&sample_feedback("valley_1")), 3);
+        assert_eq!(emitter.emit(&sample_feedback("ridge_1")), 3);
+
+        let received = loopback.wait_for(6, Duration::from_secs(5));
+        for transport in [Transport::Tcp, Transport::Udp, Transport::Webhook] {
+            let ids: Vec<&str> = received
+                .iter()
+                .filter(|(t, _)| *t == transport)
+                .map(|(_, b)| b.basin_id.as_str())
+                .collect();
+            assert_eq!(ids, vec!["valley_1", "ridge_1"], "{:?}", transport);
+        }
+
+        let stats = emitter.stats();
+        assert_eq!(stats[2].1.retries, 1);
+        assert_eq!(
+            stats[2].1.last_error.as_deref(),
+            Some("webhook answered HTTP 503")
+        );
+    }
+
+    #[test]
+    fn test_tcp_sink_reconnects() {
+        let loopback = Loopback::start().unwrap();
+        let mut sink = TcpSink::new(&loopback.tcp_addr());
+        sink.send(&sample_feedback("valley_1")).unwrap();
+
+        loopback.wait_for(1, Duration::from_secs(5));
+        loopback.drop_tcp_clients();
+
+        // The first write after the peer closed may still succeed; the
+        // stream is replaced as soon as one fails
+        let mut delivered = false;
+        for _ in 0..20 {
+            if sink.send(&sample_feedback("valley_2")).is_ok()
+                && loopback
+                    .received()
+                    .iter()
+                    .any(|(_, b)| b.basin_id == "valley_2")
+            {
+                delivered = true;
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(delivered);
+    }
+
+    #[test]
+    fn test_msgpack_tcp_sink_sends_frames() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut sink = TcpSink::msgpack(&listener.local_addr().unwrap().to_string());
+        sink.send(&sample_feedback("valley_1")).unwrap();
+        sink.send(&sample_feedback("valley_2")).unwrap();
+
+        let (mut peer, _) = listener.accept().unwrap();
+        let first: BasinFeedback = wire::read_frame(&mut peer).unwrap().unwrap();
+        let second: BasinFeedback = wire::read_frame(&mut peer).unwrap().unwrap();
+        assert_eq!(first.basin_id, "valley_1");
+        assert_eq!(second.basin_id, "valley_2");
+    }
+
+    #[test]
+    fn test_unreachable_sinks_fail() {
+        // Bind then drop a listener to get a port nobody listens on
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut tcp = TcpSink::new(&format!("127.0.0.1:{}", port));
+        assert!(matches!(
+            tcp.send(&sample_feedback("v")),
+            Err(SinkError::Io(_))
+        ));
+
+        let mut hook = WebhookSink::new(&format!("http://127.0.0.1:{}/hook", port)).unwrap();
+        assert!(hook.send(&sample_feedback("v")).is_err());
+    }
+}
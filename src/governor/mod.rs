@@ -1,3 +1,427 @@
 // Adaptive threshold controller (tempo-aware)
+//
+// Proportional control toward SPEC §7's operating point: 10–30 basins/min
+// with mean nd_cohesion ≥ 0.5. Each tempo's persistence_min follows that
+// tempo's own basin rate; the shape thresholds (min_points, epsilon) tighten
+// when cohesion drops and relax when too few basins form at all. Every
+// change is clamped to its bounds and logged with the observation behind it.
+// Only persistence_min is per tempo: min_points and epsilon shape the one
+// DBSCAN pass over all tempos, so both tempos share them, and min_mass is
+// left as configured.
 
-// TODO Phase 2: Implement governor
+use crate::clustering::ClusterConfig;
+use crate::types::{BasinFeedback, BasinType, Tempo};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Adjustments kept for inspection
+pub const HISTORY: usize = 64;
+
+/// Targets, gain and bounds
+#[derive(Debug, Clone)]
+pub struct GovernorConfig {
+    pub min_rate: f32,              // basins/min per tempo; below this thresholds relax
+    pub max_rate: f32,              // basins/min per tempo; above this persistence rises
+    pub min_cohesion: f32,          // mean silhouette; below this shapes tighten
+    pub gain: f32,                  // fraction of a value moved per unit of error
+    pub window_ms: u64,             // trailing window rates are measured over
+    pub interval_ms: u64,           // min time between two adjustment rounds
+    pub persistence: (u32, u32),    // persistence_min bounds (ticks)
+    pub min_points: (usize, usize), // min_points bounds
+    pub epsilon: (f32, f32),        // epsilon bounds (cosine distance)
+}
+
+impl Default for GovernorConfig {
+    fn default() -> Self {
+        Self {
+            min_rate: 5.0, // Fast + Slow together span SPEC's 10–30/min
+            max_rate: 15.0,
+            min_cohesion: 0.5,
+            gain: 0.5,
+            window_ms: 60_000,
+            interval_ms: 5_000,
+            persistence: (1, 64),
+            min_points: (2, 16),
+            epsilon: (0.05, 0.5),
+        }
+    }
+}
+
+/// What the governor saw when it made a decision
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observation {
+    pub fast_rate: f32,        // Fast basins/min over the window
+    pub slow_rate: f32,        // Slow basins/min over the window
+    pub cohesion: Option<f32>, // mean nd_cohesion of valleys and peaks
+    pub live_points: usize,    // attracting entries in the live window
+}
+
+impl Observation {
+    fn rate(&self, tempo: Tempo) -> f32 {
+        match tempo {
+            Tempo::Fast => self.fast_rate,
+            _ => self.slow_rate,
+        }
+    }
+}
+
+/// Threshold the governor moved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parameter {
+    Persistence(Tempo),
+    MinPoints,
+    Epsilon,
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Parameter::Persistence(tempo) => write!(f, "persistence_min[{:?}]", tempo),
+            Parameter::MinPoints => write!(f, "min_points"),
+            Parameter::Epsilon => write!(f, "epsilon"),
+        }
+    }
+}
+
+/// One logged threshold change
+#[derive(Debug, Clone, PartialEq)]
+pub struct Adjustment {
+    pub at: u64, // epoch ms
+    pub parameter: Parameter,
+    pub from: f32,
+    pub to: f32,
+    pub observed: Observation,
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let o = &self.observed;
+        write!(
+            f,
+            "{} {} -> {} (fast {:.1}/min, slow {:.1}/min, cohesion ",
+            self.parameter, self.from, self.to, o.fast_rate, o.slow_rate
+        )?;
+        match o.cohesion {
+            Some(c) => write!(f, "{:.2})", c),
+            None => write!(f, "n/a)"),
+        }
+    }
+}
+
+/// Emitted basin as remembered for rate and cohesion
+struct Sample {
+    at: u64,
+    tempo: Tempo,
+    cohesion: Option<f32>, // None for ridges (boundary, not a basin body)
+}
+
+/// Proportional controller over a ClusterConfig
+pub struct Governor {
+    config: GovernorConfig,
+    samples: VecDeque<Sample>,
+    started: Option<u64>,    // first observation (rates need a full window)
+    last_round: Option<u64>, // last adjustment round
+    history: VecDeque<Adjustment>,
+    total: u64, // adjustments ever made
+}
+
+impl Governor {
+    pub fn new() -> Self {
+        Self::with_config(GovernorConfig::default())
+    }
+
+    pub fn with_config(config: GovernorConfig) -> Self {
+        Self {
+            config,
+            samples: VecDeque::new(),
+            started: None,
+            last_round: None,
+            history: VecDeque::new(),
+            total: 0,
+        }
+    }
+
+    pub fn config(&self) -> &GovernorConfig {
+        &self.config
+    }
+
+    /// Record one tick's feedback (urgent alerts bypass thresholds, so
+    /// they are ignored)
+    pub fn observe(&mut self, feedback: &[BasinFeedback], now: u64) {
+        self.started.get_or_insert(now);
+        for basin in feedback.iter().filter(|b| b.tempo != Tempo::Urgent) {
+            self.samples.push_back(Sample {
+                at: now,
+                tempo: basin.tempo,
+                cohesion: (basin.type_ != BasinType::Ridge).then_some(basin.nd_cohesion),
+            });
+        }
+        let horizon = now.saturating_sub(self.config.window_ms);
+        while self.samples.front().is_some_and(|s| s.at < horizon) {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Rates and cohesion over the trailing window
+    pub fn observation(&self, live_points: usize) -> Observation {
+        let minutes = self.config.window_ms as f32 / 60_000.0;
+        let count = |tempo: Tempo| self.samples.iter().filter(|s| s.tempo == tempo).count();
+        let cohesions: Vec<f32> = self.samples.iter().filter_map(|s| s.cohesion).collect();
+
+        Observation {
+            fast_rate: count(Tempo::Fast) as f32 / minutes,
+            slow_rate: count(Tempo::Slow) as f32 / minutes,
+            cohesion: (!cohesions.is_empty())
+                .then(|| cohesions.iter().sum::<f32>() / cohesions.len() as f32),
+            live_points,
+        }
+    }
+
+    /// Move `thresholds` toward the targets; returns (and logs) the changes
+    /// Does nothing until a full window was observed, nor more often than
+    /// every `interval_ms`
+    pub fn adjust(
+        &mut self,
+        thresholds: &mut ClusterConfig,
+        live_points: usize,
+        now: u64,
+    ) -> Vec<Adjustment> {
+        let warm = self
+            .started
+            .is_some_and(|t| now.saturating_sub(t) >= self.config.window_ms);
+        let due = self
+            .last_round
+            .is_none_or(|t| now.saturating_sub(t) >= self.config.interval_ms);
+        if !warm || !due {
+            return Vec::new();
+        }
+        self.last_round = Some(now);
+
+        let observed = self.observation(live_points);
+        let c = &self.config;
+        // Relaxing only makes sense with material that could form a basin
+        let has_material = live_points >= thresholds.min_points;
+        let mut changes: Vec<(Parameter, f32, f32)> = Vec::new();
+
+        for tempo in [Tempo::Fast, Tempo::Slow] {
+            let rate = observed.rate(tempo);
+            let from = thresholds.persistence_min(tempo);
+            let to = if rate > c.max_rate {
+                step_up(from, c.gain * (rate / c.max_rate - 1.0))
+            } else if rate < c.min_rate && has_material {
+                step_down(from, c.gain * (1.0 - rate / c.min_rate))
+            } else {
+                from
+            };
+            let to = to.clamp(c.persistence.0, c.persistence.1);
+            if to != from {
+                match tempo {
+                    Tempo::Fast => thresholds.min_persistence_fast = to,
+                    _ => thresholds.min_persistence = to,
+                }
+                changes.push((Parameter::Persistence(tempo), from as f32, to as f32));
+            }
+        }
+
+        let total_rate = observed.fast_rate + observed.slow_rate;
+        let (min_points, epsilon) = match observed.cohesion {
+            // Loose basins: demand denser cores within a smaller radius
+            Some(cohesion) if cohesion < c.min_cohesion => {
+                let error = ((c.min_cohesion - cohesion) / c.min_cohesion).min(1.0);
+                (
+                    step_up(thresholds.min_points as u32, c.gain * error) as usize,
+                    thresholds.epsilon * (1.0 - c.gain * error),
+                )
+            }
+            // Too few basins overall: let sparser, wider groups through
+            _ if total_rate < 2.0 * c.min_rate && has_material => {
+                let error = 1.0 - total_rate / (2.0 * c.min_rate);
+                (
+                    step_down(thresholds.min_points as u32, c.gain * error) as usize,
+                    thresholds.epsilon * (1.0 + c.gain * error),
+                )
+            }
+            _ => (thresholds.min_points, thresholds.epsilon),
+        };
+
+        let min_points = min_points.clamp(c.min_points.0, c.min_points.1);
+        if min_points != thresholds.min_points {
+            changes.push((
+                Parameter::MinPoints,
+                thresholds.min_points as f32,
+                min_points as f32,
+            ));
+            thresholds.min_points = min_points;
+        }
+        let epsilon = epsilon.clamp(c.epsilon.0, c.epsilon.1);
+        if (epsilon - thresholds.epsilon).abs() > 1e-4 {
+            changes.push((Parameter::Epsilon, thresholds.epsilon, epsilon));
+            thresholds.epsilon = epsilon;
+        }
+
+        let adjustments: Vec<Adjustment> = changes
+            .into_iter()
+            .map(|(parameter, from, to)| Adjustment {
+                at: now,
+                parameter,
+                from,
+                to,
+                observed,
+            })
+            .collect();
+        for adjustment in &adjustments {
+            if self.history.len() == HISTORY {
+                self.history.pop_front();
+            }
+            self.history.push_back(adjustment.clone());
+        }
+        self.total += adjustments.len() as u64;
+        adjustments
+    }
+
+    /// Most recent adjustments, oldest first (at most HISTORY)
+    pub fn history(&self) -> impl Iterator<Item = &Adjustment> {
+        self.history.iter()
+    }
+
+    /// Adjustments made since the governor started
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+impl Default for Governor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Proportional step of at least one unit
+fn step_up(value: u32, fraction: f32) -> u32 {
+    value + ((value as f32 * fraction).round() as u32).max(1)
+}
+
+fn step_down(value: u32, fraction: f32) -> u32 {
+    value.saturating_sub(((value as f32 * fraction).round() as u32).max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Action;
+
+    fn basin(tempo: Tempo, cohesion: f32) -> BasinFeedback {
+        BasinFeedback {
+            basin_id: "valley_1".to_string(),
+            type_: BasinType::Valley,
+            coords_2d: [0.0, 0.0],
+            rep_id: "h0".to_string(),
+            rep_phrase: "memory safety".to_string(),
+            contributors: vec!["h0".to_string(), "h1".to_string()],
+            nd_cohesion: cohesion,
+            nd_radius: 0.1,
+            persistence: 2,
+            tempo,
+            dissent: 0,
+            centroid: None,
+            endpoints: None,
+            axis: None,
+            decompose_into: None,
+            recommended_action: Action::PlanSpike,
+            precard: None,
+            thresholds: None,
+            timestamp: 1000,
+        }
+    }
+
+    /// Feed `per_min` basins of `tempo` per minute for one full window
+    fn warm_up(governor: &mut Governor, tempo: Tempo, per_min: usize, cohesion: f32) -> u64 {
+        governor.observe(&[], 0);
+        let feedback = vec![basin(tempo, cohesion); per_min];
+        governor.observe(&feedback, 60_000);
+        60_000
+    }
+
+    #[test]
+    fn test_fast_flood_raises_only_fast_persistence() {
+        let mut governor = Governor::new();
+        let mut config = ClusterConfig::default();
+        let now = warm_up(&mut governor, Tempo::Fast, 45, 0.75);
+
+        let adjustments = governor.adjust(&mut config, 100, now);
+        // 45/min is 3x the 15/min ceiling: error 2, gain 0.5 → +100%
+        assert_eq!(config.min_persistence_fast, 4);
+        assert_eq!(config.min_persistence, 1); // no Slow basins: relaxed
+        assert_eq!(
+            adjustments[0],
+            Adjustment {
+                at: now,
+                parameter: Parameter::Persistence(Tempo::Fast),
+                from: 2.0,
+                to: 4.0,
+                observed: Observation {
+                    fast_rate: 45.0,
+                    slow_rate: 0.0,
+                    cohesion: Some(0.75),
+                    live_points: 100,
+                },
+            }
+        );
+        assert_eq!(
+            adjustments[0].to_string(),
+            "persistence_min[Fast] 2 -> 4 (fast 45.0/min, slow 0.0/min, cohesion 0.75)"
+        );
+        assert_eq!(governor.total(), adjustments.len() as u64);
+    }
+
+    #[test]
+    fn test_low_cohesion_tightens_shape() {
+        let mut governor = Governor::new();
+        let mut config = ClusterConfig::default();
+        let now = warm_up(&mut governor, Tempo::Slow, 10, 0.25);
+
+        governor.adjust(&mut config, 100, now);
+        // error (0.5 - 0.25) / 0.5 = 0.5, gain 0.5 → epsilon shrinks 25%
+        assert_eq!(config.min_points, 3);
+        assert!((config.epsilon - 0.25 * 0.75).abs() < 1e-6);
+        assert_eq!(config.min_persistence, 2); // 10/min is within band
+    }
+
+    #[test]
+    fn test_starvation_relaxes_within_bounds() {
+        let mut governor = Governor::new();
+        let mut config = ClusterConfig::default();
+        governor.observe(&[], 0);
+
+        // No basins, but enough live points to form some
+        let mut now = 60_000;
+        for _ in 0..20 {
+            governor.observe(&[], now);
+            governor.adjust(&mut config, 50, now);
+            now += 5_000;
+        }
+        assert_eq!(config.min_persistence, 1);
+        assert_eq!(config.min_persistence_fast, 1);
+        assert_eq!(config.min_points, 2);
+        assert_eq!(config.epsilon, 0.5);
+
+        // Nothing to cluster: no relaxation (and nothing logged)
+        let mut governor = Governor::new();
+        let mut config = ClusterConfig::default();
+        governor.observe(&[], 0);
+        assert!(governor.adjust(&mut config, 0, 60_000).is_empty());
+        assert_eq!(config.epsilon, 0.25);
+    }
+
+    #[test]
+    fn test_waits_for_window_and_interval() {
+        let mut governor = Governor::new();
+        let mut config = ClusterConfig::default();
+        governor.observe(&vec![basin(Tempo::Fast, 0.8); 45], 0);
+
+        assert!(governor.adjust(&mut config, 100, 30_000).is_empty()); // not warm
+        assert!(!governor.adjust(&mut config, 100, 60_000).is_empty());
+        assert!(governor.adjust(&mut config, 100, 61_000).is_empty()); // too soon
+        assert_eq!(governor.history().count() as u64, governor.total());
+    }
+}
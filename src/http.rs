@@ -0,0 +1,310 @@
+// Minimal HTTP/1.1 over std::net (no async runtime, no client crate)
+//
+// Just enough for JSON request/response on a LAN: one request per
+// connection (`Connection: close`), Content-Length or chunked bodies.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const MAX_BODY: usize = 64 * 1024 * 1024;
+
+/// Parsed `http://host[:port]/path` URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Url {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl Url {
+    pub fn parse(url: &str) -> io::Result<Self> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| invalid(format!("only http:// URLs are supported: {}", url)))?;
+
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port
+                    .parse()
+                    .map_err(|_| invalid(format!("bad port in {}", url)))?;
+                (host, port)
+            }
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(invalid(format!("missing host in {}", url)));
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// Status + body of a response (or request, server-side)
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub status: u16,
+    pub body: Vec<u8>,
+}
+
+/// Incoming request as seen by a server
+#[derive(Debug)]
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    #[cfg_attr(not(unix), allow(dead_code))] // read by the unix-only API
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Send `method` with an optional JSON body and read the whole response
+pub(crate) fn send(
+    method: &str,
+    url: &Url,
+    body: Option<&[u8]>,
+    timeout: Duration,
+) -> io::Result<Response> {
+    let addr = (url.host.as_str(), url.port)
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| invalid(format!("cannot resolve {}", url.host)))?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: {}:{}\r\nConnection: close\r\nAccept: application/json\r\n",
+        method, url.path, url.host, url.port
+    );
+    if let Some(body) = body {
+        head.push_str("Content-Type: application/json\r\n");
+        head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    head.push_str("\r\n");
+
+    stream.write_all(head.as_bytes())?;
+    if let Some(body) = body {
+        stream.write_all(body)?;
+    }
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let status_line = read_line(&mut reader)?;
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| invalid(format!("bad status line: {:?}", status_line)))?;
+
+    let headers = read_headers(&mut reader)?;
+    let body = read_body(&mut reader, &headers, true)?;
+
+    Ok(Response { status, body })
+}
+
+/// POST a JSON body
+pub(crate) fn post_json(url: &Url, body: &[u8], timeout: Duration) -> io::Result<Response> {
+    send("POST", url, Some(body), timeout)
+}
+
+/// Read one request from a client connection (None on clean EOF)
+pub(crate) fn read_request<R: BufRead>(reader: &mut R) -> io::Result<Option<Request>> {
+    let line = match read_line(reader) {
+        Ok(line) => line,
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut parts = line.split_whitespace();
+    let (method, path) = match (parts.next(), parts.next()) {
+        (Some(m), Some(p)) => (m.to_string(), p.to_string()),
+        _ => return Err(invalid(format!("bad request line: {:?}", line))),
+    };
+
+    let headers = read_headers(reader)?;
+    let body = read_body(reader, &headers, false)?;
+    let content_type = header(&headers, "Content-Type").map(str::to_string);
+
+    Ok(Some(Request {
+        method,
+        path,
+        content_type,
+        body,
+    }))
+}
+
+/// Write a complete response and close the exchange
+pub(crate) fn write_response<W: Write>(
+    writer: &mut W,
+    status: u16,
+    content_type: &str,
+    body: &[u8],
+) -> io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        reason(status),
+        content_type,
+        body.len()
+    );
+    writer.write_all(head.as_bytes())?;
+    writer.write_all(body)?;
+    writer.flush()
+}
+
+/// Open a Server-Sent Events response; events follow until the socket closes
+#[cfg(unix)]
+pub(crate) fn write_event_stream_head<W: Write>(writer: &mut W) -> io::Result<()> {
+    writer.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+    )?;
+    writer.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+fn read_line<R: BufRead>(reader: &mut R) -> io::Result<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connection closed",
+        ));
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn read_headers<R: BufRead>(reader: &mut R) -> io::Result<Vec<(String, String)>> {
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            return Ok(headers);
+        }
+        if let Some((k, v)) = line.split_once(':') {
+            headers.push((k.trim().to_string(), v.trim().to_string()));
+        }
+    }
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// Body per Content-Length, chunked encoding, or (responses only) EOF
+fn read_body<R: BufRead>(
+    reader: &mut R,
+    headers: &[(String, String)],
+    until_eof: bool,
+) -> io::Result<Vec<u8>> {
+    let chunked = header(headers, "transfer-encoding")
+        .map(|v| v.eq_ignore_ascii_case("chunked"))
+        .unwrap_or(false);
+    if chunked {
+        return read_chunked(reader);
+    }
+
+    if let Some(len) = header(headers, "content-length") {
+        let len: usize = len
+            .parse()
+            .map_err(|_| invalid(format!("bad content-length: {}", len)))?;
+        if len > MAX_BODY {
+            return Err(invalid(format!("body too large: {} bytes", len)));
+        }
+        let mut body = vec![0; len];
+        reader.read_exact(&mut body)?;
+        return Ok(body);
+    }
+
+    let mut body = Vec::new();
+    if until_eof {
+        reader.take(MAX_BODY as u64).read_to_end(&mut body)?;
+    }
+    Ok(body)
+}
+
+fn read_chunked<R: BufRead>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        let size_hex = line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| invalid(format!("bad chunk size: {:?}", line)))?;
+        if size == 0 {
+            // Skip trailers up to the final blank line
+            while !read_line(reader)?.is_empty() {}
+            return Ok(body);
+        }
+        if body.len() + size > MAX_BODY {
+            return Err(invalid("chunked body too large".to_string()));
+        }
+        let start = body.len();
+        body.resize(start + size, 0);
+        reader.read_exact(&mut body[start..])?;
+        read_line(reader)?; // CRLF after each chunk
+    }
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_parse_url() {
+        let url = Url::parse("http://embedding-host:8000/embed").unwrap();
+        assert_eq!(url.host, "embedding-host");
+        assert_eq!(url.port, 8000);
+        assert_eq!(url.path, "/embed");
+
+        let url = Url::parse("http://localhost").unwrap();
+        assert_eq!((url.port, url.path.as_str()), (80, "/"));
+
+        assert!(Url::parse("https://secure/embed").is_err());
+        assert!(Url::parse("http://:80/").is_err());
+    }
+
+    #[test]
+    fn test_read_request_and_chunked_body() {
+        let raw = b"POST /embed HTTP/1.1\r\nHost: x\r\nContent-Length: 4\r\n\r\n[\"a\"]";
+        let req = read_request(&mut Cursor::new(&raw[..])).unwrap().unwrap();
+        assert_eq!(req.method, "POST");
+        assert_eq!(req.path, "/embed");
+        assert_eq!(req.body, b"[\"a\"");
+
+        let chunked = b"4\r\nWiki\r\n5;ext\r\npedia\r\n0\r\n\r\n";
+        let body = read_chunked(&mut Cursor::new(&chunked[..])).unwrap();
+        assert_eq!(body, b"Wikipedia");
+    }
+}
@@ -0,0 +1,421 @@
+// HNSW approximate nearest-neighbour index over ledger vectors
+//
+// Hierarchical navigable small world graph (Malkov & Yashunin), cosine
+// distance on unit vectors. Ids are caller-chosen (ledger positions);
+// removed ids are tombstoned and the graph is rebuilt once tombstones
+// outnumber live nodes.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Graph shape and search effort
+#[derive(Debug, Clone)]
+pub struct HnswConfig {
+    pub m: usize,               // links per node per layer (2m on layer 0)
+    pub ef_construction: usize, // candidate list size while inserting
+    pub ef_search: usize,       // candidate list size while querying
+    pub seed: u64,              // level RNG seed (deterministic builds)
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 100,
+            ef_search: 64,
+            seed: 0x5EF1,
+        }
+    }
+}
+
+struct Node {
+    id: usize,
+    vector: Vec<f32>,
+    links: Vec<Vec<usize>>, // per layer, node indices
+    deleted: bool,
+}
+
+/// Approximate k-NN / radius index
+pub struct Hnsw {
+    config: HnswConfig,
+    nodes: Vec<Node>,
+    slots: HashMap<usize, usize>, // id → node index (live only)
+    entry: Option<usize>,         // node index of the top-layer entry point
+    max_level: usize,
+    deleted: usize, // tombstoned nodes still in the graph
+    rng: u64,
+}
+
+impl Hnsw {
+    pub fn new(config: HnswConfig) -> Self {
+        let rng = config.seed.max(1);
+        Self {
+            config,
+            nodes: Vec::new(),
+            slots: HashMap::new(),
+            entry: None,
+            max_level: 0,
+            deleted: 0,
+            rng,
+        }
+    }
+
+    /// Index `vector` under `id` (replaces any previous vector for `id`)
+    pub fn insert(&mut self, id: usize, vector: Vec<f32>) {
+        self.remove(id);
+
+        let level = self.random_level();
+        let node = self.nodes.len();
+        self.nodes.push(Node {
+            id,
+            vector,
+            links: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.slots.insert(id, node);
+
+        let Some(mut entry) = self.entry else {
+            self.entry = Some(node);
+            self.max_level = level;
+            return;
+        };
+
+        // Greedy descent through layers above the new node's level
+        let query = self.nodes[node].vector.clone();
+        for layer in (level + 1..=self.max_level).rev() {
+            entry = self.greedy(&query, entry, layer);
+        }
+
+        for layer in (0..=level.min(self.max_level)).rev() {
+            let found = self.search_layer(&query, &[entry], self.config.ef_construction, layer);
+            entry = found[0].1;
+
+            let max_links = self.max_links(layer);
+            let chosen: Vec<usize> = found.iter().take(max_links).map(|s| s.1).collect();
+            for &other in &chosen {
+                self.nodes[other].links[layer].push(node);
+                if self.nodes[other].links[layer].len() > max_links {
+                    self.prune(other, layer);
+                }
+            }
+            self.nodes[node].links[layer] = chosen;
+        }
+
+        if level > self.max_level {
+            self.entry = Some(node);
+            self.max_level = level;
+        }
+    }
+
+    /// Tombstone `id`; returns false if it was not indexed
+    /// Tombstones still route searches but never appear in results
+    pub fn remove(&mut self, id: usize) -> bool {
+        let Some(node) = self.slots.remove(&id) else {
+            return false;
+        };
+        self.nodes[node].deleted = true;
+        self.deleted += 1;
+
+        if self.deleted > self.slots.len() && self.nodes.len() > 2 * self.config.m {
+            self.rebuild();
+        }
+        true
+    }
+
+    /// Up to `k` nearest live ids as (id, cosine distance), closest first
+    pub fn knn(&self, query: &[f32], k: usize) -> Vec<(usize, f32)> {
+        let Some(mut entry) = self.entry else {
+            return Vec::new();
+        };
+        if k == 0 {
+            return Vec::new();
+        }
+
+        for layer in (1..=self.max_level).rev() {
+            entry = self.greedy(query, entry, layer);
+        }
+
+        // Widen the beam by the tombstone share so k live results survive
+        let ef = self.config.ef_search.max(k) * self.nodes.len() / self.slots.len().max(1);
+        self.search_layer(query, &[entry], ef, 0)
+            .into_iter()
+            .filter(|s| !self.nodes[s.1].deleted)
+            .take(k)
+            .map(|s| (self.nodes[s.1].id, s.0))
+            .collect()
+    }
+
+    /// Live ids within cosine distance `radius`, closest first
+    /// Grows k until the furthest hit falls outside the radius
+    pub fn within(&self, query: &[f32], radius: f32) -> Vec<(usize, f32)> {
+        let mut k = self.config.ef_search;
+        loop {
+            let mut hits = self.knn(query, k);
+            let exhausted = hits.len() < k;
+            let beyond = hits.last().map(|h| h.1 > radius).unwrap_or(true);
+            if exhausted || beyond {
+                hits.retain(|h| h.1 <= radius);
+                return hits;
+            }
+            k *= 2;
+        }
+    }
+
+    /// Whether `id` is indexed (and not tombstoned)
+    pub fn contains(&self, id: usize) -> bool {
+        self.slots.contains_key(&id)
+    }
+
+    /// Number of live ids
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Check if no live ids remain
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            2 * self.config.m
+        } else {
+            self.config.m
+        }
+    }
+
+    /// Level drawn from an exponential distribution, mL = 1/ln(m)
+    fn random_level(&mut self) -> usize {
+        // xorshift64*
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let bits = self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11;
+        let uniform = (bits as f64 + 1.0) / (1u64 << 53) as f64; // (0, 1]
+
+        let ml = 1.0 / (self.config.m.max(2) as f64).ln();
+        (-uniform.ln() * ml) as usize
+    }
+
+    fn distance(&self, query: &[f32], node: usize) -> f32 {
+        cosine_distance(query, &self.nodes[node].vector)
+    }
+
+    /// Closest node reachable by hill-climbing on one layer
+    fn greedy(&self, query: &[f32], mut current: usize, layer: usize) -> usize {
+        let mut best = self.distance(query, current);
+        loop {
+            let mut moved = false;
+            for &next in &self.nodes[current].links[layer] {
+                let d = self.distance(query, next);
+                if d < best {
+                    best = d;
+                    current = next;
+                    moved = true;
+                }
+            }
+            if !moved {
+                return current;
+            }
+        }
+    }
+
+    /// Beam search on one layer; returns up to `ef` (distance, node), closest first
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entries: &[usize],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Scored> {
+        let mut visited: HashSet<usize> = entries.iter().copied().collect();
+        let mut candidates: BinaryHeap<std::cmp::Reverse<Scored>> = BinaryHeap::new();
+        let mut results: BinaryHeap<Scored> = BinaryHeap::new();
+
+        for &e in entries {
+            let scored = Scored(self.distance(query, e), e);
+            candidates.push(std::cmp::Reverse(scored));
+            results.push(scored);
+        }
+
+        while let Some(std::cmp::Reverse(current)) = candidates.pop() {
+            let furthest = results.peek().map(|s| s.0).unwrap_or(f32::MAX);
+            if current.0 > furthest && results.len() >= ef {
+                break;
+            }
+
+            for &next in &self.nodes[current.1].links[layer] {
+                if !visited.insert(next) {
+                    continue;
+                }
+                let d = self.distance(query, next);
+                let furthest = results.peek().map(|s| s.0).unwrap_or(f32::MAX);
+                if results.len() < ef || d < furthest {
+                    candidates.push(std::cmp::Reverse(Scored(d, next)));
+                    results.push(Scored(d, next));
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        results.into_sorted_vec()
+    }
+
+    /// Keep only the closest links of `node` on `layer`
+    fn prune(&mut self, node: usize, layer: usize) {
+        let max_links = self.max_links(layer);
+        let mut links: Vec<Scored> = self.nodes[node].links[layer]
+            .iter()
+            .map(|&other| {
+                Scored(
+                    cosine_distance(&self.nodes[node].vector, &self.nodes[other].vector),
+                    other,
+                )
+            })
+            .collect();
+        links.sort();
+        links.truncate(max_links);
+        self.nodes[node].links[layer] = links.into_iter().map(|s| s.1).collect();
+    }
+
+    /// Re-insert live nodes into a fresh graph, dropping tombstones
+    fn rebuild(&mut self) {
+        let mut live: Vec<(usize, Vec<f32>)> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .filter(|n| !n.deleted)
+            .map(|n| (n.id, n.vector))
+            .collect();
+        live.sort_by_key(|(id, _)| *id);
+
+        self.slots.clear();
+        self.entry = None;
+        self.max_level = 0;
+        self.deleted = 0;
+        for (id, vector) in live {
+            self.insert(id, vector);
+        }
+    }
+}
+
+impl Default for Hnsw {
+    fn default() -> Self {
+        Self::new(HnswConfig::default())
+    }
+}
+
+/// 1 - dot product (vectors are unit length)
+pub fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    1.0 - a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>()
+}
+
+/// (distance, node index) ordered by distance
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scored(f32, usize);
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embed::EmbedService;
+
+    fn brute_force(vectors: &[Vec<f32>], query: &[f32], k: usize) -> Vec<usize> {
+        let mut scored: Vec<(f32, usize)> = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (cosine_distance(query, v), i))
+            .collect();
+        scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+        scored.into_iter().take(k).map(|(_, i)| i).collect()
+    }
+
+    fn vectors(n: usize) -> Vec<Vec<f32>> {
+        let embed = EmbedService::with_dim(32);
+        (0..n)
+            .map(|i| embed.embed(&format!("phrase {}", i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_knn_recall_against_brute_force() {
+        let data = vectors(500);
+        let mut index = Hnsw::default();
+        for (i, v) in data.iter().enumerate() {
+            index.insert(i, v.clone());
+        }
+        assert_eq!(index.len(), 500);
+
+        let mut found = 0;
+        for q in 0..50 {
+            let query = &data[q * 7];
+            let exact: HashSet<usize> = brute_force(&data, query, 10).into_iter().collect();
+            let approx = index.knn(query, 10);
+            assert_eq!(approx.len(), 10);
+            assert!(approx.windows(2).all(|w| w[0].1 <= w[1].1));
+            found += approx.iter().filter(|(id, _)| exact.contains(id)).count();
+        }
+
+        let recall = found as f32 / 500.0;
+        assert!(recall >= 0.9, "recall@10 = {}", recall);
+    }
+
+    #[test]
+    fn test_within_radius() {
+        let data = vectors(200);
+        let mut index = Hnsw::default();
+        for (i, v) in data.iter().enumerate() {
+            index.insert(i, v.clone());
+        }
+
+        let query = &data[0];
+        let radius = 0.9;
+        let mut expected: Vec<usize> = (0..data.len())
+            .filter(|&i| cosine_distance(query, &data[i]) <= radius)
+            .collect();
+        let mut got: Vec<usize> = index.within(query, radius).iter().map(|h| h.0).collect();
+        expected.sort_unstable();
+        got.sort_unstable();
+
+        assert!(got.contains(&0));
+        assert_eq!(got, expected);
+    }
+
+    #[test]
+    fn test_tombstones_and_rebuild() {
+        let data = vectors(300);
+        let mut index = Hnsw::default();
+        for (i, v) in data.iter().enumerate() {
+            index.insert(i, v.clone());
+        }
+
+        assert!(index.remove(5));
+        assert!(!index.remove(5));
+        assert!(!index.contains(5));
+        assert!(index.knn(&data[5], 5).iter().all(|(id, _)| *id != 5));
+
+        // Age out most of the window; the graph compacts itself
+        for i in 0..250 {
+            index.remove(i);
+        }
+        assert_eq!(index.len(), 50);
+        assert!(index.nodes.len() < 300, "tombstones were compacted");
+
+        let hits = index.knn(&data[260], 3);
+        assert_eq!(hits[0].0, 260);
+        assert!(hits.iter().all(|(id, _)| *id >= 250));
+    }
+}
@@ -1,3 +1,5 @@
-// N-D vector ledger (append-only, in-memory for Phase 1)
+// N-D vector ledger (append-only, in-memory or segment-file backed)
 
+pub mod hnsw;
+pub mod segment;
 pub mod store;
@@ -0,0 +1,701 @@
+// Append-only segment files for the durable ledger
+//
+// Layout: <dir>/00000000.seg, 00000001.seg, ...
+// Each segment starts with an 8-byte magic and a header naming the
+// embedding model and dimension of its vectors, followed by records:
+//   [len: u32 LE][crc32(payload): u32 LE][payload: len bytes]
+// The header uses the same framing. The magic's last byte is the format
+// version; segments of any other version are refused.
+// A torn or corrupt record at the tail of the newest segment is truncated
+// on open; corruption anywhere else is reported as InvalidData, and so is
+// a header naming a different model.
+
+use crate::types::{LedgerEntry, Polarity, Tempo};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"SEFISEG4";
+const MAGIC_PREFIX: &[u8] = b"SEFISEG"; // '1'-'3' were pre-release formats
+const RECORD_HEADER: usize = 8; // len + crc
+const MAX_RECORD: usize = 64 * 1024 * 1024; // sanity bound for torn lengths
+
+/// Embedding model behind a ledger's vectors, stored in every segment header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelInfo {
+    pub model_id: String,
+    pub dim: usize,
+}
+
+impl ModelInfo {
+    pub fn new(model_id: &str, dim: usize) -> Self {
+        Self {
+            model_id: model_id.to_string(),
+            dim,
+        }
+    }
+}
+
+/// When appended records are flushed to stable storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    Always,      // fsync after every record (safest, slowest)
+    EveryN(u32), // fsync after every N records
+    Never,       // leave it to the OS page cache
+}
+
+/// Tuning for the on-disk ledger
+#[derive(Debug, Clone, Copy)]
+pub struct SegmentConfig {
+    pub segment_bytes: u64, // roll to a new segment beyond this size
+    pub fsync: FsyncPolicy,
+}
+
+impl Default for SegmentConfig {
+    fn default() -> Self {
+        Self {
+            segment_bytes: 64 * 1024 * 1024,
+            fsync: FsyncPolicy::EveryN(64),
+        }
+    }
+}
+
+/// Writer over a directory of segment files
+pub struct SegmentLog {
+    dir: PathBuf,
+    config: SegmentConfig,
+    model: ModelInfo,
+    file: File,
+    segment_id: u32,
+    segment_len: u64,
+    unsynced: u32,
+    truncated: u64, // torn tail bytes dropped by `open`
+    #[cfg(test)]
+    fail_sync: bool, // make `sync` fail, as a full or failing disk would
+}
+
+impl SegmentLog {
+    /// Open (or create) a segment directory for `model` and replay every record
+    /// Torn tail writes in the newest segment are truncated away; segments
+    /// written for another model or dimension are refused
+    pub fn open(
+        dir: &Path,
+        model: &ModelInfo,
+        config: SegmentConfig,
+    ) -> io::Result<(Self, Vec<LedgerEntry>)> {
+        fs::create_dir_all(dir)?;
+
+        let ids = segment_ids(dir)?;
+        let mut entries = Vec::new();
+        let mut truncated = 0;
+
+        for (i, &id) in ids.iter().enumerate() {
+            let is_last = i + 1 == ids.len();
+            let path = segment_path(dir, id);
+            let bytes = fs::read(&path)?;
+
+            if let Some(magic) = bytes.get(..MAGIC.len()) {
+                if magic.starts_with(MAGIC_PREFIX) && magic != MAGIC {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "{} has segment format {}, expected {}",
+                            path.display(),
+                            magic[7] as char,
+                            MAGIC[7] as char
+                        ),
+                    ));
+                }
+            }
+
+            let (good_len, stored, mut recovered) = replay(&bytes);
+            if let Some(stored) = stored.filter(|stored| stored != model) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{} holds {} ({} dims), not {} ({} dims)",
+                        path.display(),
+                        stored.model_id,
+                        stored.dim,
+                        model.model_id,
+                        model.dim
+                    ),
+                ));
+            }
+            if good_len < bytes.len() as u64 {
+                if !is_last {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("corrupt record in sealed segment {}", path.display()),
+                    ));
+                }
+                truncated = truncate(&path, good_len)?;
+            }
+            entries.append(&mut recovered);
+        }
+
+        let segment_id = ids.last().copied().unwrap_or(0);
+        let (file, segment_len) = open_for_append(&segment_path(dir, segment_id), model)?;
+
+        let log = Self {
+            dir: dir.to_path_buf(),
+            config,
+            model: model.clone(),
+            file,
+            segment_id,
+            segment_len,
+            unsynced: 0,
+            truncated,
+            #[cfg(test)]
+            fail_sync: false,
+        };
+
+        Ok((log, entries))
+    }
+
+    /// Append one entry as a checksummed record
+    /// On Err nothing was appended: a record whose fsync failed is cut off
+    /// again, so retrying the same entry never leaves two copies to replay
+    pub fn append(&mut self, entry: &LedgerEntry) -> io::Result<()> {
+        let record = frame(&encode_entry(entry));
+
+        let full = self.segment_len + record.len() as u64 > self.config.segment_bytes;
+        if full && self.segment_len > header_len(&self.model) {
+            self.roll()?;
+        }
+
+        if let Err(err) = self.file.write_all(&record) {
+            // Drop the partial record so later appends don't follow garbage
+            self.rewind()?;
+            return Err(err);
+        }
+
+        let due = match self.config.fsync {
+            FsyncPolicy::Always => true,
+            FsyncPolicy::EveryN(n) => self.unsynced + 1 >= n.max(1),
+            FsyncPolicy::Never => false,
+        };
+        if due {
+            if let Err(err) = self.sync() {
+                self.rewind()?;
+                return Err(err);
+            }
+        } else {
+            self.unsynced += 1;
+        }
+        self.segment_len += record.len() as u64;
+
+        Ok(())
+    }
+
+    /// Flush written records to stable storage
+    pub fn sync(&mut self) -> io::Result<()> {
+        #[cfg(test)]
+        if self.fail_sync {
+            return Err(io::Error::other("injected fsync failure"));
+        }
+        self.file.sync_data()?;
+        self.unsynced = 0;
+        Ok(())
+    }
+
+    /// Directory holding the segment files
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Model named in the segment headers
+    pub fn model(&self) -> &ModelInfo {
+        &self.model
+    }
+
+    /// Bytes of torn tail truncated away by `open` (0 after a clean shutdown)
+    pub fn truncated(&self) -> u64 {
+        self.truncated
+    }
+
+    /// Cut the current segment back to its last committed record
+    fn rewind(&mut self) -> io::Result<()> {
+        self.file.set_len(self.segment_len)?;
+        self.file.seek(SeekFrom::Start(self.segment_len))?;
+        Ok(())
+    }
+
+    /// Seal the current segment and start the next one
+    fn roll(&mut self) -> io::Result<()> {
+        self.sync()?;
+        self.segment_id += 1;
+        let (file, len) = open_for_append(&segment_path(&self.dir, self.segment_id), &self.model)?;
+        self.file = file;
+        self.segment_len = len;
+        Ok(())
+    }
+}
+
+fn segment_path(dir: &Path, id: u32) -> PathBuf {
+    dir.join(format!("{:08}.seg", id))
+}
+
+/// Sorted ids of existing segment files
+fn segment_ids(dir: &Path) -> io::Result<Vec<u32>> {
+    let mut ids = Vec::new();
+    for item in fs::read_dir(dir)? {
+        let name = item?.file_name();
+        let name = name.to_string_lossy();
+        if let Some(stem) = name.strip_suffix(".seg") {
+            if let Ok(id) = stem.parse::<u32>() {
+                ids.push(id);
+            }
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// Open a segment for appending, writing magic and header if it is new or empty
+fn open_for_append(path: &Path, model: &ModelInfo) -> io::Result<(File, u64)> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+
+    let mut len = file.seek(SeekFrom::End(0))?;
+    if len == 0 {
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&frame(&encode_model(model)));
+        file.write_all(&header)?;
+        file.sync_data()?;
+        len = header.len() as u64;
+    }
+    Ok((file, len))
+}
+
+/// Bytes before the first record of a segment
+fn header_len(model: &ModelInfo) -> u64 {
+    (MAGIC.len() + RECORD_HEADER + encode_model(model).len()) as u64
+}
+
+/// `payload` with its length and checksum in front
+fn frame(payload: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(RECORD_HEADER + payload.len());
+    record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    record.extend_from_slice(&crc32(payload).to_le_bytes());
+    record.extend_from_slice(payload);
+    record
+}
+
+/// Cut `path` down to `len` bytes; returns how many bytes were dropped
+fn truncate(path: &Path, len: u64) -> io::Result<u64> {
+    // A torn magic or header means nothing valid was ever written here
+    let len = if len < MAGIC.len() as u64 { 0 } else { len };
+    let file = OpenOptions::new().write(true).open(path)?;
+    let dropped = file.metadata()?.len().saturating_sub(len);
+    file.set_len(len)?;
+    file.sync_all()?;
+    Ok(dropped)
+}
+
+/// Decode records until the first torn or corrupt one
+/// Returns the byte length of the valid prefix, the segment's model header
+/// (None if torn) and the decoded entries
+fn replay(bytes: &[u8]) -> (u64, Option<ModelInfo>, Vec<LedgerEntry>) {
+    let mut entries = Vec::new();
+    if !bytes.starts_with(MAGIC) {
+        return (0, None, entries);
+    }
+
+    let header = next_record(bytes, MAGIC.len());
+    let Some((model, mut pos)) =
+        header.and_then(|(payload, end)| Some((decode_model(payload)?, end)))
+    else {
+        return (0, None, entries); // torn header
+    };
+
+    while let Some((payload, end)) = next_record(bytes, pos) {
+        match decode_entry(payload) {
+            Some(entry) => entries.push(entry),
+            None => break,
+        }
+        pos = end;
+    }
+
+    (pos as u64, Some(model), entries)
+}
+
+/// Checksummed payload of the record at `pos` and the position after it
+/// None for a torn or corrupt record
+fn next_record(bytes: &[u8], pos: usize) -> Option<(&[u8], usize)> {
+    if pos + RECORD_HEADER > bytes.len() {
+        return None;
+    }
+    let len = u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+    let crc = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap());
+    let start = pos + RECORD_HEADER;
+
+    if len > MAX_RECORD || start + len > bytes.len() {
+        return None; // torn write
+    }
+    let payload = &bytes[start..start + len];
+    if crc32(payload) != crc {
+        return None;
+    }
+    Some((payload, start + len))
+}
+
+fn encode_model(model: &ModelInfo) -> Vec<u8> {
+    let mut buf = Vec::new();
+    put_str(&mut buf, &model.model_id);
+    buf.extend_from_slice(&(model.dim as u32).to_le_bytes());
+    buf
+}
+
+fn decode_model(payload: &[u8]) -> Option<ModelInfo> {
+    let mut r = payload;
+    let model_id = get_str(&mut r)?;
+    let dim = u32::from_le_bytes(take(&mut r, 4)?.try_into().ok()?) as usize;
+    r.is_empty().then_some(ModelInfo { model_id, dim })
+}
+
+fn encode_entry(entry: &LedgerEntry) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(64 + entry.vector.len() * 4);
+    buf.extend_from_slice(&entry.timestamp.to_le_bytes());
+    buf.push(match entry.tempo {
+        Tempo::Fast => 0,
+        Tempo::Slow => 1,
+        Tempo::Urgent => 2,
+    });
+    buf.push(match entry.polarity {
+        Polarity::Attract => 0,
+        Polarity::Repel => 1,
+    });
+    buf.extend_from_slice(&entry.amp.to_le_bytes());
+    buf.extend_from_slice(&entry.sigma.to_le_bytes());
+    put_str(&mut buf, &entry.rationale_hash);
+    put_str(&mut buf, &entry.agent_id);
+    put_str(&mut buf, &entry.provenance);
+    put_str(&mut buf, &entry.phrase);
+    buf.extend_from_slice(&(entry.vector.len() as u32).to_le_bytes());
+    for x in &entry.vector {
+        buf.extend_from_slice(&x.to_le_bytes());
+    }
+    buf
+}
+
+fn decode_entry(payload: &[u8]) -> Option<LedgerEntry> {
+    let mut r = payload;
+
+    let timestamp = u64::from_le_bytes(take(&mut r, 8)?.try_into().ok()?);
+    let tempo = match take(&mut r, 1)?[0] {
+        0 => Tempo::Fast,
+        1 => Tempo::Slow,
+        2 => Tempo::Urgent,
+        _ => return None,
+    };
+    let polarity = match take(&mut r, 1)?[0] {
+        0 => Polarity::Attract,
+        1 => Polarity::Repel,
+        _ => return None,
+    };
+    let amp = get_f32(&mut r)?;
+    let sigma = get_f32(&mut r)?;
+    let rationale_hash = get_str(&mut r)?;
+    let agent_id = get_str(&mut r)?;
+    let provenance = get_str(&mut r)?;
+    let phrase = get_str(&mut r)?;
+
+    let dim = u32::from_le_bytes(take(&mut r, 4)?.try_into().ok()?) as usize;
+    let raw = take(&mut r, dim.checked_mul(4)?)?;
+    let vector = raw
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+        .collect();
+
+    if !r.is_empty() {
+        return None;
+    }
+
+    Some(LedgerEntry {
+        vector,
+        rationale_hash,
+        agent_id,
+        provenance,
+        phrase,
+        timestamp,
+        tempo,
+        polarity,
+        amp,
+        sigma,
+        coords_2d: None,
+    })
+}
+
+fn put_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn get_str(r: &mut &[u8]) -> Option<String> {
+    let len = u32::from_le_bytes(take(r, 4)?.try_into().ok()?) as usize;
+    String::from_utf8(take(r, len)?.to_vec()).ok()
+}
+
+fn get_f32(r: &mut &[u8]) -> Option<f32> {
+    Some(f32::from_le_bytes(take(r, 4)?.try_into().ok()?))
+}
+
+fn take<'a>(r: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    if r.len() < n {
+        return None;
+    }
+    let (head, tail) = r.split_at(n);
+    *r = tail;
+    Some(head)
+}
+
+/// CRC-32 (IEEE 802.3, reflected, poly 0xEDB88320)
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc = CRC_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("sefi_segment_{}_{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn model() -> ModelInfo {
+        ModelInfo::new("test-model", 3)
+    }
+
+    fn entry(hash: &str, timestamp: u64) -> LedgerEntry {
+        LedgerEntry {
+            vector: vec![0.25, -0.5, 1.0],
+            rationale_hash: hash.to_string(),
+            agent_id: "agent1".to_string(),
+            provenance: "test".to_string(),
+            phrase: "memory safety".to_string(),
+            timestamp,
+            tempo: Tempo::Slow,
+            polarity: Polarity::Repel,
+            amp: 0.8,
+            sigma: 1.0,
+            coords_2d: None,
+        }
+    }
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_append_and_replay() {
+        let dir = temp_dir("replay");
+
+        {
+            let (mut log, entries) =
+                SegmentLog::open(&dir, &model(), SegmentConfig::default()).unwrap();
+            assert!(entries.is_empty());
+            log.append(&entry("h0", 1)).unwrap();
+            log.append(&entry("h1", 2)).unwrap();
+        }
+
+        let (_, entries) = SegmentLog::open(&dir, &model(), SegmentConfig::default()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].rationale_hash, "h1");
+        assert_eq!(entries[1].phrase, "memory safety");
+        assert_eq!(entries[1].vector, vec![0.25, -0.5, 1.0]);
+        assert_eq!((entries[1].amp, entries[1].sigma), (0.8, 1.0));
+        assert_eq!(entries[1].polarity, Polarity::Repel);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_sync_appends_nothing() {
+        let dir = temp_dir("failsync");
+        let config = SegmentConfig {
+            fsync: FsyncPolicy::Always,
+            ..SegmentConfig::default()
+        };
+
+        {
+            let (mut log, _) = SegmentLog::open(&dir, &model(), config).unwrap();
+            log.append(&entry("h0", 1)).unwrap();
+            log.fail_sync = true;
+            assert!(log.append(&entry("h1", 2)).is_err());
+
+            // The caller retries once the disk recovers
+            log.fail_sync = false;
+            log.append(&entry("h1", 2)).unwrap();
+        }
+
+        let (_, entries) = SegmentLog::open(&dir, &model(), config).unwrap();
+        let hashes: Vec<&str> = entries.iter().map(|e| e.rationale_hash.as_str()).collect();
+        assert_eq!(hashes, vec!["h0", "h1"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_refuses_another_model() {
+        let dir = temp_dir("model");
+        {
+            let (mut log, _) = SegmentLog::open(&dir, &model(), SegmentConfig::default()).unwrap();
+            log.append(&entry("h0", 1)).unwrap();
+        }
+
+        for other in [
+            ModelInfo::new("other-model", 3),
+            ModelInfo::new("test-model", 4),
+        ] {
+            let err = SegmentLog::open(&dir, &other, SegmentConfig::default())
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(err.to_string().contains("test-model (3 dims)"));
+        }
+        let (_, entries) = SegmentLog::open(&dir, &model(), SegmentConfig::default()).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_refuses_other_format_versions() {
+        let dir = temp_dir("version");
+        let mut bytes = b"SEFISEG3".to_vec();
+        bytes.extend_from_slice(&[0; 16]);
+        fs::write(segment_path(&dir, 0), &bytes).unwrap();
+
+        let err = SegmentLog::open(&dir, &model(), SegmentConfig::default())
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        // Refused, not truncated as if torn
+        assert_eq!(fs::read(segment_path(&dir, 0)).unwrap(), bytes);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_segments_roll_over() {
+        let dir = temp_dir("roll");
+        let config = SegmentConfig {
+            segment_bytes: 128,
+            fsync: FsyncPolicy::Never,
+        };
+
+        {
+            let (mut log, _) = SegmentLog::open(&dir, &model(), config).unwrap();
+            for i in 0..5 {
+                log.append(&entry(&format!("h{}", i), i)).unwrap();
+            }
+        }
+
+        assert!(segment_ids(&dir).unwrap().len() > 1);
+        let (_, entries) = SegmentLog::open(&dir, &model(), config).unwrap();
+        let hashes: Vec<_> = entries.iter().map(|e| e.rationale_hash.as_str()).collect();
+        assert_eq!(hashes, ["h0", "h1", "h2", "h3", "h4"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_torn_tail_is_truncated() {
+        let dir = temp_dir("torn");
+        let path = segment_path(&dir, 0);
+
+        {
+            let (mut log, _) = SegmentLog::open(&dir, &model(), SegmentConfig::default()).unwrap();
+            log.append(&entry("h0", 1)).unwrap();
+            log.append(&entry("h1", 2)).unwrap();
+        }
+        let intact = fs::read(&path).unwrap().len() as u64;
+
+        // Simulate a crash halfway through a third record
+        let mut partial = Vec::new();
+        let payload = encode_entry(&entry("h2", 3));
+        partial.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        partial.extend_from_slice(&crc32(&payload).to_le_bytes());
+        partial.extend_from_slice(&payload[..payload.len() / 2]);
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(&partial)
+            .unwrap();
+
+        let (mut log, entries) =
+            SegmentLog::open(&dir, &model(), SegmentConfig::default()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(fs::metadata(&path).unwrap().len(), intact);
+        assert_eq!(log.truncated(), partial.len() as u64);
+
+        // Appends continue cleanly after the truncation point
+        log.append(&entry("h2", 3)).unwrap();
+        drop(log);
+        let (_, entries) = SegmentLog::open(&dir, &model(), SegmentConfig::default()).unwrap();
+        assert_eq!(entries.len(), 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_sealed_segment_is_an_error() {
+        let dir = temp_dir("sealed");
+        let config = SegmentConfig {
+            segment_bytes: 128,
+            fsync: FsyncPolicy::Always,
+        };
+
+        {
+            let (mut log, _) = SegmentLog::open(&dir, &model(), config).unwrap();
+            for i in 0..4 {
+                log.append(&entry(&format!("h{}", i), i)).unwrap();
+            }
+        }
+
+        // Flip a payload byte in the first (sealed) segment
+        let path = segment_path(&dir, 0);
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        fs::write(&path, bytes).unwrap();
+
+        let err = SegmentLog::open(&dir, &model(), config).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,12 +1,17 @@
-// In-memory ledger for Phase 1 (VLC-backed in Phase 2)
+// Vector ledger: in-memory window, optionally backed by segment files
 
+use super::segment::{ModelInfo, SegmentConfig, SegmentLog};
 use crate::types::{ConceptPacket, LedgerEntry};
 use std::collections::HashMap;
+use std::io;
+use std::path::Path;
 
-/// Simple in-memory vector ledger
+/// Append-only vector ledger
+/// In-memory by default; `open` adds a durable segment-file backend
 pub struct Ledger {
     entries: Vec<LedgerEntry>,
     index: HashMap<String, usize>, // rationale_hash → index
+    log: Option<SegmentLog>,       // None = memory only
 }
 
 impl Ledger {
@@ -14,24 +19,104 @@ impl Ledger {
         Self {
             entries: Vec::new(),
             index: HashMap::new(),
+            log: None,
         }
     }
 
+    /// Open a durable ledger in `dir` with default segment settings
+    pub fn open(dir: impl AsRef<Path>, model: ModelInfo) -> io::Result<Self> {
+        Self::open_with(dir, model, SegmentConfig::default())
+    }
+
+    /// Open a durable ledger for vectors from `model`, replaying existing segments
+    /// Torn tail records are truncated and the index is rebuilt from disk;
+    /// a ledger written by another model or dimension is an InvalidData error
+    pub fn open_with(
+        dir: impl AsRef<Path>,
+        model: ModelInfo,
+        config: SegmentConfig,
+    ) -> io::Result<Self> {
+        let (log, recovered) = SegmentLog::open(dir.as_ref(), &model, config)?;
+
+        let mut ledger = Self::new();
+        for entry in recovered {
+            ledger.push(entry);
+        }
+        ledger.log = Some(log);
+
+        Ok(ledger)
+    }
+
     /// Append a concept packet with its N-D embedding
-    pub fn append(&mut self, packet: ConceptPacket, vector: Vec<f32>) {
-        let idx = self.entries.len();
+    /// Durable ledgers write the record before it becomes visible
+    /// Vectors must match the dimension of entries already stored
+    pub fn append(&mut self, packet: ConceptPacket, vector: Vec<f32>) -> io::Result<()> {
+        if let Some(dim) = self.dim() {
+            if vector.len() != dim {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("vector has {} dims, ledger holds {}", vector.len(), dim),
+                ));
+            }
+        }
 
         let entry = LedgerEntry {
             vector,
-            rationale_hash: packet.rationale_hash.clone(),
+            rationale_hash: packet.rationale_hash,
             agent_id: packet.agent_id,
             provenance: packet.provenance,
+            phrase: packet.phrase,
             timestamp: packet.timestamp,
             tempo: packet.tempo,
-            coords_2d: None, // computed on demand
+            polarity: packet.polarity,
+            amp: packet.amp,
+            sigma: packet.sigma,
+            coords_2d: None, // filled on read from the engine's projector
         };
 
-        self.index.insert(packet.rationale_hash, idx);
+        if let Some(log) = self.log.as_mut() {
+            log.append(&entry)?;
+        }
+        self.push(entry);
+
+        Ok(())
+    }
+
+    /// Flush pending records to disk (no-op for in-memory ledgers)
+    pub fn sync(&mut self) -> io::Result<()> {
+        match self.log.as_mut() {
+            Some(log) => log.sync(),
+            None => Ok(()),
+        }
+    }
+
+    /// Vector dimension of stored entries
+    /// None while an in-memory ledger is empty; durable ledgers know it upfront
+    pub fn dim(&self) -> Option<usize> {
+        self.entries
+            .first()
+            .map(|e| e.vector.len())
+            .or_else(|| self.model().map(|m| m.dim))
+    }
+
+    /// Embedding model recorded in the segment headers (None for in-memory ledgers)
+    pub fn model(&self) -> Option<&ModelInfo> {
+        self.log.as_ref().map(|log| log.model())
+    }
+
+    /// Whether appends are persisted to segment files
+    pub fn is_durable(&self) -> bool {
+        self.log.is_some()
+    }
+
+    /// Bytes of torn tail dropped while opening (0 for in-memory ledgers)
+    pub fn truncated(&self) -> u64 {
+        self.log.as_ref().map_or(0, |log| log.truncated())
+    }
+
+    fn push(&mut self, entry: LedgerEntry) {
+        self.index
+            .insert(entry.rationale_hash.clone(), self.entries.len());
         self.entries.push(entry);
     }
 
@@ -53,7 +138,17 @@ impl Ledger {
             .collect()
     }
 
-    /// Get recent entries within time window (for clustering)
+    /// Position just past the last entry; pass to `since` to get later appends
+    pub fn watermark(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Entries appended at or after `watermark` (for incremental clustering)
+    pub fn since(&self, watermark: usize) -> &[LedgerEntry] {
+        &self.entries[watermark.min(self.entries.len())..]
+    }
+
+    /// Get recent entries within time window
     pub fn recent_window(&self, window_ms: u64, now: u64) -> Vec<&LedgerEntry> {
         self.entries
             .iter()
@@ -83,6 +178,22 @@ mod tests {
     use super::*;
     use crate::types::{Polarity, Tempo};
 
+    fn packet(hash: &str) -> ConceptPacket {
+        ConceptPacket {
+            phrase: "test phrase".to_string(),
+            amp: 0.8,
+            sigma: 1.0,
+            polarity: Polarity::Attract,
+            tempo: Tempo::Fast,
+            provenance: "test".to_string(),
+            agent_id: "agent1".to_string(),
+            rationale_hash: hash.to_string(),
+            timestamp: 1000,
+            center: None,
+            model: None,
+        }
+    }
+
     #[test]
     fn test_ledger_append_and_get() {
         let mut ledger = Ledger::new();
@@ -97,10 +208,12 @@ mod tests {
             agent_id: "agent1".to_string(),
             rationale_hash: "hash123".to_string(),
             timestamp: 1000,
+            center: None,
+            model: None,
         };
 
         let vector = vec![0.1; 768];
-        ledger.append(packet, vector.clone());
+        ledger.append(packet, vector.clone()).unwrap();
 
         let entry = ledger.get("hash123").unwrap();
         assert_eq!(entry.rationale_hash, "hash123");
@@ -124,8 +237,10 @@ mod tests {
                 agent_id: "agent1".to_string(),
                 rationale_hash: format!("hash{}", i),
                 timestamp: i * 1000, // 0, 1000, 2000, 3000, 4000
+                center: None,
+                model: None,
             };
-            ledger.append(packet, vec![0.1; 768]);
+            ledger.append(packet, vec![0.1; 768]).unwrap();
         }
 
         // Get entries within 2500ms window from time 5000
@@ -134,4 +249,67 @@ mod tests {
         let recent = ledger.recent_window(2500, 5000);
         assert_eq!(recent.len(), 2); // timestamps 3000, 4000
     }
+
+    #[test]
+    fn test_since_watermark() {
+        let mut ledger = Ledger::new();
+        ledger.append(packet("a"), vec![0.1; 4]).unwrap();
+        let mark = ledger.watermark();
+        ledger.append(packet("b"), vec![0.1; 4]).unwrap();
+        ledger.append(packet("c"), vec![0.1; 4]).unwrap();
+
+        let fresh: Vec<&str> = ledger
+            .since(mark)
+            .iter()
+            .map(|e| e.rationale_hash.as_str())
+            .collect();
+        assert_eq!(fresh, vec!["b", "c"]);
+        assert!(ledger.since(ledger.watermark()).is_empty());
+        assert!(ledger.since(99).is_empty());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_dimension() {
+        let mut ledger = Ledger::new();
+        ledger.append(packet("a"), vec![0.1; 8]).unwrap();
+        let err = ledger.append(packet("b"), vec![0.1; 4]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(ledger.len(), 1);
+        assert_eq!(ledger.dim(), Some(8));
+    }
+
+    #[test]
+    fn test_durable_ledger_recovers_index() {
+        let dir = std::env::temp_dir().join(format!("sefi_ledger_{}", uuid::Uuid::new_v4()));
+
+        {
+            let mut ledger = Ledger::open(&dir, ModelInfo::new("test-model", 4)).unwrap();
+            assert!(ledger.is_durable());
+            for i in 0..3 {
+                let packet = ConceptPacket {
+                    phrase: format!("phrase{}", i),
+                    amp: 0.5,
+                    sigma: 1.0,
+                    polarity: Polarity::Attract,
+                    tempo: Tempo::Slow,
+                    provenance: "test".to_string(),
+                    agent_id: "agent1".to_string(),
+                    rationale_hash: format!("hash{}", i),
+                    timestamp: i * 1000,
+                    center: None,
+                    model: None,
+                };
+                ledger.append(packet, vec![i as f32; 4]).unwrap();
+            }
+            ledger.sync().unwrap();
+        }
+
+        let ledger = Ledger::open(&dir, ModelInfo::new("test-model", 4)).unwrap();
+        assert_eq!(ledger.len(), 3);
+        let entry = ledger.get("hash2").unwrap();
+        assert_eq!(entry.phrase, "phrase2");
+        assert_eq!(entry.vector, vec![2.0; 4]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -11,9 +11,28 @@ pub mod ledger;
 pub mod validator;
 pub mod feedback;
 pub mod governor;
+pub mod engine;
+pub mod wire;
+mod http;
+#[cfg(unix)]
+pub mod daemon;
+#[cfg(unix)]
+pub mod api;
+
+pub use engine::{Engine, EngineError};
+
+use std::time::{SystemTime, UNIX_EPOCH};
 
 // Re-export core types
 pub use types::{
     Action, BasinFeedback, BasinType, ConceptPacket, LedgerEntry, Polarity, PreCard, SynthTier,
     Tempo, ThresholdSnapshot,
 };
+
+/// Wall clock in ms epoch
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
@@ -40,6 +40,10 @@ pub struct ConceptPacket {
     pub agent_id: String,        // stable role ID
     pub rationale_hash: String,  // hash of reasoning step
     pub timestamp: u64,          // ms epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub center: Option<Vec<f32>>, // precomputed embedding; None = embed `phrase`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,   // model that produced `center` (must match the embedder)
 }
 
 /// Basin type
@@ -103,10 +107,14 @@ pub struct BasinFeedback {
     pub nd_radius: f32,             // N-D radius covering p% of members
     pub persistence: u32,           // ticks this basin survived
     pub tempo: Tempo,               // tempo of the basin (from dominant contributors)
+    #[serde(default)]
+    pub dissent: u32,               // distinct agents with live Repel packets in reach
 
     // Optional fields
     pub centroid: Option<Vec<f32>>,         // N-D mean (diagnostic only, never semantic)
     pub endpoints: Option<Vec<String>>,     // ridge: [rep_id_A, rep_id_B]
+    #[serde(default)]
+    pub axis: Option<Vec<f32>>,             // ridge: tradeoff axis, medoid B - medoid A
     pub decompose_into: Option<Vec<String>>, // peak: suggested split medoids
     pub recommended_action: Action,         // what to do with this basin
 
@@ -118,13 +126,17 @@ pub struct BasinFeedback {
 }
 
 /// Ledger entry for N-D vector storage
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LedgerEntry {
     pub vector: Vec<f32>,           // 768d embedding
     pub rationale_hash: String,     // unique ID
     pub agent_id: String,
     pub provenance: String,         // context pointer
+    pub phrase: String,             // anchor text of the source packet
     pub timestamp: u64,
     pub tempo: Tempo,               // for decay logic
-    pub coords_2d: Option<[f32; 2]>, // from projection (computed on demand)
+    pub polarity: Polarity,         // Repel = negative evidence
+    pub amp: f32,                   // source confidence, scales mass
+    pub sigma: f32,                 // kernel width, in units of epsilon
+    pub coords_2d: Option<[f32; 2]>, // from projection, filled on read
 }
@@ -0,0 +1,113 @@
+// Silhouette cohesion and coverage radius in N-D
+
+use crate::ledger::hnsw::cosine_distance;
+use crate::types::LedgerEntry;
+
+/// Per-member silhouette s = (b - a) / max(a, b), in [-1, 1]
+///   a = mean distance to the other members of the basin
+///   b = mean distance to the members of the nearest other basin
+/// With no other basin, b is taken as 1.0 (an orthogonal background)
+/// A basin's lone member scores 0, as is conventional
+pub fn silhouettes(members: &[&LedgerEntry], others: &[Vec<&LedgerEntry>]) -> Vec<f32> {
+    if members.len() < 2 {
+        return vec![0.0; members.len()];
+    }
+
+    members
+        .iter()
+        .enumerate()
+        .map(|(i, member)| {
+            let a = members
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, other)| cosine_distance(&member.vector, &other.vector))
+                .sum::<f32>()
+                / (members.len() - 1) as f32;
+            let b = others
+                .iter()
+                .filter(|basin| !basin.is_empty())
+                .map(|basin| mean_distance(member, basin))
+                .fold(None, |best: Option<f32>, d| {
+                    Some(best.map_or(d, |b| b.min(d)))
+                })
+                .unwrap_or(1.0);
+
+            let scale = a.max(b);
+            if scale > 0.0 {
+                (b - a) / scale
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Smallest distance from `center` that covers `coverage` of the members
+/// (coverage in (0, 1]; 1.0 gives the furthest member)
+pub fn coverage_radius(members: &[&LedgerEntry], center: &[f32], coverage: f32) -> f32 {
+    if members.is_empty() {
+        return 0.0;
+    }
+    let mut distances: Vec<f32> = members
+        .iter()
+        .map(|e| cosine_distance(&e.vector, center).max(0.0))
+        .collect();
+    distances.sort_by(f32::total_cmp);
+
+    let covered = (coverage.clamp(0.0, 1.0) * members.len() as f32).ceil() as usize;
+    distances[covered.clamp(1, members.len()) - 1]
+}
+
+fn mean_distance(entry: &LedgerEntry, basin: &[&LedgerEntry]) -> f32 {
+    basin
+        .iter()
+        .map(|other| cosine_distance(&entry.vector, &other.vector))
+        .sum::<f32>()
+        / basin.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::tests::entry_at;
+
+    fn at(degrees: &[f32]) -> Vec<LedgerEntry> {
+        degrees
+            .iter()
+            .map(|&d| entry_at(&format!("d{}", d), d))
+            .collect()
+    }
+
+    #[test]
+    fn test_silhouette_separated_vs_overlapping() {
+        let tight = at(&[0.0, 2.0, 4.0]);
+        let far = at(&[90.0, 92.0]);
+        let near = at(&[3.0, 5.0]);
+        let members: Vec<&LedgerEntry> = tight.iter().collect();
+
+        let s = silhouettes(&members, &[far.iter().collect()]);
+        assert!(s.iter().all(|&x| x > 0.9), "well separated: {:?}", s);
+
+        // The nearest basin wins, even when a far one is also present
+        let s = silhouettes(&members, &[far.iter().collect(), near.iter().collect()]);
+        assert!(s.iter().any(|&x| x < 0.0), "overlapping: {:?}", s);
+        assert!(s.iter().all(|&x| (-1.0..=1.0).contains(&x)));
+
+        assert_eq!(silhouettes(&members[..1], &[]), vec![0.0]);
+    }
+
+    #[test]
+    fn test_coverage_radius() {
+        let entries = at(&[0.0, 10.0, 20.0, 30.0, 90.0]);
+        let members: Vec<&LedgerEntry> = entries.iter().collect();
+        let center = &members[0].vector;
+
+        // 80% of five members = four: the 90° outlier is left out
+        let r80 = coverage_radius(&members, center, 0.8);
+        assert!((r80 - (1.0 - 30f32.to_radians().cos())).abs() < 1e-5);
+        let r100 = coverage_radius(&members, center, 1.0);
+        assert!((r100 - 1.0).abs() < 1e-5);
+        assert_eq!(coverage_radius(&[], center, 0.9), 0.0);
+    }
+}
@@ -0,0 +1,53 @@
+// Medoid computation: the real member nearest the Fréchet mean
+
+use crate::embed::normalize_vector;
+use crate::ledger::hnsw::cosine_distance;
+use crate::types::LedgerEntry;
+
+/// Fréchet mean on the unit sphere, approximated by the normalized mean
+/// (exact for the chordal metric, close for tight clusters)
+pub fn frechet_mean(entries: &[&LedgerEntry]) -> Vec<f32> {
+    let Some(first) = entries.first() else {
+        return Vec::new();
+    };
+    let mut mean = vec![0.0; first.vector.len()];
+    for entry in entries {
+        for (m, x) in mean.iter_mut().zip(&entry.vector) {
+            *m += x;
+        }
+    }
+    normalize_vector(&mut mean);
+    mean
+}
+
+/// Index of the entry closest to `point` (ties go to the earliest)
+pub fn nearest(entries: &[&LedgerEntry], point: &[f32]) -> Option<usize> {
+    entries
+        .iter()
+        .map(|e| cosine_distance(&e.vector, point))
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+        .map(|(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::tests::entry_at;
+
+    #[test]
+    fn test_medoid_is_nearest_to_mean() {
+        // Mean of 0°, 10°, 20° and 60° is ~22°: 20° is the real member there
+        let entries: Vec<LedgerEntry> = [0.0, 10.0, 20.0, 60.0]
+            .iter()
+            .map(|&d| entry_at(&format!("d{}", d), d))
+            .collect();
+        let members: Vec<&LedgerEntry> = entries.iter().collect();
+
+        let mean = frechet_mean(&members);
+        let norm: f32 = mean.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+        assert_eq!(nearest(&members, &mean), Some(2));
+        assert_eq!(nearest(&[], &mean), None);
+    }
+}
@@ -1,3 +1,123 @@
 // Basin validator (medoid + cohesion computation)
+//
+// Turns a cluster's member list into the numbers a BasinFeedback reports:
+// the medoid (real member nearest the Fréchet mean), the mean silhouette
+// against the nearest other basin, and the radius covering most members.
 
-// TODO M1.3: Implement medoid and cohesion calculation
+pub mod cohesion;
+pub mod medoid;
+
+use crate::ledger::store::Ledger;
+use crate::types::LedgerEntry;
+
+/// Share of members `nd_radius` must cover (outliers beyond it are ignored)
+pub const RADIUS_COVERAGE: f32 = 0.9;
+
+/// Medoid, cohesion and extent of one basin
+#[derive(Debug, Clone)]
+pub struct ValidatedBasin {
+    pub members: Vec<String>, // rationale_hashes found in the ledger
+    pub medoid: String,       // rationale_hash nearest the Fréchet mean
+    pub medoid_phrase: String,
+    pub frechet_mean: Vec<f32>, // diagnostic only, never semantic
+    pub silhouettes: Vec<f32>,  // per member, same order as `members`
+    pub cohesion: f32,          // mean silhouette in [-1, 1]
+    pub radius: f32,            // cosine distance covering RADIUS_COVERAGE of members
+}
+
+/// Validate a basin against the other live basins
+/// Hashes missing from the ledger are skipped; None if none are left
+pub fn validate_basin(
+    members: &[String],
+    ledger: &Ledger,
+    other_clusters: &[Vec<String>],
+) -> Option<ValidatedBasin> {
+    let entries = ledger.get_batch(members);
+    let others: Vec<Vec<&LedgerEntry>> = other_clusters
+        .iter()
+        .map(|hashes| ledger.get_batch(hashes))
+        .collect();
+
+    let frechet_mean = medoid::frechet_mean(&entries);
+    let medoid = entries[medoid::nearest(&entries, &frechet_mean)?];
+    let silhouettes = cohesion::silhouettes(&entries, &others);
+    let cohesion = silhouettes.iter().sum::<f32>() / silhouettes.len() as f32;
+
+    Some(ValidatedBasin {
+        members: entries.iter().map(|e| e.rationale_hash.clone()).collect(),
+        medoid: medoid.rationale_hash.clone(),
+        medoid_phrase: medoid.phrase.clone(),
+        radius: cohesion::coverage_radius(&entries, &medoid.vector, RADIUS_COVERAGE),
+        frechet_mean,
+        silhouettes,
+        cohesion,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::types::{ConceptPacket, Polarity, Tempo};
+
+    /// Slow, attracting entry with a 2-d unit vector at `degrees`
+    pub(crate) fn entry_at(hash: &str, degrees: f32) -> LedgerEntry {
+        let rad = degrees.to_radians();
+        LedgerEntry {
+            vector: vec![rad.cos(), rad.sin()],
+            rationale_hash: hash.to_string(),
+            agent_id: "agent1".to_string(),
+            provenance: "test".to_string(),
+            phrase: hash.to_string(),
+            timestamp: 1000,
+            tempo: Tempo::Slow,
+            polarity: Polarity::Attract,
+            amp: 0.8,
+            sigma: 1.0,
+            coords_2d: None,
+        }
+    }
+
+    fn ledger_at(points: &[(&str, f32)]) -> Ledger {
+        let mut ledger = Ledger::new();
+        for &(hash, degrees) in points {
+            let entry = entry_at(hash, degrees);
+            let packet = ConceptPacket {
+                phrase: entry.phrase,
+                amp: entry.amp,
+                sigma: entry.sigma,
+                polarity: entry.polarity,
+                tempo: entry.tempo,
+                provenance: entry.provenance,
+                agent_id: entry.agent_id,
+                rationale_hash: entry.rationale_hash,
+                timestamp: entry.timestamp,
+                center: None,
+                model: None,
+            };
+            ledger.append(packet, entry.vector).unwrap();
+        }
+        ledger
+    }
+
+    #[test]
+    fn test_validate_basin() {
+        let ledger = ledger_at(&[
+            ("a0", 0.0),
+            ("a1", 4.0),
+            ("a2", 8.0),
+            ("b0", 80.0),
+            ("b1", 84.0),
+        ]);
+        let a: Vec<String> = ["a0", "a1", "a2", "missing"].map(String::from).to_vec();
+        let b: Vec<String> = ["b0", "b1"].map(String::from).to_vec();
+
+        let basin = validate_basin(&a, &ledger, &[b]).unwrap();
+        assert_eq!(basin.members, vec!["a0", "a1", "a2"]);
+        assert_eq!(basin.medoid, "a1");
+        assert_eq!(basin.silhouettes.len(), 3);
+        assert!(basin.cohesion > 0.9 && basin.cohesion <= 1.0);
+        assert!(basin.radius > 0.0 && basin.radius <= 1.0 - 4f32.to_radians().cos() + 1e-6);
+
+        assert!(validate_basin(&["missing".to_string()], &ledger, &[]).is_none());
+    }
+}
@@ -0,0 +1,290 @@
+// Text dashboard for `sefi top` (plain terminal cells, no curses)
+//
+// Turns one TopReport into exactly `height` lines of exactly `width`
+// characters: header, ingest sparkline, basin table beside a braille
+// mini-map of the projection, and the feed of emitted feedback. The binary
+// owns the terminal (raw mode, alternate screen); keeping this module pure
+// lets it be tested without a tty.
+
+use crate::daemon::{FeedLine, TopReport};
+
+/// Eight bar heights, lowest first
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Braille dot bits by (column, row) inside a 2×4 cell
+const DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// Widest mini-map in columns
+const MAP_WIDTH: usize = 40;
+
+/// Lay out a full screen; sizes below 20×8 are clamped up
+pub fn render(report: &TopReport, width: usize, height: usize) -> Vec<String> {
+    let (width, height) = (width.max(20), height.max(8));
+    let status = &report.status;
+    let recent = report.ingest.iter().rev().take(10).sum::<u32>() as f32 / 10.0;
+    let storage = if status.durable { "durable" } else { "memory" };
+
+    let mut lines = vec![fit(
+        &format!(
+            " sefi top  {}  {} entries ({})  {} basins  {:.1}/s",
+            clock(report.now),
+            status.ledger_len,
+            storage,
+            status.basins.len(),
+            recent
+        ),
+        width,
+    )];
+
+    let last = report.ingest.last().copied().unwrap_or(0);
+    let peak = report.ingest.iter().copied().max().unwrap_or(0);
+    let (label, tail) = (" ingest/s ", format!("  now {} peak {}", last, peak));
+    let spark = width.saturating_sub(label.chars().count() + tail.chars().count());
+    lines.push(fit(
+        &format!("{}{}{}", label, sparkline(&report.ingest, spark), tail),
+        width,
+    ));
+    lines.push("─".repeat(width));
+
+    // Body: basin table (left) and mini-map (right), feed below
+    let feed_rows = ((height - 6) / 3).clamp(1, 8);
+    let body_rows = height - 6 - feed_rows;
+    let map_cols = if width >= 60 {
+        (width / 3).min(MAP_WIDTH)
+    } else {
+        0
+    };
+    let table_cols = width - map_cols - usize::from(map_cols > 0);
+
+    let mut table = vec![String::from("  #   MASS  PERS   COH  AGT  TEMPO   MEDOID")];
+    table.extend(status.basins.iter().enumerate().map(|(i, b)| {
+        format!(
+            "{:>3} {:>6.2} {:>5} {:>5.2} {:>4}  {:<7} {}",
+            i + 1,
+            b.mass,
+            b.persistence,
+            b.cohesion,
+            b.agents,
+            format!("{:?}", b.tempo),
+            b.rep_phrase
+        )
+    }));
+    if status.basins.is_empty() {
+        table.push("    (no live basins)".to_string());
+    }
+
+    let marks: Vec<(char, [f32; 2])> = status
+        .basins
+        .iter()
+        .take(9)
+        .enumerate()
+        .filter_map(|(i, b)| Some((char::from(b'1' + i as u8), b.coords_2d?)))
+        .collect();
+    let map = braille_map(&report.map, &marks, map_cols, body_rows);
+
+    for (row, map) in map.iter().enumerate() {
+        let left = fit(table.get(row).map_or("", |s| s.as_str()), table_cols);
+        if map_cols > 0 {
+            lines.push(format!("{}│{}", left, map));
+        } else {
+            lines.push(left);
+        }
+    }
+
+    lines.push("─".repeat(width));
+    lines.push(fit(" feed", width));
+    let skip = report.feed.len().saturating_sub(feed_rows);
+    for row in 0..feed_rows {
+        let line = report
+            .feed
+            .get(skip + row)
+            .map(feed_line)
+            .unwrap_or_default();
+        lines.push(fit(&line, width));
+    }
+    lines.push(fit(" q quit", width));
+    lines
+}
+
+/// Bars for the last `width` values, scaled to the largest of them
+pub fn sparkline(values: &[u32], width: usize) -> String {
+    let shown = &values[values.len().saturating_sub(width)..];
+    let max = shown.iter().copied().max().unwrap_or(0);
+    let bars: String = shown
+        .iter()
+        .map(|&v| match v {
+            0 => ' ',
+            _ => BARS[((v as usize * BARS.len()).div_ceil(max as usize) - 1).min(7)],
+        })
+        .collect();
+    format!("{:>width$}", bars, width = width)
+}
+
+/// `cols` × `rows` braille cells plotting `points`; `marks` overwrite the
+/// cell they fall in. Both share one bounding box, y pointing up
+pub fn braille_map(
+    points: &[[f32; 2]],
+    marks: &[(char, [f32; 2])],
+    cols: usize,
+    rows: usize,
+) -> Vec<String> {
+    let mut cells = vec![vec![0u32; cols]; rows];
+    let mut labels: Vec<Vec<Option<char>>> = vec![vec![None; cols]; rows];
+    if cols == 0 || rows == 0 {
+        return vec![String::new(); rows];
+    }
+
+    let all = points.iter().chain(marks.iter().map(|(_, xy)| xy));
+    let (mut lo, mut hi) = ([f32::MAX; 2], [f32::MIN; 2]);
+    for xy in all {
+        for k in 0..2 {
+            lo[k] = lo[k].min(xy[k]);
+            hi[k] = hi[k].max(xy[k]);
+        }
+    }
+    let (dots_x, dots_y) = (cols * 2, rows * 4);
+    let dot = |xy: &[f32; 2]| {
+        let scale = |v: f32, k: usize, n: usize| {
+            let span = hi[k] - lo[k];
+            let t = if span > 0.0 { (v - lo[k]) / span } else { 0.5 };
+            ((t * (n - 1) as f32).round() as usize).min(n - 1)
+        };
+        (
+            scale(xy[0], 0, dots_x),
+            dots_y - 1 - scale(xy[1], 1, dots_y),
+        )
+    };
+
+    for xy in points {
+        let (x, y) = dot(xy);
+        cells[y / 4][x / 2] |= DOTS[x % 2][y % 4];
+    }
+    for (label, xy) in marks {
+        let (x, y) = dot(xy);
+        labels[y / 4][x / 2] = Some(*label);
+    }
+
+    cells
+        .iter()
+        .zip(&labels)
+        .map(|(row, marks)| {
+            row.iter()
+                .zip(marks)
+                .map(|(&bits, mark)| {
+                    mark.unwrap_or_else(|| {
+                        if bits == 0 {
+                            ' '
+                        } else {
+                            char::from_u32(0x2800 + bits).unwrap_or(' ')
+                        }
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn feed_line(line: &FeedLine) -> String {
+    format!(
+        " {}  {:<6} {:<7} coh {:>5.2}  {:>3} contrib  \"{}\"",
+        clock(line.timestamp),
+        format!("{:?}", line.type_),
+        format!("{:?}", line.tempo),
+        line.cohesion,
+        line.contributors,
+        line.rep_phrase
+    )
+}
+
+/// UTC wall clock hh:mm:ss of an epoch-ms timestamp
+fn clock(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Truncate or pad with spaces to exactly `width` characters
+fn fit(text: &str, width: usize) -> String {
+    let mut out: String = text.chars().take(width).collect();
+    let len = out.chars().count();
+    out.extend(std::iter::repeat_n(' ', width - len));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::{BasinSummary, StatusReport, INGEST_WINDOW};
+    use crate::types::{BasinType, Tempo};
+
+    fn report() -> TopReport {
+        let basin = BasinSummary {
+            basin_id: "c1".to_string(),
+            rep_phrase: "memory safety".to_string(),
+            members: 3,
+            persistence: 4,
+            mass: 2.4,
+            dissent: 0,
+            tempo: Tempo::Slow,
+            cohesion: 0.8,
+            agents: 2,
+            coords_2d: Some([1.0, 1.0]),
+        };
+        let mut ingest = vec![0; INGEST_WINDOW];
+        ingest[INGEST_WINDOW - 1] = 4;
+        TopReport {
+            status: StatusReport {
+                ledger_len: 3,
+                durable: false,
+                basins: vec![basin],
+            },
+            now: 3_723_000, // 01:02:03
+            ingest,
+            map: vec![[0.0, 0.0], [1.0, 1.0], [-1.0, 0.5]],
+            feed: vec![FeedLine {
+                timestamp: 3_723_000,
+                basin_id: "c1".to_string(),
+                type_: BasinType::Valley,
+                rep_phrase: "memory safety".to_string(),
+                cohesion: 0.8,
+                contributors: 3,
+                tempo: Tempo::Slow,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_render_fills_the_screen() {
+        for (width, height) in [(100, 30), (50, 12), (5, 2)] {
+            let lines = render(&report(), width, height);
+            assert_eq!(lines.len(), height.max(8));
+            assert!(lines.iter().all(|l| l.chars().count() == width.max(20)));
+        }
+
+        let lines = render(&report(), 100, 30);
+        assert!(lines[0].contains("01:02:03"));
+        assert!(lines[3].contains("MEDOID") && lines[3].contains('│'));
+        assert!(lines[4].contains("memory safety") && lines[4].contains("0.80"));
+        assert!(lines
+            .iter()
+            .any(|l| l.contains("Valley") && l.contains("3 contrib")));
+    }
+
+    #[test]
+    fn test_sparkline_and_braille() {
+        assert_eq!(sparkline(&[0, 1, 2, 4], 4), " ▂▄█");
+        assert_eq!(sparkline(&[8, 8, 0], 2), "█ ");
+        assert_eq!(sparkline(&[3], 3), "  █");
+
+        // Opposite corners of a 2×1 map, and a mark on top of a point
+        let map = braille_map(&[[0.0, 0.0], [1.0, 1.0]], &[], 2, 1);
+        assert_eq!(map, vec!["\u{2840}\u{2808}".to_string()]);
+        let map = braille_map(&[[0.0, 0.0], [1.0, 1.0]], &[('1', [1.0, 1.0])], 2, 1);
+        assert_eq!(map, vec!["\u{2840}1".to_string()]);
+        assert_eq!(braille_map(&[], &[], 0, 3).len(), 3);
+    }
+}
@@ -0,0 +1,132 @@
+// Numbered PNG frame sequences and their APNG animation
+
+use super::png::{self, Image};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File name of the assembled animation inside a frame directory
+pub const ANIMATION: &str = "field.apng";
+
+/// Writes frame_00000000.png, frame_00000001.png, ... into one directory
+pub struct FrameWriter {
+    dir: PathBuf,
+    next: u64,
+}
+
+impl FrameWriter {
+    /// Create `dir` if needed; numbering continues after the highest
+    /// existing frame, so gaps left by deleted frames are never reused
+    pub fn create(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let next = numbered(&dir)?.last().map_or(0, |(i, _)| i + 1);
+        Ok(Self { dir, next })
+    }
+
+    pub fn write(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let path = self.dir.join(format!("frame_{:08}.png", self.next));
+        fs::write(&path, image.to_png())?;
+        self.next += 1;
+        Ok(path)
+    }
+
+    /// Number the next frame will get
+    pub fn next_index(&self) -> u64 {
+        self.next
+    }
+}
+
+/// Frame files in `dir`, in frame order
+pub fn frames(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    Ok(numbered(dir)?.into_iter().map(|(_, p)| p).collect())
+}
+
+/// (index, path) of every frame_N.png in `dir`, by index (any zero padding)
+fn numbered(dir: &Path) -> io::Result<Vec<(u64, PathBuf)>> {
+    let mut found: Vec<(u64, PathBuf)> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter_map(|p| {
+            let name = p.file_name()?.to_str()?;
+            let digits = name.strip_prefix("frame_")?.strip_suffix(".png")?;
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            Some((digits.parse().ok()?, p))
+        })
+        .collect();
+    found.sort();
+    Ok(found)
+}
+
+/// Join every frame in `dir` into `dir/field.apng` (loops forever)
+pub fn animate(dir: &Path, delay_ms: u16) -> io::Result<PathBuf> {
+    let frames = frames(dir)?
+        .iter()
+        .map(fs::read)
+        .collect::<io::Result<Vec<Vec<u8>>>>()?;
+    let path = dir.join(ANIMATION);
+    fs::write(&path, png::assemble_apng(&frames, delay_ms, 0)?)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_number_resume_and_animate() {
+        let dir = std::env::temp_dir().join(format!("sefi_frames_{}", uuid::Uuid::new_v4()));
+        let mut writer = FrameWriter::create(&dir).unwrap();
+        writer.write(&Image::new(8, 8, [0, 0, 0])).unwrap();
+        writer.write(&Image::new(8, 8, [9, 9, 9])).unwrap();
+
+        let mut writer = FrameWriter::create(&dir).unwrap();
+        let third = writer.write(&Image::new(8, 8, [90, 9, 9])).unwrap();
+        assert!(third.ends_with("frame_00000002.png"));
+        assert_eq!(writer.next_index(), 3);
+
+        let apng = fs::read(animate(&dir, 100).unwrap()).unwrap();
+        let fdat = png::chunks(&apng)
+            .unwrap()
+            .iter()
+            .filter(|(kind, _)| kind == b"fdAT")
+            .count();
+        assert_eq!(fdat, 2);
+        assert_eq!(frames(&dir).unwrap().len(), 3); // the animation is not a frame
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_numbering_follows_the_highest_frame() {
+        let dir = std::env::temp_dir().join(format!("sefi_frames_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        // A gap, a narrower legacy name and a stray file
+        let png = Image::new(2, 2, [0, 0, 0]).to_png();
+        fs::write(dir.join("frame_00003.png"), &png).unwrap();
+        fs::write(dir.join("frame_00000010.png"), &png).unwrap();
+        fs::write(dir.join("frame_notes.png"), b"").unwrap();
+
+        let mut writer = FrameWriter::create(&dir).unwrap();
+        assert_eq!(writer.next_index(), 11);
+        let next = writer.write(&Image::new(2, 2, [0, 0, 0])).unwrap();
+        assert!(next.ends_with("frame_00000011.png"));
+
+        let order: Vec<String> = frames(&dir)
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                "frame_00003.png",
+                "frame_00000010.png",
+                "frame_00000011.png"
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
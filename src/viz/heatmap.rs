@@ -0,0 +1,436 @@
+// Headless CPU heatmap of the projected field
+//
+// Live ledger entries are splatted as Gaussian kernels (weighted by decayed
+// amplitude) onto a density grid, coloured with an inferno-like ramp. Each
+// basin gets an outline where its own members' density falls to `contour`
+// of its peak, plus a medoid marker and label in a built-in 5×7 font.
+// Display only: nothing here feeds back into clustering.
+
+use super::png::Image;
+use crate::clustering::decay_weight;
+use crate::embed::Embedder;
+use crate::engine::Engine;
+use crate::types::BasinType;
+
+/// Frame size and drawing parameters
+#[derive(Debug, Clone)]
+pub struct HeatmapConfig {
+    pub width: u32,
+    pub height: u32,
+    pub bandwidth: f32, // kernel sigma in pixels
+    pub margin: f32,    // fraction of each side kept clear of data
+    pub contour: f32,   // outline at this fraction of a basin's peak density
+    pub labels: bool,   // draw medoid phrases and the caption
+}
+
+impl Default for HeatmapConfig {
+    fn default() -> Self {
+        Self {
+            width: 512,
+            height: 512,
+            bandwidth: 8.0,
+            margin: 0.08,
+            contour: 0.35,
+            labels: true,
+        }
+    }
+}
+
+/// One projected entry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldPoint {
+    pub xy: [f32; 2],
+    pub weight: f32, // amp × decay
+}
+
+/// One basin (cluster or ridge) to outline and label
+#[derive(Debug, Clone)]
+pub struct BasinMark {
+    pub id: String,
+    pub type_: BasinType,
+    pub label: String, // medoid phrase
+    pub medoid: [f32; 2],
+    pub members: Vec<FieldPoint>,
+}
+
+/// Everything a frame shows, already in projection coordinates
+#[derive(Debug, Clone, Default)]
+pub struct Scene {
+    pub points: Vec<FieldPoint>,
+    pub basins: Vec<BasinMark>,
+}
+
+impl Scene {
+    /// Live attracting entries, clusters and mature ridges of `engine`
+    /// Empty until the engine's projector has fitted a basis
+    pub fn capture<E: Embedder>(engine: &Engine<E>, now: u64) -> Self {
+        let Some(projection) = engine.projector().projection() else {
+            return Self::default();
+        };
+        let ledger = engine.ledger();
+        let point = |hash: &str| {
+            ledger.get(hash).map(|e| FieldPoint {
+                xy: projection.project(&e.vector),
+                weight: e.amp * decay_weight(e, now),
+            })
+        };
+
+        let points = engine
+            .live_entries()
+            .map(|e| FieldPoint {
+                xy: projection.project(&e.vector),
+                weight: e.amp * decay_weight(e, now),
+            })
+            .filter(|p| p.weight > 0.0)
+            .collect();
+
+        let mut basins: Vec<BasinMark> = engine
+            .clusters()
+            .clusters()
+            .map(|c| BasinMark {
+                id: c.id.clone(),
+                type_: c.basin_type(),
+                label: c.medoid_phrase.clone(),
+                medoid: point(&c.medoid_hash).map_or([0.0, 0.0], |p| p.xy),
+                members: c.members.iter().filter_map(|h| point(h)).collect(),
+            })
+            .collect();
+        basins.extend(
+            engine
+                .clusters()
+                .ridges()
+                .mature()
+                .into_iter()
+                .map(|r| BasinMark {
+                    id: r.id.clone(),
+                    type_: BasinType::Ridge,
+                    label: r.rep_phrase.clone(),
+                    medoid: point(&r.rep_hash).map_or([0.0, 0.0], |p| p.xy),
+                    members: r.boundary.iter().filter_map(|h| point(h)).collect(),
+                }),
+        );
+        basins.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Self { points, basins }
+    }
+}
+
+/// Rasterize `scene` (empty scenes give a background-only frame)
+pub fn render(scene: &Scene, config: &HeatmapConfig) -> Image {
+    let (w, h) = (config.width.max(1), config.height.max(1));
+    let mut image = Image::new(w, h, ramp(0.0));
+    let Some(view) = Viewport::fit(scene, config) else {
+        if config.labels {
+            draw_text(&mut image, 6, 6, "NO DATA", [200, 200, 200]);
+        }
+        return image;
+    };
+
+    let field = density(&scene.points, &view, config.bandwidth);
+    let peak = field.iter().copied().fold(0.0f32, f32::max);
+    if peak > 0.0 {
+        for (i, &d) in field.iter().enumerate() {
+            let rgb = ramp((d / peak).sqrt()); // sqrt lifts the faint tails
+            image.pixels[i * 3..i * 3 + 3].copy_from_slice(&rgb);
+        }
+    }
+
+    for basin in &scene.basins {
+        let colour = outline_colour(basin.type_);
+        let own = density(&basin.members, &view, config.bandwidth);
+        let level = own.iter().copied().fold(0.0f32, f32::max) * config.contour;
+        if level > 0.0 {
+            for y in 0..h as i32 {
+                for x in 0..w as i32 {
+                    if on_contour(&own, w as i32, h as i32, x, y, level) {
+                        image.put(x, y, colour);
+                    }
+                }
+            }
+        }
+    }
+
+    for basin in &scene.basins {
+        let [x, y] = view.to_pixel(basin.medoid);
+        let (x, y) = (x.round() as i32, y.round() as i32);
+        for d in -3..=3 {
+            image.put(x + d, y, [255, 255, 255]);
+            image.put(x, y + d, [255, 255, 255]);
+        }
+        if config.labels {
+            let label: String = basin.label.chars().take(24).collect();
+            draw_text(&mut image, x + 6, y - 3, &label, [255, 255, 255]);
+        }
+    }
+
+    if config.labels {
+        let caption = format!(
+            "{} POINTS  {} BASINS",
+            scene.points.len(),
+            scene.basins.len()
+        );
+        draw_text(&mut image, 6, 6, &caption, [200, 200, 200]);
+    }
+    image
+}
+
+/// Maps projection coordinates to pixels (uniform scale, y up)
+struct Viewport {
+    width: u32,
+    height: u32,
+    center: [f32; 2],
+    scale: f32, // pixels per projection unit
+}
+
+impl Viewport {
+    fn fit(scene: &Scene, config: &HeatmapConfig) -> Option<Self> {
+        let coords = scene
+            .points
+            .iter()
+            .map(|p| p.xy)
+            .chain(scene.basins.iter().map(|b| b.medoid));
+        let mut lo = [f32::MAX; 2];
+        let mut hi = [f32::MIN; 2];
+        let mut any = false;
+        for [x, y] in coords.filter(|c| c[0].is_finite() && c[1].is_finite()) {
+            lo = [lo[0].min(x), lo[1].min(y)];
+            hi = [hi[0].max(x), hi[1].max(y)];
+            any = true;
+        }
+        if !any {
+            return None;
+        }
+
+        let (w, h) = (config.width.max(1), config.height.max(1));
+        let usable = (1.0 - 2.0 * config.margin).clamp(0.1, 1.0);
+        let span_x = (hi[0] - lo[0]).max(1e-6);
+        let span_y = (hi[1] - lo[1]).max(1e-6);
+        let scale = (w as f32 * usable / span_x).min(h as f32 * usable / span_y);
+        let scale = if span_x <= 1e-6 && span_y <= 1e-6 {
+            1.0 // a single location: any scale centres it
+        } else {
+            scale
+        };
+
+        Some(Self {
+            width: w,
+            height: h,
+            center: [(lo[0] + hi[0]) / 2.0, (lo[1] + hi[1]) / 2.0],
+            scale,
+        })
+    }
+
+    fn to_pixel(&self, xy: [f32; 2]) -> [f32; 2] {
+        [
+            self.width as f32 / 2.0 + (xy[0] - self.center[0]) * self.scale,
+            self.height as f32 / 2.0 - (xy[1] - self.center[1]) * self.scale,
+        ]
+    }
+}
+
+/// Weighted Gaussian splats on a width × height grid
+fn density(points: &[FieldPoint], view: &Viewport, sigma: f32) -> Vec<f32> {
+    let (w, h) = (view.width as i32, view.height as i32);
+    let mut grid = vec![0.0f32; (w * h) as usize];
+    let sigma = sigma.max(0.5);
+    let radius = (3.0 * sigma).ceil() as i32;
+    let inv = 1.0 / (2.0 * sigma * sigma);
+
+    for p in points {
+        let [px, py] = view.to_pixel(p.xy);
+        let (cx, cy) = (px.round() as i32, py.round() as i32);
+        for y in (cy - radius).max(0)..=(cy + radius).min(h - 1) {
+            for x in (cx - radius).max(0)..=(cx + radius).min(w - 1) {
+                let (dx, dy) = (x as f32 - px, y as f32 - py);
+                grid[(y * w + x) as usize] += p.weight * (-(dx * dx + dy * dy) * inv).exp();
+            }
+        }
+    }
+    grid
+}
+
+/// Inside the level set with a 4-neighbour outside it (or on the frame edge)
+fn on_contour(grid: &[f32], w: i32, h: i32, x: i32, y: i32, level: f32) -> bool {
+    let at = |x: i32, y: i32| {
+        if x < 0 || y < 0 || x >= w || y >= h {
+            0.0
+        } else {
+            grid[(y * w + x) as usize]
+        }
+    };
+    at(x, y) >= level
+        && [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .iter()
+            .any(|(dx, dy)| at(x + dx, y + dy) < level)
+}
+
+fn outline_colour(type_: BasinType) -> [u8; 3] {
+    match type_ {
+        BasinType::Valley => [0, 255, 200],
+        BasinType::Peak => [255, 165, 0],
+        BasinType::Ridge => [255, 0, 255],
+    }
+}
+
+/// Inferno-like ramp over t in [0, 1]
+fn ramp(t: f32) -> [u8; 3] {
+    const STOPS: [[f32; 3]; 5] = [
+        [0.0, 0.0, 4.0],
+        [87.0, 16.0, 110.0],
+        [188.0, 55.0, 84.0],
+        [249.0, 142.0, 9.0],
+        [252.0, 255.0, 164.0],
+    ];
+    let t = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let i = (t.floor() as usize).min(STOPS.len() - 2);
+    let f = t - i as f32;
+    [0, 1, 2].map(|c| (STOPS[i][c] + (STOPS[i + 1][c] - STOPS[i][c]) * f).round() as u8)
+}
+
+/// 5×7 text, upper-cased; unknown characters draw as '?'
+fn draw_text(image: &mut Image, x: i32, y: i32, text: &str, rgb: [u8; 3]) {
+    for (n, ch) in text.chars().enumerate() {
+        let rows = glyph(ch.to_ascii_uppercase());
+        let left = x + n as i32 * 6;
+        for (dy, bits) in rows.iter().enumerate() {
+            for dx in 0..5 {
+                if bits & (0x10 >> dx) != 0 {
+                    image.put(left + dx + 1, y + dy as i32 + 1, [0, 0, 0]); // shadow
+                    image.put(left + dx, y + dy as i32, rgb);
+                }
+            }
+        }
+    }
+}
+
+fn glyph(ch: char) -> [u8; 7] {
+    FONT.iter()
+        .find(|(c, _)| *c == ch)
+        .or_else(|| FONT.iter().find(|(c, _)| *c == '?'))
+        .map(|(_, rows)| *rows)
+        .unwrap()
+}
+
+/// Rows top to bottom, bit 4 = leftmost column
+const FONT: [(char, [u8; 7]); 48] = [
+    (' ', [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]),
+    ('A', [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11]),
+    ('B', [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E]),
+    ('C', [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E]),
+    ('D', [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E]),
+    ('E', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F]),
+    ('F', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10]),
+    ('G', [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F]),
+    ('H', [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11]),
+    ('I', [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('J', [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C]),
+    ('K', [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11]),
+    ('L', [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F]),
+    ('M', [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11]),
+    ('N', [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11]),
+    ('O', [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E]),
+    ('P', [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10]),
+    ('Q', [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D]),
+    ('R', [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11]),
+    ('S', [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E]),
+    ('T', [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04]),
+    ('U', [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E]),
+    ('V', [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04]),
+    ('W', [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A]),
+    ('X', [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11]),
+    ('Y', [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04]),
+    ('Z', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F]),
+    ('0', [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E]),
+    ('1', [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('2', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F]),
+    ('3', [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E]),
+    ('4', [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02]),
+    ('5', [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E]),
+    ('6', [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E]),
+    ('7', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08]),
+    ('8', [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E]),
+    ('9', [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C]),
+    ('-', [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00]),
+    ('_', [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F]),
+    ('.', [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C]),
+    (',', [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08]),
+    (':', [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00]),
+    ('/', [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00]),
+    ('\'', [0x0C, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00]),
+    ('(', [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02]),
+    (')', [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08]),
+    ('!', [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04]),
+    ('?', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob(cx: f32, cy: f32, n: usize) -> Vec<FieldPoint> {
+        (0..n)
+            .map(|i| {
+                let a = i as f32;
+                FieldPoint {
+                    xy: [cx + 0.1 * a.cos(), cy + 0.1 * a.sin()],
+                    weight: 1.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_draws_density_contours_and_labels() {
+        let left = blob(-2.0, 0.0, 12);
+        let right = blob(2.0, 0.0, 4);
+        let scene = Scene {
+            points: [left.clone(), right.clone()].concat(),
+            basins: vec![BasinMark {
+                id: "valley_1".to_string(),
+                type_: BasinType::Valley,
+                label: "memory safety".to_string(),
+                medoid: [-2.0, 0.0],
+                members: left,
+            }],
+        };
+        let config = HeatmapConfig {
+            labels: false,
+            ..Default::default()
+        };
+        let image = render(&scene, &config);
+        assert_eq!(image.pixels.len(), 512 * 512 * 3);
+
+        // Uniform scale: x spans the usable width, both blobs on the midline
+        let view = Viewport::fit(&scene, &config).unwrap();
+        let [lx, ly] = view.to_pixel([-2.0, 0.0]);
+        let [rx, _] = view.to_pixel([2.0, 0.0]);
+        assert!((lx - 512.0 * 0.08).abs() < 20.0 && (ly - 256.0).abs() < 1.0);
+
+        // Denser blob is brighter; empty corner stays background
+        let brightness = |p: [u8; 3]| p.iter().map(|&c| c as u32).sum::<u32>();
+        let right_blob = image.get(rx as u32, 256);
+        assert!(brightness(right_blob) > brightness(ramp(0.0)));
+        assert_eq!(image.get(0, 511), ramp(0.0));
+
+        // The valley is outlined somewhere left of the midline, not on the right
+        let outlined = |range: std::ops::Range<u32>| {
+            range
+                .into_iter()
+                .any(|x| (0..512).any(|y| image.get(x, y) == outline_colour(BasinType::Valley)))
+        };
+        assert!(outlined(0..256));
+        assert!(!outlined(256..512));
+        // Medoid marker
+        assert_eq!(image.get(lx.round() as u32 + 3, 256), [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_empty_scene_and_text() {
+        let image = render(&Scene::default(), &HeatmapConfig::default());
+        assert_eq!(image.get(300, 300), ramp(0.0));
+        assert_ne!(image.get(6, 6), ramp(0.0)); // "NO DATA"
+
+        assert_eq!(glyph('~'), glyph('?'));
+        assert_ne!(glyph('A'), glyph('?'));
+    }
+}
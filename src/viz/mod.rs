@@ -1,3 +1,8 @@
 // 2D visualization (oscilloscope only - no semantic decisions)
 
-// TODO M1.4: Implement PCA projection + heatmap renderer
+#[cfg(unix)]
+pub mod dashboard;
+pub mod frames;
+pub mod heatmap;
+pub mod png;
+pub mod projection;
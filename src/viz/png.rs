@@ -0,0 +1,468 @@
+// Minimal PNG / APNG writer (8-bit RGB, no dependencies)
+//
+// Image data is deflated into a single fixed-Huffman block: LZ77 matches
+// from a hash-chained 32K window, coded with the static tables of RFC 1951
+// so no code lengths need to be computed or sent. Heatmaps are mostly flat
+// background and smooth gradients, which this already shrinks several
+// times over. Animations are assembled from already-encoded frames by
+// re-wrapping their IDAT payloads as APNG fdAT chunks, so nothing is ever
+// decoded.
+
+use crate::ledger::segment::crc32;
+use std::io;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// LZ77 window and match limits of deflate
+const WINDOW: usize = 32_768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// Candidates tried per position (longer chains find little more in frames)
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+/// Smallest match length / distance of each deflate code, and its extra bits
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// 8-bit RGB raster, row-major
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>, // width × height × 3
+}
+
+impl Image {
+    /// Filled with one colour
+    pub fn new(width: u32, height: u32, fill: [u8; 3]) -> Self {
+        let pixels = fill
+            .iter()
+            .copied()
+            .cycle()
+            .take(width as usize * height as usize * 3)
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> [u8; 3] {
+        let i = (y as usize * self.width as usize + x as usize) * 3;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    /// Set a pixel; coordinates outside the image are ignored
+    pub fn put(&mut self, x: i32, y: i32, rgb: [u8; 3]) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 3;
+        self.pixels[i..i + 3].copy_from_slice(&rgb);
+    }
+
+    /// Encode as a PNG file
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut png = SIGNATURE.to_vec();
+        push_chunk(&mut png, b"IHDR", &ihdr(self.width, self.height));
+        push_chunk(&mut png, b"IDAT", &zlib(&self.scanlines()));
+        push_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    /// Rows prefixed with filter type 0 (None)
+    fn scanlines(&self) -> Vec<u8> {
+        let stride = self.width as usize * 3;
+        let mut raw = Vec::with_capacity((stride + 1) * self.height as usize);
+        for row in self.pixels.chunks(stride.max(1)) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+        raw
+    }
+}
+
+/// Join PNG frames of equal size into one looping APNG
+/// `delay_ms` is the display time of each frame; `plays` 0 loops forever
+pub fn assemble_apng(frames: &[Vec<u8>], delay_ms: u16, plays: u32) -> io::Result<Vec<u8>> {
+    let first = frames
+        .first()
+        .ok_or_else(|| invalid("no frames to animate".to_string()))?;
+    let header = chunk(first, b"IHDR")?;
+    let (width, height) = (&header[0..4], &header[4..8]);
+
+    let mut apng = SIGNATURE.to_vec();
+    push_chunk(&mut apng, b"IHDR", &header);
+    let mut actl = (frames.len() as u32).to_be_bytes().to_vec();
+    actl.extend_from_slice(&plays.to_be_bytes());
+    push_chunk(&mut apng, b"acTL", &actl);
+
+    let mut sequence = 0u32;
+    for (i, frame) in frames.iter().enumerate() {
+        if chunk(frame, b"IHDR")? != header {
+            return Err(invalid(format!("frame {} differs in size or format", i)));
+        }
+
+        let mut fctl = sequence.to_be_bytes().to_vec();
+        fctl.extend_from_slice(width);
+        fctl.extend_from_slice(height);
+        fctl.extend_from_slice(&[0; 8]); // x and y offset
+        fctl.extend_from_slice(&delay_ms.to_be_bytes());
+        fctl.extend_from_slice(&1000u16.to_be_bytes()); // delay in ms
+        fctl.extend_from_slice(&[0, 0]); // dispose none, blend source
+        push_chunk(&mut apng, b"fcTL", &fctl);
+        sequence += 1;
+
+        let data = chunks(frame)?
+            .into_iter()
+            .filter(|(kind, _)| kind == b"IDAT")
+            .flat_map(|(_, data)| data.to_vec())
+            .collect::<Vec<u8>>();
+        if i == 0 {
+            push_chunk(&mut apng, b"IDAT", &data);
+        } else {
+            let mut fdat = sequence.to_be_bytes().to_vec();
+            fdat.extend_from_slice(&data);
+            push_chunk(&mut apng, b"fdAT", &fdat);
+            sequence += 1;
+        }
+    }
+    push_chunk(&mut apng, b"IEND", &[]);
+    Ok(apng)
+}
+
+/// (type, data) of every chunk in a PNG file, CRCs checked
+pub fn chunks(png: &[u8]) -> io::Result<Vec<([u8; 4], &[u8])>> {
+    let mut rest = png
+        .strip_prefix(&SIGNATURE[..])
+        .ok_or_else(|| invalid("not a PNG file".to_string()))?;
+    let mut found = Vec::new();
+    while !rest.is_empty() {
+        if rest.len() < 12 {
+            return Err(invalid("truncated chunk".to_string()));
+        }
+        let len = u32::from_be_bytes(rest[0..4].try_into().unwrap()) as usize;
+        if rest.len() < 12 + len {
+            return Err(invalid("truncated chunk".to_string()));
+        }
+        let kind: [u8; 4] = rest[4..8].try_into().unwrap();
+        let data = &rest[8..8 + len];
+        let crc = u32::from_be_bytes(rest[8 + len..12 + len].try_into().unwrap());
+        if crc32(&rest[4..8 + len]) != crc {
+            return Err(invalid(format!(
+                "bad CRC in {} chunk",
+                String::from_utf8_lossy(&kind)
+            )));
+        }
+        found.push((kind, data));
+        rest = &rest[12 + len..];
+    }
+    Ok(found)
+}
+
+/// Data of the first chunk of `kind`
+fn chunk(png: &[u8], kind: &[u8; 4]) -> io::Result<Vec<u8>> {
+    chunks(png)?
+        .into_iter()
+        .find(|(k, _)| k == kind)
+        .map(|(_, data)| data.to_vec())
+        .ok_or_else(|| invalid(format!("missing {} chunk", String::from_utf8_lossy(kind))))
+}
+
+fn ihdr(width: u32, height: u32) -> Vec<u8> {
+    let mut data = width.to_be_bytes().to_vec();
+    data.extend_from_slice(&height.to_be_bytes());
+    data.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit RGB, deflate, no interlace
+    data
+}
+
+fn push_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// zlib stream holding one fixed-Huffman deflate block
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01]; // deflate, 32K window, no dictionary
+    out.extend_from_slice(&deflate_fixed(data));
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+/// LZ77 over a hash-chained window, coded with the fixed Huffman tables
+fn deflate_fixed(data: &[u8]) -> Vec<u8> {
+    let mut bits = BitWriter::default();
+    bits.put(1, 1); // BFINAL
+    bits.put(1, 2); // BTYPE = 01
+
+    let hash = |i: usize| {
+        let key = u32::from_le_bytes([data[i], data[i + 1], data[i + 2], 0]);
+        (key.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+    };
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; data.len()]; // previous position, same hash
+
+    let mut i = 0;
+    while i < data.len() {
+        let (mut best_len, mut best_dist) = (0, 0);
+        if i + MIN_MATCH <= data.len() {
+            let max = (data.len() - i).min(MAX_MATCH);
+            let mut candidate = head[hash(i)];
+            for _ in 0..MAX_CHAIN {
+                if candidate == usize::MAX || i - candidate > WINDOW {
+                    break;
+                }
+                let len = (0..max)
+                    .take_while(|&k| data[candidate + k] == data[i + k])
+                    .count();
+                if len > best_len {
+                    (best_len, best_dist) = (len, i - candidate);
+                    if len == max {
+                        break;
+                    }
+                }
+                candidate = prev[candidate];
+            }
+        }
+
+        let step = if best_len >= MIN_MATCH {
+            put_match(&mut bits, best_len, best_dist);
+            best_len
+        } else {
+            put_symbol(&mut bits, data[i] as u16);
+            1
+        };
+        let end = (i + step).min(data.len().saturating_sub(MIN_MATCH - 1));
+        for (j, link) in prev.iter_mut().enumerate().take(end).skip(i) {
+            let h = hash(j);
+            *link = head[h];
+            head[h] = j;
+        }
+        i += step;
+    }
+    put_symbol(&mut bits, 256); // end of block
+    bits.finish()
+}
+
+/// Literal/length symbol under the fixed code (RFC 1951 §3.2.6)
+fn put_symbol(bits: &mut BitWriter, symbol: u16) {
+    let (code, len) = match symbol {
+        0..=143 => (0x30 + symbol, 8),
+        144..=255 => (0x190 + symbol - 144, 9),
+        256..=279 => (symbol - 256, 7),
+        _ => (0xC0 + symbol - 280, 8),
+    };
+    bits.put_code(code, len);
+}
+
+fn put_match(bits: &mut BitWriter, len: usize, dist: usize) {
+    let (len, dist) = (len as u16, dist as u16);
+    let l = LENGTH_BASE.iter().rposition(|&b| b <= len).unwrap();
+    put_symbol(bits, 257 + l as u16);
+    bits.put((len - LENGTH_BASE[l]) as u32, LENGTH_EXTRA[l] as u32);
+    let d = DIST_BASE.iter().rposition(|&b| b <= dist).unwrap();
+    bits.put_code(d as u16, 5);
+    bits.put((dist - DIST_BASE[d]) as u32, DIST_EXTRA[d] as u32);
+}
+
+/// Deflate bit stream: values LSB first, Huffman codes MSB first
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn put(&mut self, value: u32, bits: u32) {
+        self.acc |= (value as u64) << self.count;
+        self.count += bits;
+        while self.count >= 8 {
+            self.out.push(self.acc as u8);
+            self.acc >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn put_code(&mut self, code: u16, len: u32) {
+        let reversed = (code.reverse_bits() >> (16 - len)) as u32;
+        self.put(reversed, len);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.acc as u8);
+        }
+        self.out
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= 65_521;
+        b %= 65_521;
+    }
+    (b << 16) | a
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bit reader for the inflater below
+    struct Bits<'a> {
+        data: &'a [u8],
+        pos: usize, // in bits
+    }
+
+    impl Bits<'_> {
+        fn bit(&mut self) -> u32 {
+            let bit = (self.data[self.pos / 8] >> (self.pos % 8)) & 1;
+            self.pos += 1;
+            bit as u32
+        }
+
+        fn value(&mut self, bits: u8) -> u32 {
+            (0..bits).fold(0, |v, i| v | self.bit() << i)
+        }
+
+        fn code(&mut self, len: u32) -> u32 {
+            (0..len).fold(0, |v, _| v << 1 | self.bit())
+        }
+    }
+
+    /// Inflate a zlib stream of fixed-Huffman blocks (enough to check ourselves)
+    fn inflate(zlib: &[u8]) -> Vec<u8> {
+        let mut bits = Bits {
+            data: &zlib[2..],
+            pos: 0,
+        };
+        let mut raw: Vec<u8> = Vec::new();
+        loop {
+            let last = bits.bit() == 1;
+            assert_eq!(bits.value(2), 1, "fixed Huffman block");
+            loop {
+                let mut code = bits.code(7);
+                let symbol = if code <= 0x17 {
+                    256 + code
+                } else {
+                    code = code << 1 | bits.bit();
+                    match code {
+                        0x30..=0xBF => code - 0x30,
+                        0xC0..=0xC7 => 280 + code - 0xC0,
+                        _ => 144 + (code << 1 | bits.bit()) - 0x190,
+                    }
+                };
+                match symbol {
+                    0..=255 => raw.push(symbol as u8),
+                    256 => break,
+                    _ => {
+                        let l = symbol as usize - 257;
+                        let len = LENGTH_BASE[l] as usize + bits.value(LENGTH_EXTRA[l]) as usize;
+                        let d = bits.code(5) as usize;
+                        let dist = DIST_BASE[d] as usize + bits.value(DIST_EXTRA[d]) as usize;
+                        for _ in 0..len {
+                            raw.push(raw[raw.len() - dist]);
+                        }
+                    }
+                }
+            }
+            if last {
+                break;
+            }
+        }
+        let end = 2 + bits.pos.div_ceil(8);
+        assert_eq!(zlib[end..], adler32(&raw).to_be_bytes());
+        raw
+    }
+
+    #[test]
+    fn test_deflate_roundtrip() {
+        // Literals above and below 144, overlapping and maximal matches
+        let mut data: Vec<u8> = (0..=255).collect();
+        data.extend(std::iter::repeat_n(7, 1000));
+        data.extend((0..5000u32).map(|i| (i * i % 251) as u8));
+        data.extend_from_within(100..900);
+        for input in [&[][..], &[42][..], b"abcabcabcabc", &data] {
+            assert_eq!(inflate(&zlib(input)), input);
+        }
+    }
+
+    #[test]
+    fn test_png_roundtrip() {
+        let mut image = Image::new(300, 250, [10, 20, 30]);
+        image.put(299, 249, [255, 0, 0]);
+        image.put(-1, 5, [255, 255, 255]); // ignored
+        let png = image.to_png();
+
+        let found = chunks(&png).unwrap();
+        let kinds: Vec<&[u8; 4]> = found.iter().map(|(k, _)| k).collect();
+        assert_eq!(kinds, vec![b"IHDR", b"IDAT", b"IEND"]);
+        assert_eq!(found[0].1, ihdr(300, 250).as_slice());
+
+        // 250 rows of filter byte + 900 bytes, mostly one colour
+        let raw = inflate(found[1].1);
+        assert_eq!(raw.len(), 250 * 901);
+        assert_eq!(&raw[..4], &[0, 10, 20, 30]);
+        assert_eq!(&raw[raw.len() - 3..], &[255, 0, 0]);
+        assert!(found[1].1.len() * 50 < raw.len(), "flat image compresses");
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn test_apng_assembly() {
+        let frames: Vec<Vec<u8>> = (0..3)
+            .map(|i| Image::new(4, 4, [i * 80, 0, 0]).to_png())
+            .collect();
+        let apng = assemble_apng(&frames, 250, 0).unwrap();
+
+        let found = chunks(&apng).unwrap();
+        let kinds: Vec<String> = found
+            .iter()
+            .map(|(k, _)| String::from_utf8_lossy(k).into_owned())
+            .collect();
+        assert_eq!(
+            kinds,
+            vec!["IHDR", "acTL", "fcTL", "IDAT", "fcTL", "fdAT", "fcTL", "fdAT", "IEND"]
+        );
+        assert_eq!(found[1].1, [0, 0, 0, 3, 0, 0, 0, 0]);
+        // Sequence numbers run across fcTL and fdAT: 0, 1, 2, 3, 4
+        let seq = |i: usize| u32::from_be_bytes(found[i].1[0..4].try_into().unwrap());
+        assert_eq!([seq(2), seq(4), seq(5), seq(6), seq(7)], [0, 1, 2, 3, 4]);
+        // Frame 2's pixels survive the rewrap
+        assert_eq!(inflate(&found[7].1[4..])[1], 160);
+
+        let odd = Image::new(5, 4, [0, 0, 0]).to_png();
+        assert!(assemble_apng(&[frames[0].clone(), odd], 250, 0).is_err());
+        assert!(assemble_apng(&[], 250, 0).is_err());
+    }
+}
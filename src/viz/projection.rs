@@ -0,0 +1,444 @@
+// PCA projection from N-D ledger state to 2-D (display only)
+//
+// The basis is fitted on an evenly strided ledger sample and then frozen so
+// the picture stays still between refits. A refit aligns the new basis to
+// the old one with orthogonal Procrustes on the new sample: the rotation or
+// reflection that best maps new coordinates onto old ones is folded into
+// the axes, so components that merely changed sign don't flip the display.
+
+use crate::ledger::store::Ledger;
+use crate::types::LedgerEntry;
+use nalgebra::{DMatrix, DVector, Matrix2};
+
+/// Power-iteration steps per principal component
+const MAX_ITERATIONS: usize = 200;
+
+/// When and on how much data the basis is fitted
+#[derive(Debug, Clone)]
+pub struct ProjectionConfig {
+    pub sample_size: usize, // max ledger entries used per fit
+    pub refit_ms: u64,      // refit at most this often...
+    pub refit_growth: f32,  // ...or as soon as the ledger grew by this factor
+}
+
+impl Default for ProjectionConfig {
+    fn default() -> Self {
+        Self {
+            sample_size: 512,
+            refit_ms: 60_000,
+            refit_growth: 2.0,
+        }
+    }
+}
+
+/// Frozen 2-D basis: coords = axes · (v - mean) + offset
+#[derive(Debug, Clone)]
+pub struct Projection {
+    pub mean: Vec<f32>,
+    pub axes: [Vec<f32>; 2], // orthonormal, in N-D
+    pub offset: [f32; 2],    // Procrustes translation (zero on a first fit)
+    pub explained: [f32; 2], // share of total variance along each axis
+}
+
+impl Projection {
+    /// Top two principal components of `vectors`
+    /// None with fewer than two vectors or mismatched dimensions
+    pub fn fit(vectors: &[&[f32]]) -> Option<Self> {
+        let n = vectors.len();
+        let dim = vectors.first()?.len();
+        if n < 2 || dim < 2 || vectors.iter().any(|v| v.len() != dim) {
+            return None;
+        }
+
+        let mut data = DMatrix::from_fn(n, dim, |i, j| vectors[i][j]);
+        let mean: Vec<f32> = data.column_iter().map(|c| c.mean()).collect();
+        for (j, mut column) in data.column_iter_mut().enumerate() {
+            column.add_scalar_mut(-mean[j]);
+        }
+        let total = data.norm_squared();
+
+        let first = principal_axis(&data, &[]);
+        let second = principal_axis(&data, &[&first]);
+        let variance = |axis: &DVector<f32>| (&data * axis).norm_squared();
+        let explained = if total > 0.0 {
+            [variance(&first) / total, variance(&second) / total]
+        } else {
+            [0.0, 0.0]
+        };
+
+        Some(Self {
+            mean,
+            axes: [
+                first.iter().copied().collect(),
+                second.iter().copied().collect(),
+            ],
+            offset: [0.0, 0.0],
+            explained,
+        })
+    }
+
+    /// 2-D coordinates of one N-D vector
+    pub fn project(&self, vector: &[f32]) -> [f32; 2] {
+        let along = |axis: &[f32]| -> f32 {
+            axis.iter()
+                .zip(vector.iter().zip(&self.mean))
+                .map(|(a, (v, m))| a * (v - m))
+                .sum()
+        };
+        [
+            along(&self.axes[0]) + self.offset[0],
+            along(&self.axes[1]) + self.offset[1],
+        ]
+    }
+
+    pub fn project_entry(&self, entry: &LedgerEntry) -> [f32; 2] {
+        self.project(&entry.vector)
+    }
+
+    /// Rotate/reflect and shift this basis so `anchors` land as close as
+    /// possible to where `previous` put them (orthogonal Procrustes)
+    pub fn aligned_to(mut self, previous: &Projection, anchors: &[&[f32]]) -> Self {
+        if anchors.is_empty() || previous.mean.len() != self.mean.len() {
+            return self;
+        }
+        let old: Vec<[f32; 2]> = anchors.iter().map(|v| previous.project(v)).collect();
+        let new: Vec<[f32; 2]> = anchors.iter().map(|v| self.project(v)).collect();
+        let centroid = |points: &[[f32; 2]]| {
+            let n = points.len() as f32;
+            let sum = points
+                .iter()
+                .fold([0.0, 0.0], |s, p| [s[0] + p[0], s[1] + p[1]]);
+            [sum[0] / n, sum[1] / n]
+        };
+        let (old_c, new_c) = (centroid(&old), centroid(&new));
+
+        // Cross-covariance of the centred coordinates: M = newᵀ · old
+        let mut m = Matrix2::<f32>::zeros();
+        for (p, q) in new.iter().zip(&old) {
+            for r in 0..2 {
+                for c in 0..2 {
+                    m[(r, c)] += (p[r] - new_c[r]) * (q[c] - old_c[c]);
+                }
+            }
+        }
+        let svd = m.svd(true, true);
+        let (Some(u), Some(v_t)) = (svd.u, svd.v_t) else {
+            return self;
+        };
+        let rotation = u * v_t; // new · R ≈ old
+
+        // Fold R into the axes: axis'_c = Σ_r R[r][c] · axis_r
+        let [a0, a1] = &self.axes;
+        let axes = [0, 1].map(|c| {
+            a0.iter()
+                .zip(a1)
+                .map(|(x, y)| rotation[(0, c)] * x + rotation[(1, c)] * y)
+                .collect::<Vec<f32>>()
+        });
+        let rotate =
+            |p: [f32; 2]| [0, 1].map(|c| p[0] * rotation[(0, c)] + p[1] * rotation[(1, c)]);
+        let shifted = rotate([new_c[0] - self.offset[0], new_c[1] - self.offset[1]]);
+
+        self.axes = axes;
+        self.offset = [old_c[0] - shifted[0], old_c[1] - shifted[1]];
+        self
+    }
+}
+
+/// Dominant direction of `data` orthogonal to `previous` (power iteration)
+/// Sign is fixed so the largest loading is positive
+fn principal_axis(data: &DMatrix<f32>, previous: &[&DVector<f32>]) -> DVector<f32> {
+    let dim = data.ncols();
+    let deflate = |mut v: DVector<f32>| {
+        for p in previous {
+            let along = v.dot(p);
+            v.axpy(-along, p, 1.0);
+        }
+        v
+    };
+
+    // Start from the sample with the most residual variance
+    let start = data
+        .row_iter()
+        .map(|r| deflate(r.transpose()))
+        .max_by(|a, b| a.norm_squared().total_cmp(&b.norm_squared()))
+        .filter(|v| v.norm() > 1e-6);
+    let mut axis = match start {
+        Some(v) => v.normalize(),
+        None => return fallback_axis(dim, previous),
+    };
+
+    for _ in 0..MAX_ITERATIONS {
+        let next = deflate(data.tr_mul(&(data * &axis)));
+        let norm = next.norm();
+        if norm <= 1e-12 {
+            return fallback_axis(dim, previous); // no variance left
+        }
+        let next = next / norm;
+        let converged = next.dot(&axis).abs() > 1.0 - 1e-7;
+        axis = next;
+        if converged {
+            break;
+        }
+    }
+
+    let largest = axis
+        .iter()
+        .copied()
+        .fold(0.0f32, |m, x| if x.abs() > m.abs() { x } else { m });
+    if largest < 0.0 {
+        axis.neg_mut();
+    }
+    axis
+}
+
+/// Unit vector orthogonal to `previous` when the data has no such direction
+fn fallback_axis(dim: usize, previous: &[&DVector<f32>]) -> DVector<f32> {
+    (0..dim)
+        .map(|j| {
+            let mut v = DVector::zeros(dim);
+            v[j] = 1.0;
+            for p in previous {
+                let along = v.dot(p);
+                v.axpy(-along, p, 1.0);
+            }
+            v
+        })
+        .max_by(|a, b| a.norm_squared().total_cmp(&b.norm_squared()))
+        .map(|v| v.normalize())
+        .unwrap_or_else(|| DVector::zeros(dim))
+}
+
+/// Evenly strided sample of at most `size` ledger entries
+pub fn sample(ledger: &Ledger, size: usize) -> Vec<&LedgerEntry> {
+    let entries = ledger.entries();
+    if size == 0 || entries.is_empty() {
+        return Vec::new();
+    }
+    let step = entries.len().div_ceil(size);
+    entries.iter().step_by(step).collect()
+}
+
+/// Sample and previous basis for one refit, detached from the ledger
+pub struct Refit {
+    vectors: Vec<Vec<f32>>,
+    previous: Option<Projection>,
+    at: u64,    // epoch ms the refit was prepared at
+    len: usize, // ledger length it was prepared at
+}
+
+impl Refit {
+    /// Fit and align the new basis (the costly part)
+    /// None when the sample cannot be fitted
+    pub fn run(self) -> Option<Fitted> {
+        let vectors: Vec<&[f32]> = self.vectors.iter().map(|v| v.as_slice()).collect();
+        let fitted = Projection::fit(&vectors)?;
+        let projection = match &self.previous {
+            Some(previous) => fitted.aligned_to(previous, &vectors),
+            None => fitted,
+        };
+        Some(Fitted {
+            projection,
+            at: self.at,
+            len: self.len,
+        })
+    }
+}
+
+/// Basis ready for `Projector::install`
+pub struct Fitted {
+    projection: Projection,
+    at: u64,
+    len: usize,
+}
+
+/// Keeps a frozen projection and refits it on schedule
+pub struct Projector {
+    config: ProjectionConfig,
+    projection: Option<Projection>,
+    fitted_at: Option<u64>, // epoch ms of the last fit
+    fitted_len: usize,      // ledger length at the last fit
+}
+
+impl Projector {
+    pub fn new() -> Self {
+        Self::with_config(ProjectionConfig::default())
+    }
+
+    pub fn with_config(config: ProjectionConfig) -> Self {
+        Self {
+            config,
+            projection: None,
+            fitted_at: None,
+            fitted_len: 0,
+        }
+    }
+
+    pub fn config(&self) -> &ProjectionConfig {
+        &self.config
+    }
+
+    /// Current basis (None until the ledger had two entries to fit on)
+    pub fn projection(&self) -> Option<&Projection> {
+        self.projection.as_ref()
+    }
+
+    /// Whether `refit` would fit now
+    pub fn is_due(&self, ledger: &Ledger, now: u64) -> bool {
+        let grown = ledger.len() as f32 >= self.fitted_len as f32 * self.config.refit_growth;
+        match self.fitted_at {
+            None => ledger.len() >= 2,
+            Some(at) => {
+                ledger.len() != self.fitted_len
+                    && (grown || now.saturating_sub(at) >= self.config.refit_ms)
+            }
+        }
+    }
+
+    /// Refit if due, aligned to the previous basis; returns whether it did
+    pub fn refit(&mut self, ledger: &Ledger, now: u64) -> bool {
+        match self.prepare(ledger, now).and_then(Refit::run) {
+            Some(fitted) => {
+                self.install(fitted);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Copy out what a due refit needs, so the fit itself can run without
+    /// holding the ledger (see `Refit::run` and `install`)
+    pub fn prepare(&self, ledger: &Ledger, now: u64) -> Option<Refit> {
+        if !self.is_due(ledger, now) {
+            return None;
+        }
+        let sample = sample(ledger, self.config.sample_size);
+        Some(Refit {
+            vectors: sample.iter().map(|e| e.vector.clone()).collect(),
+            previous: self.projection.clone(),
+            at: now,
+            len: ledger.len(),
+        })
+    }
+
+    /// Adopt a basis produced by `Refit::run`
+    pub fn install(&mut self, fitted: Fitted) {
+        self.projection = Some(fitted.projection);
+        self.fitted_at = Some(fitted.at);
+        self.fitted_len = fitted.len;
+    }
+
+    /// Coordinates under the current basis
+    pub fn project(&self, vector: &[f32]) -> Option<[f32; 2]> {
+        self.projection.as_ref().map(|p| p.project(vector))
+    }
+}
+
+impl Default for Projector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Points spread mostly along x, a little along y, none along z
+    fn plane() -> Vec<Vec<f32>> {
+        (0..20)
+            .map(|i| {
+                let t = i as f32 - 9.5;
+                vec![3.0 * t, (i % 4) as f32 - 1.5, 0.0]
+            })
+            .collect()
+    }
+
+    fn refs(vectors: &[Vec<f32>]) -> Vec<&[f32]> {
+        vectors.iter().map(|v| v.as_slice()).collect()
+    }
+
+    #[test]
+    fn test_fit_finds_principal_axes() {
+        let vectors = plane();
+        let projection = Projection::fit(&refs(&vectors)).unwrap();
+
+        let [x, y] = &projection.axes;
+        assert!((x[0] - 1.0).abs() < 1e-4 && x[2].abs() < 1e-4);
+        assert!((y[1].abs() - 1.0).abs() < 1e-3 && y[2].abs() < 1e-4);
+        assert!(projection.explained[0] > 0.95);
+        assert!(projection.explained[0] + projection.explained[1] > 0.999);
+
+        // Mean maps to the origin; x is preserved up to the mean shift
+        let p = projection.project(&vectors[0]);
+        assert!((p[0] - 3.0 * -9.5).abs() < 0.05);
+        assert!(Projection::fit(&refs(&vectors[..1])).is_none());
+    }
+
+    #[test]
+    fn test_procrustes_undoes_flips_and_rotation() {
+        let vectors = plane();
+        let anchors = refs(&vectors);
+        let original = Projection::fit(&anchors).unwrap();
+
+        // Same data, basis rotated 90° and mirrored, display moved
+        let mut flipped = original.clone();
+        flipped.axes = [original.axes[1].clone(), original.axes[0].clone()];
+        flipped.offset = [5.0, -2.0];
+
+        let aligned = flipped.aligned_to(&original, &anchors);
+        for v in &anchors {
+            let (a, b) = (original.project(v), aligned.project(v));
+            assert!((a[0] - b[0]).abs() < 1e-3 && (a[1] - b[1]).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_projector_freezes_between_refits() {
+        use crate::types::{ConceptPacket, Polarity, Tempo};
+
+        let mut ledger = Ledger::new();
+        let push = |ledger: &mut Ledger, i: usize, v: Vec<f32>| {
+            let packet = ConceptPacket {
+                phrase: format!("p{}", i),
+                amp: 0.5,
+                sigma: 1.0,
+                polarity: Polarity::Attract,
+                tempo: Tempo::Slow,
+                provenance: "test".to_string(),
+                agent_id: "agent1".to_string(),
+                rationale_hash: format!("h{}", i),
+                timestamp: 0,
+                center: None,
+                model: None,
+            };
+            ledger.append(packet, v).unwrap();
+        };
+        for (i, v) in plane().into_iter().enumerate() {
+            push(&mut ledger, i, v);
+        }
+
+        let mut projector = Projector::new();
+        assert!(projector.project(&[1.0, 0.0, 0.0]).is_none());
+        assert!(projector.refit(&ledger, 0));
+        let before = projector.project(&[1.0, 2.0, 0.0]).unwrap();
+
+        // A few new points: frozen until refit_ms passes
+        for i in 20..25 {
+            push(&mut ledger, i, vec![0.0, 0.0, 40.0]);
+        }
+        assert!(!projector.refit(&ledger, 1_000));
+        assert_eq!(projector.project(&[1.0, 2.0, 0.0]).unwrap(), before);
+
+        // The refit basis changes, but old points stay roughly put
+        assert!(projector.refit(&ledger, 60_000));
+        let after = projector.project(&[1.0, 2.0, 0.0]).unwrap();
+        assert!(
+            (after[0] - before[0]).abs() < 2.0,
+            "{:?} vs {:?}",
+            before,
+            after
+        );
+        assert!(!projector.refit(&ledger, 60_001)); // nothing new
+    }
+}
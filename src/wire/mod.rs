@@ -0,0 +1,308 @@
+// Binary wire format for ConceptPacket and BasinFeedback
+//
+// One message is [version][kind][MessagePack body], where the body is the
+// message's JSON form encoded as MessagePack (see msgpack.rs): same field
+// names, same shape, decodable by any MessagePack library. Streams carry
+// messages as frames prefixed with their u32 big-endian length.
+//
+//   version  VERSION; bumped when the body schema changes incompatibly
+//   kind     Message::KIND, so a stream can't mix up packets and feedback
+//
+// Bodies are maps keyed by field name rather than positional arrays. Keys
+// cost a few bytes per field, but a receiver needs no schema to read them,
+// optional fields stay absent instead of padding the array, and adding a
+// field is not a breaking change. Going through serde_json::Value keeps the
+// JSON derives the single source of truth for both encodings.
+//
+// `POST /packets` accepts a body of ConceptPacket frames under MEDIA_TYPE,
+// and the `tcp+msgpack:` sink streams BasinFeedback frames.
+
+pub mod msgpack;
+
+use crate::types::{BasinFeedback, ConceptPacket};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Schema version written into every message
+pub const VERSION: u8 = 1;
+
+/// Content type of a body made of frames
+pub const MEDIA_TYPE: &str = "application/msgpack";
+
+/// Largest frame accepted by `read_frame`
+pub const MAX_FRAME: usize = 16 * 1024 * 1024;
+
+/// Why a message could not be encoded or decoded
+#[derive(Debug)]
+pub enum WireError {
+    Io(io::Error),
+    Json(serde_json::Error),        // body does not fit the message type
+    Version(u8),                    // unsupported schema version
+    Kind { expected: u8, got: u8 }, // a different message type
+    Malformed(String),              // not valid framing or MessagePack
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WireError::Io(err) => write!(f, "{}", err),
+            WireError::Json(err) => write!(f, "bad message body: {}", err),
+            WireError::Version(v) => {
+                write!(f, "unsupported wire version {} (expected {})", v, VERSION)
+            }
+            WireError::Kind { expected, got } => {
+                write!(f, "expected message kind {}, got {}", expected, got)
+            }
+            WireError::Malformed(msg) => write!(f, "malformed message: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for WireError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WireError::Io(err) => Some(err),
+            WireError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for WireError {
+    fn from(err: io::Error) -> Self {
+        WireError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for WireError {
+    fn from(err: serde_json::Error) -> Self {
+        WireError::Json(err)
+    }
+}
+
+/// A type with a binary encoding
+pub trait Message: Serialize + DeserializeOwned {
+    /// Kind byte identifying the type on the wire
+    const KIND: u8;
+}
+
+impl Message for ConceptPacket {
+    const KIND: u8 = 1;
+}
+
+impl Message for BasinFeedback {
+    const KIND: u8 = 2;
+}
+
+/// Version, kind and MessagePack body of `message`
+pub fn encode<M: Message>(message: &M) -> Result<Vec<u8>, WireError> {
+    let mut out = vec![VERSION, M::KIND];
+    msgpack::encode(&serde_json::to_value(message)?, &mut out);
+    Ok(out)
+}
+
+/// Inverse of `encode`; checks version and kind first
+pub fn decode<M: Message>(bytes: &[u8]) -> Result<M, WireError> {
+    let [version, kind, body @ ..] = bytes else {
+        return Err(WireError::Malformed("missing header".to_string()));
+    };
+    if *version != VERSION {
+        return Err(WireError::Version(*version));
+    }
+    if *kind != M::KIND {
+        return Err(WireError::Kind {
+            expected: M::KIND,
+            got: *kind,
+        });
+    }
+    Ok(serde_json::from_value(msgpack::decode(body)?)?)
+}
+
+/// Write `message` as one length-prefixed frame
+pub fn write_frame<W: Write, M: Message>(writer: &mut W, message: &M) -> Result<(), WireError> {
+    let bytes = encode(message)?;
+    writer.write_all(&(bytes.len() as u32).to_be_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Read the next frame; None on a clean end of stream between frames
+pub fn read_frame<R: Read, M: Message>(reader: &mut R) -> Result<Option<M>, WireError> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len[..1]) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    reader.read_exact(&mut len[1..])?;
+
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME {
+        return Err(WireError::Malformed(format!("frame of {} bytes", len)));
+    }
+    let mut bytes = vec![0; len];
+    reader.read_exact(&mut bytes)?;
+    decode(&bytes).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feedback::precard;
+    use crate::types::{Action, BasinType, Polarity, Tempo};
+    use std::io::Cursor;
+
+    /// xorshift64: reproducible inputs without a property-testing crate
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        fn float(&mut self) -> f32 {
+            match self.below(4) {
+                0 => 0.0,
+                1 => f32::MIN_POSITIVE,
+                _ => {
+                    (self.next() as f32 / u64::MAX as f32 - 0.5)
+                        * 10f32.powi(self.below(12) as i32 - 6)
+                }
+            }
+        }
+
+        fn text(&mut self) -> String {
+            const PARTS: [&str; 8] = ["memory", "safety", " ", "é", "汉字", "\"q\"", "\n", "🦀"];
+            let len = [0, 3, 40, 300][self.below(4) as usize];
+            (0..len).map(|_| PARTS[self.below(8) as usize]).collect()
+        }
+    }
+
+    fn packet(rng: &mut Rng) -> ConceptPacket {
+        let len = rng.below(800) as usize;
+        let center = (rng.below(2) == 0).then(|| (0..len).map(|_| rng.float()).collect());
+        ConceptPacket {
+            phrase: rng.text(),
+            amp: rng.float(),
+            sigma: rng.float(),
+            polarity: [Polarity::Attract, Polarity::Repel][rng.below(2) as usize],
+            tempo: [Tempo::Fast, Tempo::Slow, Tempo::Urgent][rng.below(3) as usize],
+            provenance: rng.text(),
+            agent_id: rng.text(),
+            rationale_hash: rng.text(),
+            timestamp: rng.next() >> rng.below(64),
+            model: center.as_ref().map(|_| rng.text()),
+            center,
+        }
+    }
+
+    fn feedback(rng: &mut Rng) -> BasinFeedback {
+        let len = rng.below(800) as usize;
+        let rep_phrase = rng.text();
+        BasinFeedback {
+            basin_id: rng.text(),
+            type_: [BasinType::Valley, BasinType::Ridge, BasinType::Peak][rng.below(3) as usize],
+            coords_2d: [rng.float(), rng.float()],
+            rep_id: rng.text(),
+            contributors: (0..rng.below(20)).map(|_| rng.text()).collect(),
+            nd_cohesion: rng.float(),
+            nd_radius: rng.float(),
+            persistence: rng.next() as u32,
+            tempo: [Tempo::Fast, Tempo::Slow, Tempo::Urgent][rng.below(3) as usize],
+            dissent: rng.next() as u32,
+            centroid: (rng.below(2) == 0).then(|| (0..len).map(|_| rng.float()).collect()),
+            endpoints: (rng.below(2) == 0).then(|| vec![rng.text(), rng.text()]),
+            axis: None,
+            decompose_into: None,
+            recommended_action: Action::PlanSpike,
+            precard: (rng.below(2) == 0).then(|| precard::alert(&rep_phrase)),
+            thresholds: None,
+            timestamp: rng.next(),
+            rep_phrase,
+        }
+    }
+
+    /// Binary round trip agrees with the JSON round trip, field for field
+    fn check<M: Message>(message: &M) {
+        let json = serde_json::to_string(message).unwrap();
+        let from_json: M = serde_json::from_str(&json).unwrap();
+        let from_wire: M = decode(&encode(message).unwrap()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&from_wire).unwrap(),
+            serde_json::to_value(message).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&from_wire).unwrap(),
+            serde_json::to_value(&from_json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_matches_json() {
+        let mut rng = Rng(0x5EF1_2024);
+        for _ in 0..300 {
+            check(&packet(&mut rng));
+            check(&feedback(&mut rng));
+        }
+
+        // Smaller than JSON where it matters: feedback with a centroid
+        let mut basin = feedback(&mut rng);
+        basin.contributors.truncate(2);
+        basin.precard = None;
+        basin.centroid = Some((0..768).map(|i| (i as f32).sin()).collect());
+        let binary = encode(&basin).unwrap().len();
+        let json = serde_json::to_vec(&basin).unwrap().len();
+        assert!(binary * 2 < json, "{} vs {} bytes", binary, json);
+    }
+
+    #[test]
+    fn test_frames_and_header_checks() {
+        let mut rng = Rng(7);
+        let packets: Vec<ConceptPacket> = (0..5).map(|_| packet(&mut rng)).collect();
+        let mut stream = Vec::new();
+        for p in &packets {
+            write_frame(&mut stream, p).unwrap();
+        }
+
+        let mut reader = Cursor::new(&stream);
+        for p in &packets {
+            let got: ConceptPacket = read_frame(&mut reader).unwrap().unwrap();
+            assert_eq!(got.rationale_hash, p.rationale_hash);
+        }
+        assert!(read_frame::<_, ConceptPacket>(&mut reader)
+            .unwrap()
+            .is_none());
+
+        // A frame cut short is an error, not a clean end
+        let mut cut = Cursor::new(&stream[..stream.len() - 1]);
+        for _ in 0..4 {
+            read_frame::<_, ConceptPacket>(&mut cut).unwrap();
+        }
+        assert!(read_frame::<_, ConceptPacket>(&mut cut).is_err());
+
+        let mut bytes = encode(&packets[0]).unwrap();
+        assert!(matches!(
+            decode::<BasinFeedback>(&bytes),
+            Err(WireError::Kind {
+                expected: 2,
+                got: 1
+            })
+        ));
+        bytes[0] = 9;
+        assert!(matches!(
+            decode::<ConceptPacket>(&bytes),
+            Err(WireError::Version(9))
+        ));
+        let huge = (MAX_FRAME as u32 + 1).to_be_bytes();
+        assert!(read_frame::<_, ConceptPacket>(&mut Cursor::new(huge)).is_err());
+    }
+}
@@ -0,0 +1,296 @@
+// MessagePack encoding of JSON values (the subset JSON can express)
+//
+// Integers take the smallest MessagePack int that holds them; floats that
+// survive a round trip through f32 (every f32 field) go out as float 32,
+// others as float 64. Maps keep JSON's string keys, so any MessagePack
+// library decodes a body into the same shape as the JSON form.
+
+use super::WireError;
+use serde_json::{Map, Number, Value};
+
+/// Deepest array/map nesting accepted when decoding
+const MAX_DEPTH: usize = 64;
+
+/// Append `value` to `out`
+pub fn encode(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(b) => out.push(if *b { 0xc3 } else { 0xc2 }),
+        Value::Number(n) => encode_number(n, out),
+        Value::String(s) => {
+            let len = s.len();
+            if len < 32 {
+                out.push(0xa0 | len as u8);
+            } else if len <= u8::MAX as usize {
+                out.extend_from_slice(&[0xd9, len as u8]);
+            } else if len <= u16::MAX as usize {
+                out.push(0xda);
+                out.extend_from_slice(&(len as u16).to_be_bytes());
+            } else {
+                out.push(0xdb);
+                out.extend_from_slice(&(len as u32).to_be_bytes());
+            }
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(items) => {
+            header(items.len(), [0x90, 0xdc, 0xdd], 16, out);
+            for item in items {
+                encode(item, out);
+            }
+        }
+        Value::Object(map) => {
+            header(map.len(), [0x80, 0xde, 0xdf], 16, out);
+            for (key, item) in map {
+                encode(&Value::String(key.clone()), out);
+                encode(item, out);
+            }
+        }
+    }
+}
+
+/// Decode exactly one value spanning all of `bytes`
+pub fn decode(bytes: &[u8]) -> Result<Value, WireError> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.value(0)?;
+    if reader.pos != bytes.len() {
+        return Err(WireError::Malformed(format!(
+            "{} trailing bytes",
+            bytes.len() - reader.pos
+        )));
+    }
+    Ok(value)
+}
+
+fn encode_number(n: &Number, out: &mut Vec<u8>) {
+    if let Some(u) = n.as_u64() {
+        if u < 0x80 {
+            out.push(u as u8);
+        } else if u <= u8::MAX as u64 {
+            out.extend_from_slice(&[0xcc, u as u8]);
+        } else if u <= u16::MAX as u64 {
+            out.push(0xcd);
+            out.extend_from_slice(&(u as u16).to_be_bytes());
+        } else if u <= u32::MAX as u64 {
+            out.push(0xce);
+            out.extend_from_slice(&(u as u32).to_be_bytes());
+        } else {
+            out.push(0xcf);
+            out.extend_from_slice(&u.to_be_bytes());
+        }
+    } else if let Some(i) = n.as_i64() {
+        // Negative: as_u64 took everything else
+        if i >= -32 {
+            out.push(i as i8 as u8);
+        } else if i >= i8::MIN as i64 {
+            out.extend_from_slice(&[0xd0, i as i8 as u8]);
+        } else if i >= i16::MIN as i64 {
+            out.push(0xd1);
+            out.extend_from_slice(&(i as i16).to_be_bytes());
+        } else if i >= i32::MIN as i64 {
+            out.push(0xd2);
+            out.extend_from_slice(&(i as i32).to_be_bytes());
+        } else {
+            out.push(0xd3);
+            out.extend_from_slice(&i.to_be_bytes());
+        }
+    } else {
+        let f = n.as_f64().unwrap_or(0.0);
+        if (f as f32) as f64 == f {
+            out.push(0xca);
+            out.extend_from_slice(&(f as f32).to_be_bytes());
+        } else {
+            out.push(0xcb);
+            out.extend_from_slice(&f.to_be_bytes());
+        }
+    }
+}
+
+/// Array or map header: fix form below `fix_limit`, else 16/32-bit length
+fn header(len: usize, tags: [u8; 3], fix_limit: usize, out: &mut Vec<u8>) {
+    if len < fix_limit {
+        out.push(tags[0] | len as u8);
+    } else if len <= u16::MAX as usize {
+        out.push(tags[1]);
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.push(tags[2]);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], WireError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| WireError::Malformed("truncated value".to_string()))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], WireError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    /// 8/16/32-bit big-endian length
+    fn len(&mut self, width: usize) -> Result<usize, WireError> {
+        Ok(match width {
+            1 => self.array::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.array()?) as usize,
+            _ => u32::from_be_bytes(self.array()?) as usize,
+        })
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, WireError> {
+        if depth > MAX_DEPTH {
+            return Err(WireError::Malformed("nested too deeply".to_string()));
+        }
+        let tag = self.array::<1>()?[0];
+        Ok(match tag {
+            0x00..=0x7f => Value::from(tag),
+            0x80..=0x8f => self.map((tag & 0x0f) as usize, depth)?,
+            0x90..=0x9f => self.list((tag & 0x0f) as usize, depth)?,
+            0xa0..=0xbf => self.string((tag & 0x1f) as usize)?,
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xca => float(f32::from_be_bytes(self.array()?) as f64)?,
+            0xcb => float(f64::from_be_bytes(self.array()?))?,
+            0xcc => Value::from(self.array::<1>()?[0]),
+            0xcd => Value::from(u16::from_be_bytes(self.array()?)),
+            0xce => Value::from(u32::from_be_bytes(self.array()?)),
+            0xcf => Value::from(u64::from_be_bytes(self.array()?)),
+            0xd0 => Value::from(self.array::<1>()?[0] as i8),
+            0xd1 => Value::from(i16::from_be_bytes(self.array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.array()?)),
+            0xd9 => {
+                let len = self.len(1)?;
+                self.string(len)?
+            }
+            0xda => {
+                let len = self.len(2)?;
+                self.string(len)?
+            }
+            0xdb => {
+                let len = self.len(4)?;
+                self.string(len)?
+            }
+            0xdc => {
+                let len = self.len(2)?;
+                self.list(len, depth)?
+            }
+            0xdd => {
+                let len = self.len(4)?;
+                self.list(len, depth)?
+            }
+            0xde => {
+                let len = self.len(2)?;
+                self.map(len, depth)?
+            }
+            0xdf => {
+                let len = self.len(4)?;
+                self.map(len, depth)?
+            }
+            0xe0..=0xff => Value::from(tag as i8),
+            _ => {
+                return Err(WireError::Malformed(format!(
+                    "unsupported type 0x{:02x}",
+                    tag
+                )))
+            }
+        })
+    }
+
+    fn string(&mut self, len: usize) -> Result<Value, WireError> {
+        let bytes = self.take(len)?;
+        std::str::from_utf8(bytes)
+            .map(|s| Value::String(s.to_string()))
+            .map_err(|_| WireError::Malformed("string is not UTF-8".to_string()))
+    }
+
+    fn list(&mut self, len: usize, depth: usize) -> Result<Value, WireError> {
+        // Every item takes at least one byte: cap before allocating
+        let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+        for _ in 0..len {
+            items.push(self.value(depth + 1)?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn map(&mut self, len: usize, depth: usize) -> Result<Value, WireError> {
+        let mut map = Map::new();
+        for _ in 0..len {
+            let Value::String(key) = self.value(depth + 1)? else {
+                return Err(WireError::Malformed("map key is not a string".to_string()));
+            };
+            let item = self.value(depth + 1)?;
+            map.insert(key, item);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+fn float(f: f64) -> Result<Value, WireError> {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .ok_or_else(|| WireError::Malformed("NaN or infinite float".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_known_encodings() {
+        let cases = [
+            (json!(null), vec![0xc0]),
+            (json!(true), vec![0xc3]),
+            (json!(5), vec![0x05]),
+            (json!(200), vec![0xcc, 200]),
+            (json!(-1), vec![0xff]),
+            (json!(-100), vec![0xd0, 0x9c]),
+            (json!(1_700_000_000_000u64), {
+                let mut v = vec![0xcf];
+                v.extend_from_slice(&1_700_000_000_000u64.to_be_bytes());
+                v
+            }),
+            (json!(0.5), vec![0xca, 0x3f, 0x00, 0x00, 0x00]),
+            (json!("hi"), vec![0xa2, b'h', b'i']),
+            (json!([1, 2]), vec![0x92, 0x01, 0x02]),
+            (json!({"a": 1}), vec![0x81, 0xa1, b'a', 0x01]),
+        ];
+        for (value, bytes) in cases {
+            let mut out = Vec::new();
+            encode(&value, &mut out);
+            assert_eq!(out, bytes, "{}", value);
+            assert_eq!(decode(&bytes).unwrap(), value);
+        }
+
+        // 0.1 is not an f32: it keeps full precision
+        let mut out = Vec::new();
+        encode(&json!(0.1), &mut out);
+        assert_eq!(out[0], 0xcb);
+        assert_eq!(decode(&out).unwrap(), json!(0.1));
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        assert!(decode(&[]).is_err());
+        assert!(decode(&[0xa5, b'a']).is_err()); // truncated string
+        assert!(decode(&[0x01, 0x02]).is_err()); // trailing bytes
+        assert!(decode(&[0xc1]).is_err()); // never used
+        assert!(decode(&[0x81, 0x01, 0x01]).is_err()); // integer key
+        assert!(decode(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err()); // huge, empty array
+        assert!(decode(&[0x91; 100]).is_err()); // too deep
+        assert!(decode(&[0xa1, 0xff]).is_err()); // not UTF-8
+    }
+}